@@ -1,31 +1,57 @@
 use anyhow::bail;
-use libalembic::settings::{Account, SettingsManager};
-
-pub fn account_add(server: usize, username: String, password: String) -> anyhow::Result<()> {
-    let servers = SettingsManager::get(|s| s.servers.clone());
-
-    if server >= servers.len() {
-        bail!(
-            "Invalid server index {}. Run 'alembic server list' to see available servers.",
-            server
-        );
-    }
+use libalembic::{
+    credentials::{self, CredentialStoreKind},
+    settings::{Account, SettingsManager, new_id, short_id},
+};
+
+use super::{
+    removed_meanwhile,
+    server::resolve_server,
+    vault::{prompt_password, unlock_vault_for},
+};
+
+pub fn account_add(
+    server: String,
+    username: String,
+    password: Option<String>,
+    store: Option<String>,
+) -> anyhow::Result<()> {
+    let server_index = resolve_server(&server)?;
+    let server = SettingsManager::get(|s| s.servers[server_index].clone());
+    let store: CredentialStoreKind = match store {
+        Some(store) => store.parse()?,
+        None => CredentialStoreKind::default(),
+    };
+
+    let password = match password {
+        Some(password) => password,
+        None => prompt_password()?,
+    };
+    unlock_vault_for(store)?;
 
     println!("Adding account...");
 
     let had_no_accounts = SettingsManager::get(|s| s.accounts.is_empty());
 
+    // The password has to be stored before the account is saved so settings
+    // never reference a secret that doesn't exist
+    let id = new_id();
+    let credential = credentials::set_password(&id, store, &password)?;
+
     SettingsManager::modify(|settings| {
-        settings.accounts.push(Account {
-            server_index: server,
+        let account = Account {
+            id: id.clone(),
+            server_id: server.id.clone(),
             username: username.clone(),
-            password: password.clone(),
-        });
+            credential: credential.clone(),
+        };
 
         // Auto-select if this is the first account
         if had_no_accounts && settings.selected_account.is_none() {
-            settings.selected_account = Some(0);
+            settings.selected_account = Some(account.id.clone());
         }
+
+        settings.accounts.push(account);
     })?;
 
     if had_no_accounts {
@@ -34,29 +60,35 @@ pub fn account_add(server: usize, username: String, password: String) -> anyhow:
         println!("✓ Account added!");
     }
     println!();
-    println!("  Server:   {}", servers[server].name);
+    println!("  Server:   {}", server.name);
     println!("  Username: {}", username);
+    println!("  Password: stored in {}", store);
 
     Ok(())
 }
 
-pub fn account_list(server_filter: Option<usize>) -> anyhow::Result<()> {
+pub fn account_list(server_filter: Option<String>) -> anyhow::Result<()> {
     let accounts = SettingsManager::get(|s| s.accounts.clone());
     let servers = SettingsManager::get(|s| s.servers.clone());
-    let selected_account = SettingsManager::get(|s| s.selected_account);
+    let selected_account = SettingsManager::get(|s| s.selected_account.clone());
 
     if servers.is_empty() {
         println!("No servers configured. Use 'server add' to add a server first.");
         return Ok(());
     }
 
+    let server_filter = match server_filter {
+        Some(server) => Some(&servers[resolve_server(&server)?]),
+        None => None,
+    };
+
     // Filter accounts if server is specified
     let filtered_accounts: Vec<(usize, &Account)> = accounts
         .iter()
         .enumerate()
         .filter(|(_, account)| {
             if let Some(server) = server_filter {
-                account.server_index == server
+                account.server_id == server.id
             } else {
                 true
             }
@@ -65,15 +97,9 @@ pub fn account_list(server_filter: Option<usize>) -> anyhow::Result<()> {
 
     if filtered_accounts.is_empty() {
         if let Some(server) = server_filter {
-            if server >= servers.len() {
-                bail!(
-                    "Invalid server index {}. Run 'alembic server list' to see available servers.",
-                    server
-                );
-            }
             println!(
                 "No accounts configured for server '{}'. Use 'account add' to add an account.",
-                servers[server].name
+                server.name
             );
         } else {
             println!("No accounts configured. Use 'account add' to add an account.");
@@ -82,35 +108,46 @@ pub fn account_list(server_filter: Option<usize>) -> anyhow::Result<()> {
     }
 
     for (index, account) in &filtered_accounts {
-        let is_selected = Some(*index) == selected_account;
+        let is_selected = selected_account.as_deref() == Some(account.id.as_str());
         let marker = if is_selected { " * " } else { "   " };
 
-        let server_name = if account.server_index < servers.len() {
-            &servers[account.server_index].name
-        } else {
-            "<unknown>"
-        };
+        let server_name = servers
+            .iter()
+            .find(|s| s.id == account.server_id)
+            .map(|s| s.name.as_str())
+            .unwrap_or("<unknown>");
 
-        println!("{}{}: {}@{}", marker, index, account.username, server_name);
+        println!(
+            "{}{}: {}@{} ({}) [{}]",
+            marker,
+            index,
+            account.username,
+            server_name,
+            short_id(&account.id),
+            account.credential.kind()
+        );
     }
 
     Ok(())
 }
 
-pub fn account_select(index: usize) -> anyhow::Result<()> {
-    let accounts = SettingsManager::get(|s| s.accounts.clone());
-
-    if index >= accounts.len() {
-        bail!(
-            "Invalid account index: {}. Use 'alembic account list' to see available accounts.",
-            index
-        );
+/// Resolve an account given as an index, ID or username
+pub fn resolve_account(account: &str) -> anyhow::Result<usize> {
+    match SettingsManager::get(|s| s.find_account(account)) {
+        Some(index) => Ok(index),
+        None => bail!(
+            "No account matches '{}'. Run 'alembic account list' to see available accounts.",
+            account
+        ),
     }
+}
 
-    let username = accounts[index].username.clone();
+pub fn account_select(account: String) -> anyhow::Result<()> {
+    let index = resolve_account(&account)?;
+    let Account { id, username, .. } = SettingsManager::get(|s| s.accounts[index].clone());
 
     SettingsManager::modify(|settings| {
-        settings.selected_account = Some(index);
+        settings.selected_account = Some(id);
     })?;
 
     println!("✓ Selected account: {}", username);
@@ -134,80 +171,126 @@ pub fn account_reset() -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn account_remove(index: usize) -> anyhow::Result<()> {
-    let accounts = SettingsManager::get(|s| s.accounts.clone());
-
-    if index >= accounts.len() {
-        bail!(
-            "Invalid index {}. No account exists at that index. Run 'alembic account list' to see available accounts.",
-            index
-        );
-    }
-
-    let username = accounts[index].username.clone();
+pub fn account_remove(account: String) -> anyhow::Result<()> {
+    let index = resolve_account(&account)?;
+    let account = SettingsManager::get(|s| s.accounts[index].clone());
 
-    println!("Removing account '{}'...", username);
+    println!("Removing account '{}'...", account.username);
 
     SettingsManager::modify(|settings| {
-        settings.accounts.remove(index);
-
-        // Update selected_account if it was this one or needs adjustment
-        if let Some(selected) = settings.selected_account {
-            if selected == index {
-                settings.selected_account = None;
-            } else if selected > index {
-                settings.selected_account = Some(selected - 1);
-            }
+        if let Some(index) = settings.account_index(&account.id) {
+            settings.remove_account(index);
         }
     })?;
 
+    forget_passwords(&[account]);
+
     println!("✓ Account removed!");
 
     Ok(())
 }
 
 pub fn account_edit(
-    index: usize,
-    server: Option<usize>,
+    account: String,
+    server: Option<String>,
     username: Option<String>,
     password: Option<String>,
+    store: Option<String>,
 ) -> anyhow::Result<()> {
-    let accounts = SettingsManager::get(|s| s.accounts.clone());
-
-    if index >= accounts.len() {
-        bail!(
-            "Invalid index {}. No account exists at that index. Run 'alembic account list' to see available accounts.",
-            index
-        );
-    }
+    let index = resolve_account(&account)?;
 
-    if server.is_none() && username.is_none() && password.is_none() {
+    if server.is_none() && username.is_none() && password.is_none() && store.is_none() {
         println!(
-            "No changes specified. Use --server, --username, or --password to modify the account."
+            "No changes specified. Use --server, --username, --password, or --store to modify the account."
         );
         return Ok(());
     }
 
+    let server = match server {
+        Some(server) => {
+            let server_index = resolve_server(&server)?;
+            Some(SettingsManager::get(|s| s.servers[server_index].clone()))
+        }
+        None => None,
+    };
+
+    let existing = SettingsManager::get(|s| s.accounts[index].clone());
+    let store = match store {
+        Some(store) => Some(store.parse::<CredentialStoreKind>()?),
+        None => None,
+    };
+    let target_store = store.unwrap_or(existing.credential.kind());
+    let moving = target_store != existing.credential.kind();
+
+    if moving && password.is_none() {
+        unlock_vault_for(existing.credential.kind())?;
+    }
+    if moving || password.is_some() {
+        unlock_vault_for(target_store)?;
+    }
+
+    // Write the new secret before touching settings, and only clean up the old
+    // copy once settings point at the new one
+    let credential = match password {
+        Some(password) => Some(credentials::set_password(
+            &existing.id,
+            target_store,
+            &password,
+        )?),
+        None if moving => Some(credentials::set_password(
+            &existing.id,
+            target_store,
+            &credentials::get_password(&existing)?,
+        )?),
+        None => None,
+    };
+
     println!("Editing account at index {}...", index);
 
+    let mut found = false;
     SettingsManager::modify(|settings| {
+        let Some(index) = settings.account_index(&existing.id) else {
+            return;
+        };
+        found = true;
         let account = &mut settings.accounts[index];
 
         if let Some(s) = server {
-            account.server_index = s;
-            println!("  Updated server index to: {}", s);
+            account.server_id = s.id;
+            println!("  Updated server to: {}", s.name);
         }
         if let Some(u) = username {
             println!("  Updated username to: {}", u);
             account.username = u;
         }
-        if let Some(p) = password {
-            println!("  Updated password");
-            account.password = p;
+        if let Some(credential) = &credential {
+            println!("  Updated password (stored in {})", credential.kind());
+            account.credential = credential.clone();
         }
     })?;
 
+    if !found {
+        return Err(removed_meanwhile("account", &account));
+    }
+
+    if moving {
+        forget_passwords(&[existing]);
+    }
+
     println!("✓ Account updated!");
 
     Ok(())
 }
+
+/// Remove the stored passwords of accounts that no longer exist. Failures only
+/// leave an orphaned secret behind, so they are reported but not fatal.
+pub fn forget_passwords(accounts: &[Account]) {
+    for account in accounts {
+        if let Err(e) = credentials::delete_password(account) {
+            eprintln!(
+                "Warning: Couldn't remove the stored password for '{}': {:#}",
+                account.username, e
+            );
+        }
+    }
+}
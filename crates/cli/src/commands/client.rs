@@ -1,9 +1,11 @@
 use anyhow::bail;
-use libalembic::settings::SettingsManager;
+use libalembic::settings::{SettingsManager, new_id, short_id};
+
+use super::removed_meanwhile;
 
 pub fn client_list() -> anyhow::Result<()> {
     let clients = SettingsManager::get(|s| s.clients.clone());
-    let selected_client = SettingsManager::get(|s| s.selected_client);
+    let selected_client = SettingsManager::get(|s| s.selected_client.clone());
 
     if clients.is_empty() {
         println!("No clients configured.");
@@ -12,25 +14,39 @@ pub fn client_list() -> anyhow::Result<()> {
     }
 
     for (idx, config) in clients.iter().enumerate() {
-        let is_selected = Some(idx) == selected_client;
+        let is_selected = selected_client.as_deref() == Some(config.id());
         let marker = if is_selected { " * " } else { "   " };
         let client_type = if config.is_wine() { "Wine" } else { "Windows" };
-        println!("{}{}: {} ({})", marker, idx, config.name(), client_type);
+        println!(
+            "{}{}: {} ({}, {})",
+            marker,
+            idx,
+            config.name(),
+            client_type,
+            short_id(config.id())
+        );
     }
 
     Ok(())
 }
 
-pub fn client_show(index: usize) -> anyhow::Result<()> {
-    let client_config =
-        SettingsManager::get(|s| s.clients.get(index).cloned()).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Invalid client index: {}. Use 'alembic client list' to see available clients.",
-                index
-            )
-        })?;
+/// Resolve a client given as an index, ID or name
+pub fn resolve_client(client: &str) -> anyhow::Result<usize> {
+    match SettingsManager::get(|s| s.find_client(client)) {
+        Some(index) => Ok(index),
+        None => bail!(
+            "No client matches '{}'. Use 'alembic client list' to see available clients.",
+            client
+        ),
+    }
+}
+
+pub fn client_show(client: String) -> anyhow::Result<()> {
+    let index = resolve_client(&client)?;
+    let client_config = SettingsManager::get(|s| s.clients[index].clone());
 
     println!("Client configuration (index {}):", index);
+    println!("ID: {}", client_config.id());
     println!();
     println!("{}", client_config);
 
@@ -58,7 +74,7 @@ pub fn client_show(index: usize) -> anyhow::Result<()> {
 
 #[allow(clippy::too_many_arguments)]
 pub fn client_edit(
-    index: usize,
+    client: String,
     name: Option<String>,
     client_path: Option<String>,
     wrapper_program: Option<String>,
@@ -70,13 +86,7 @@ pub fn client_edit(
     use libalembic::settings::ClientConfigType;
     use std::path::PathBuf;
 
-    let client_exists = SettingsManager::get(|s| s.clients.get(index).is_some());
-    if !client_exists {
-        bail!(
-            "Invalid client index: {}. Use 'alembic client list' to see available clients.",
-            index
-        );
-    }
+    let index = resolve_client(&client)?;
 
     if name.is_none()
         && client_path.is_none()
@@ -94,7 +104,13 @@ pub fn client_edit(
 
     println!("Editing client at index {}...", index);
 
+    let id = SettingsManager::get(|s| s.clients[index].id().to_string());
+    let mut found = false;
     SettingsManager::modify(|settings| {
+        let Some(index) = settings.client_index(&id) else {
+            return;
+        };
+        found = true;
         let client = &mut settings.clients[index];
 
         match client {
@@ -140,8 +156,20 @@ pub fn client_edit(
                 }
             }
         }
+
+        // A different executable is probably a different build
+        if client_path.is_some() {
+            match client.refresh_fingerprint() {
+                Ok(fingerprint) => println!("  Updated client build to: {}", fingerprint),
+                Err(e) => println!("  Couldn't read the client build: {:#}", e),
+            }
+        }
     })?;
 
+    if !found {
+        return Err(removed_meanwhile("client", &client));
+    }
+
     println!("✓ Client updated!");
 
     Ok(())
@@ -160,12 +188,14 @@ pub fn client_add(
 
     println!("Adding client configuration...");
 
-    let client_config = match mode.to_lowercase().as_str() {
+    let mut client_config = match mode.to_lowercase().as_str() {
         "windows" => ClientConfigType::Windows(WindowsClientConfig {
+            id: new_id(),
             name: "Manual Windows client".to_string(),
             client_path: PathBuf::from(&client_path),
             dlls: vec![],
             selected_dll: None,
+            fingerprint: None,
         }),
         "wine" => {
             let prefix =
@@ -178,11 +208,13 @@ pub fn client_add(
             }
 
             ClientConfigType::Wine(WineClientConfig {
+                id: new_id(),
                 name: "Manual Wine client".to_string(),
                 client_path: PathBuf::from(&client_path),
                 launch_command,
                 dlls: vec![],
                 selected_dll: None,
+                fingerprint: None,
             })
         }
         _ => bail!(
@@ -191,6 +223,11 @@ pub fn client_add(
         ),
     };
 
+    match client_config.refresh_fingerprint() {
+        Ok(fingerprint) => println!("Client build: {}", fingerprint),
+        Err(e) => println!("Couldn't read the client build: {:#}", e),
+    }
+
     let new_index = SettingsManager::get(|s| s.clients.len());
 
     SettingsManager::modify(|settings| {
@@ -202,23 +239,18 @@ pub fn client_add(
     println!();
 
     // Show what was configured
-    client_show(new_index)
+    client_show(new_index.to_string())
 }
 
-pub fn client_select(index: usize) -> anyhow::Result<()> {
-    let clients = SettingsManager::get(|s| s.clients.clone());
-
-    if index >= clients.len() {
-        bail!(
-            "Invalid client index: {}. Use 'alembic client list' to see available clients.",
-            index
-        );
-    }
-
-    let client_name = clients[index].name().to_string();
+pub fn client_select(client: String) -> anyhow::Result<()> {
+    let index = resolve_client(&client)?;
+    let (client_id, client_name) = SettingsManager::get(|s| {
+        let client = &s.clients[index];
+        (client.id().to_string(), client.name().to_string())
+    });
 
     SettingsManager::modify(|settings| {
-        settings.selected_client = Some(index);
+        settings.selected_client = Some(client_id);
     })?;
 
     println!("✓ Selected client: {}", client_name);
@@ -242,23 +274,19 @@ pub fn client_reset() -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn client_remove(index: usize) -> anyhow::Result<()> {
-    let removed = SettingsManager::get(|s| {
-        if index < s.clients.len() {
-            Some(s.clients[index].name().to_string())
-        } else {
-            None
-        }
-    })
-    .ok_or_else(|| {
-        anyhow::anyhow!(
-            "Invalid client index: {}. Use 'alembic client list' to see available clients.",
-            index
+pub fn client_remove(client: String) -> anyhow::Result<()> {
+    let index = resolve_client(&client)?;
+    let (id, removed) = SettingsManager::get(|s| {
+        (
+            s.clients[index].id().to_string(),
+            s.clients[index].name().to_string(),
         )
-    })?;
+    });
 
     SettingsManager::modify(|settings| {
-        settings.remove_client(index);
+        if let Some(index) = settings.client_index(&id) {
+            settings.remove_client(index);
+        }
         settings.is_configured = !settings.clients.is_empty();
     })?;
 
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use anyhow::bail;
+use libalembic::{
+    pe::PeFile,
+    sigscan::{Pattern, Resolve, Signature},
+};
+
+pub fn sigscan(
+    exe: PathBuf,
+    pattern: String,
+    section: Option<String>,
+    rel32: Option<usize>,
+    instruction_end: Option<usize>,
+    abs32: Option<usize>,
+) -> anyhow::Result<()> {
+    let pe = PeFile::load(&exe)?;
+    let mut signature = Signature::new(Pattern::parse(&pattern)?);
+    if let Some(offset) = rel32 {
+        signature = signature.resolve(Resolve::Relative {
+            offset,
+            instruction_end: instruction_end.unwrap_or(offset + 4),
+        });
+    } else if let Some(offset) = abs32 {
+        signature = signature.resolve(Resolve::Absolute { offset });
+    }
+
+    println!(
+        "Scanning {} (image base 0x{:08X}) for {}",
+        exe.display(),
+        pe.image_base(),
+        signature.pattern
+    );
+
+    let matches = signature.scan_pe(&pe, section.as_deref())?;
+    for m in &matches {
+        print!(
+            "  0x{:08X} (file offset 0x{:X}, {})",
+            m.address,
+            m.offset,
+            m.section.as_deref().unwrap_or_default()
+        );
+        match (signature.resolve, m.target) {
+            (Some(_), Some(target)) => println!(" -> 0x{:08X}", target),
+            (Some(_), None) => println!(" -> past the end of the section"),
+            (None, _) => println!(),
+        }
+    }
+
+    println!();
+    match matches.len() {
+        0 => bail!("No matches"),
+        1 => println!("Found 1 match"),
+        count => println!(
+            "Found {} matches. Add bytes to the pattern if it needs to find just one.",
+            count
+        ),
+    }
+
+    Ok(())
+}
@@ -1,7 +1,91 @@
+use std::path::PathBuf;
+
 use anyhow::bail;
-use libalembic::{scanner, settings::SettingsManager};
+use libalembic::{
+    decal::DecalRegistry,
+    dll_inspect::DllInspection,
+    inject_config::InjectConfig,
+    scanner,
+    settings::{ClientConfigType, SettingsManager, new_id, short_id},
+};
+
+use super::{client::resolve_client, removed_meanwhile};
+
+/// Resolve a DLL of a client given as an index, ID or path
+pub fn resolve_dll(client_idx: usize, dll: &str) -> anyhow::Result<usize> {
+    match SettingsManager::get(|s| s.clients[client_idx].find_dll(dll)) {
+        Some(index) => Ok(index),
+        None => bail!(
+            "No DLL matches '{}'. Use 'alembic config client dll --client {} list' to see available DLLs.",
+            dll,
+            client_idx
+        ),
+    }
+}
+
+/// IDs of a client and one of its DLLs, to find them again inside
+/// `SettingsManager::modify`, which reloads config.json
+fn dll_ids(client_idx: usize, dll_idx: usize) -> (String, String) {
+    SettingsManager::get(|s| {
+        let client = &s.clients[client_idx];
+        (client.id().to_string(), client.dlls()[dll_idx].id.clone())
+    })
+}
+
+/// Number of exports or imports listed before the rest are counted instead
+const MAX_LISTED_NAMES: usize = 10;
+
+/// List names, or say how many more there are past `MAX_LISTED_NAMES`
+fn list_names(names: &[String]) -> String {
+    if names.is_empty() {
+        return "none".to_string();
+    }
+
+    let mut list = names[..names.len().min(MAX_LISTED_NAMES)].join(", ");
+    if names.len() > MAX_LISTED_NAMES {
+        list.push_str(&format!(" and {} more", names.len() - MAX_LISTED_NAMES));
+    }
+    list
+}
+
+/// Print what a DLL is built for, exports and imports, and whether that
+/// matches its configuration
+fn print_inspection(client: &ClientConfigType, dll: &InjectConfig) {
+    let inspection = match DllInspection::for_client(client, dll) {
+        Ok(inspection) => inspection,
+        Err(e) => {
+            println!("  Couldn't inspect the DLL: {:#}", e);
+            return;
+        }
+    };
 
-pub fn client_dll_list(client_idx: usize) -> anyhow::Result<()> {
+    println!("  Architecture: {}", inspection.machine);
+    if !inspection.is_x86() {
+        println!("  Warning: acclient.exe is 32-bit x86, so this DLL can't be injected");
+    }
+
+    println!("  Exports: {}", list_names(&inspection.exports));
+    if let Some(function) = &dll.startup_function
+        && !inspection.exports_function(function)
+    {
+        println!(
+            "  Warning: the startup function {} isn't exported, so the launch will fail",
+            function
+        );
+    }
+
+    println!("  Imports: {}", list_names(&inspection.imports));
+    match &inspection.missing_imports {
+        Some(missing) if !missing.is_empty() => {
+            println!("  Warning: imported DLLs not found: {}", missing.join(", "))
+        }
+        Some(_) => {}
+        None => println!("  Couldn't find the system directory to check the imports"),
+    }
+}
+
+pub fn client_dll_list(client: String) -> anyhow::Result<()> {
+    let client_idx = resolve_client(&client)?;
     let dlls = SettingsManager::get(|s| s.get_client_dlls(client_idx).cloned());
     let selected_dll =
         SettingsManager::get(|s| s.clients.get(client_idx).and_then(|c| c.selected_dll()));
@@ -22,11 +106,12 @@ pub fn client_dll_list(client_idx: usize) -> anyhow::Result<()> {
                 let marker = if is_selected { " * " } else { "   " };
 
                 println!(
-                    "{}{}: {} ({})",
+                    "{}{}: {} ({}, {})",
                     marker,
                     idx,
                     dll.dll_path.display(),
-                    dll.dll_type
+                    dll.dll_type,
+                    short_id(&dll.id)
                 );
             }
 
@@ -42,22 +127,14 @@ pub fn client_dll_list(client_idx: usize) -> anyhow::Result<()> {
 }
 
 pub fn client_dll_add(
-    client_idx: usize,
+    client: String,
     dll_type: String,
     dll_path: String,
     startup_function: Option<String>,
 ) -> anyhow::Result<()> {
-    use libalembic::inject_config::{DllType, InjectConfig};
-    use std::path::PathBuf;
+    use libalembic::inject_config::DllType;
 
-    // Validate client exists
-    let _client =
-        SettingsManager::get(|s| s.clients.get(client_idx).cloned()).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Invalid client index: {}. Use 'alembic client list' to see available clients.",
-                client_idx
-            )
-        })?;
+    let client_idx = resolve_client(&client)?;
 
     // Parse DLL type
     let dll_type_enum = match dll_type.to_lowercase().as_str() {
@@ -79,6 +156,7 @@ pub fn client_dll_add(
 
     // Create the InjectConfig
     let inject_config = InjectConfig {
+        id: new_id(),
         dll_type: dll_type_enum,
         dll_path: PathBuf::from(&dll_path),
         startup_function: startup_fn,
@@ -88,42 +166,41 @@ pub fn client_dll_add(
     println!("  Type: {}", inject_config.dll_type);
     println!("  Path: {}", inject_config.dll_path.display());
 
+    let client = SettingsManager::get(|s| s.clients[client_idx].clone());
+    print_inspection(&client, &inject_config);
+
+    let mut found = false;
     SettingsManager::modify(|settings| {
-        settings.add_dll_to_client(client_idx, inject_config);
+        if let Some(client_idx) = settings.client_index(client.id()) {
+            found = true;
+            settings.add_dll_to_client(client_idx, inject_config);
+        }
     })?;
 
+    if !found {
+        return Err(removed_meanwhile("client", client.id()));
+    }
+
     println!();
     println!("✓ DLL configuration added!");
 
     Ok(())
 }
 
-pub fn client_dll_select(client_idx: usize, dll_idx: usize) -> anyhow::Result<()> {
-    let dlls = SettingsManager::get(|s| s.get_client_dlls(client_idx).cloned());
-
-    let dll_count = match dlls {
-        Some(dlls) => dlls.len(),
-        None => {
-            bail!(
-                "Invalid client index: {}. Use 'alembic client list' to see available clients.",
-                client_idx
-            )
-        }
-    };
-
-    if dll_idx >= dll_count {
-        println!("Invalid DLL index: {}", dll_idx);
-        println!(
-            "Use 'alembic config client dll --client {} list' to see available DLLs.",
-            client_idx
-        );
-        return Ok(());
-    }
+pub fn client_dll_select(client: String, dll: String) -> anyhow::Result<()> {
+    let client_idx = resolve_client(&client)?;
+    let dll_idx = resolve_dll(client_idx, &dll)?;
+    let (client_id, dll_id) = dll_ids(client_idx, dll_idx);
 
+    let mut found = false;
     SettingsManager::modify(|settings| {
-        settings.select_dll_for_client(client_idx, Some(dll_idx));
+        found = settings.select_client_dll(&client_id, Some(&dll_id));
     })?;
 
+    if !found {
+        return Err(removed_meanwhile("DLL", &dll));
+    }
+
     println!(
         "✓ Selected DLL at index {} for client {}",
         dll_idx, client_idx
@@ -132,12 +209,15 @@ pub fn client_dll_select(client_idx: usize, dll_idx: usize) -> anyhow::Result<()
     Ok(())
 }
 
-pub fn client_dll_reset(client_idx: usize) -> anyhow::Result<()> {
-    let was_selected =
-        SettingsManager::get(|s| s.clients.get(client_idx).and_then(|c| c.selected_dll()));
+pub fn client_dll_reset(client: String) -> anyhow::Result<()> {
+    let client_idx = resolve_client(&client)?;
+    let (client_id, was_selected) = SettingsManager::get(|s| {
+        let client = &s.clients[client_idx];
+        (client.id().to_string(), client.selected_dll())
+    });
 
     SettingsManager::modify(|settings| {
-        settings.select_dll_for_client(client_idx, None);
+        settings.select_client_dll(&client_id, None);
     })?;
 
     if was_selected.is_some() {
@@ -149,27 +229,16 @@ pub fn client_dll_reset(client_idx: usize) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn client_dll_remove(client_idx: usize, dll_idx: usize) -> anyhow::Result<()> {
+pub fn client_dll_remove(client: String, dll: String) -> anyhow::Result<()> {
     use std::io::{self, Write};
 
-    let dll_info = SettingsManager::get(|s| {
-        s.get_client_dlls(client_idx).and_then(|dlls| {
-            dlls.get(dll_idx)
-                .map(|dll| (dll.dll_type.to_string(), dll.dll_path.display().to_string()))
-        })
-    });
+    let client_idx = resolve_client(&client)?;
+    let dll_idx = resolve_dll(client_idx, &dll)?;
 
-    let (dll_type, dll_path) = match dll_info {
-        Some(info) => info,
-        None => {
-            println!("Invalid DLL index: {}", dll_idx);
-            println!(
-                "Use 'alembic config client dll --client {} list' to see available DLLs.",
-                client_idx
-            );
-            return Ok(());
-        }
-    };
+    let (dll_type, dll_path) = SettingsManager::get(|s| {
+        let dll = &s.clients[client_idx].dlls()[dll_idx];
+        (dll.dll_type.to_string(), dll.dll_path.display().to_string())
+    });
 
     println!("This will remove the following DLL configuration:");
     println!("  [{}] {} - {}", dll_idx, dll_type, dll_path);
@@ -186,8 +255,13 @@ pub fn client_dll_remove(client_idx: usize, dll_idx: usize) -> anyhow::Result<()
         return Ok(());
     }
 
+    let (client_id, dll_id) = dll_ids(client_idx, dll_idx);
     SettingsManager::modify(|settings| {
-        settings.remove_dll_from_client(client_idx, dll_idx);
+        if let Some(client_idx) = settings.client_index(&client_id)
+            && let Some(dll_idx) = settings.clients[client_idx].dll_index(&dll_id)
+        {
+            settings.remove_dll_from_client(client_idx, dll_idx);
+        }
     })?;
 
     println!();
@@ -199,55 +273,37 @@ pub fn client_dll_remove(client_idx: usize, dll_idx: usize) -> anyhow::Result<()
     Ok(())
 }
 
-pub fn client_dll_show(client_idx: usize, dll_idx: usize) -> anyhow::Result<()> {
-    let dll = SettingsManager::get(|s| {
-        s.get_client_dlls(client_idx)
-            .and_then(|dlls| dlls.get(dll_idx).cloned())
-    });
+pub fn client_dll_show(client: String, dll: String) -> anyhow::Result<()> {
+    let client_idx = resolve_client(&client)?;
+    let dll_idx = resolve_dll(client_idx, &dll)?;
+    let client = SettingsManager::get(|s| s.clients[client_idx].clone());
+    let dll = &client.dlls()[dll_idx];
 
-    match dll {
-        Some(dll) => {
-            println!(
-                "DLL configuration (client {}, index {}):",
-                client_idx, dll_idx
-            );
-            println!();
-            println!("{}", dll);
-        }
-        None => {
-            println!("Invalid client or DLL index.");
-            println!(
-                "Use 'alembic config client dll --client {} list' to see available DLLs.",
-                client_idx
-            );
-        }
-    }
+    println!(
+        "DLL configuration (client {}, index {}):",
+        client_idx, dll_idx
+    );
+    println!("ID: {}", dll.id);
+    println!();
+    println!("{}", dll);
+    println!();
+    println!("Inspection:");
+    print_inspection(&client, dll);
 
     Ok(())
 }
 
 pub fn client_dll_edit(
-    client_idx: usize,
-    dll_idx: usize,
+    client: String,
+    dll: String,
     dll_type: Option<String>,
     path: Option<String>,
     startup_function: Option<String>,
 ) -> anyhow::Result<()> {
     use libalembic::inject_config::DllType;
-    use std::path::PathBuf;
 
-    let dll_exists = SettingsManager::get(|s| {
-        s.get_client_dlls(client_idx)
-            .map(|dlls| dll_idx < dlls.len())
-            .unwrap_or(false)
-    });
-
-    if !dll_exists {
-        bail!(
-            "Invalid client or DLL index. Use 'alembic config client dll --client {} list' to see available DLLs.",
-            client_idx
-        );
-    }
+    let client_idx = resolve_client(&client)?;
+    let dll_idx = resolve_dll(client_idx, &dll)?;
 
     if dll_type.is_none() && path.is_none() && startup_function.is_none() {
         println!(
@@ -261,10 +317,15 @@ pub fn client_dll_edit(
         dll_idx, client_idx
     );
 
+    let (client_id, dll_id) = dll_ids(client_idx, dll_idx);
+    let mut found = false;
     SettingsManager::modify(|settings| {
-        if let Some(dlls) = settings.get_client_dlls_mut(client_idx)
+        if let Some(client_idx) = settings.client_index(&client_id)
+            && let Some(dll_idx) = settings.clients[client_idx].dll_index(&dll_id)
+            && let Some(dlls) = settings.get_client_dlls_mut(client_idx)
             && let Some(dll) = dlls.get_mut(dll_idx)
         {
+            found = true;
             if let Some(t) = &dll_type {
                 match t.to_lowercase().as_str() {
                     "alembic" => {
@@ -299,34 +360,32 @@ pub fn client_dll_edit(
         }
     })?;
 
+    if !found {
+        return Err(removed_meanwhile("DLL", &dll));
+    }
+
     println!("✓ DLL updated!");
 
     Ok(())
 }
 
-pub fn client_dll_scan(client_idx: usize) -> anyhow::Result<()> {
+pub fn client_dll_scan(client: String) -> anyhow::Result<()> {
     use std::io::{self, Write};
 
-    // Validate client exists
-    let _client =
-        SettingsManager::get(|s| s.clients.get(client_idx).cloned()).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Invalid client index: {}. Use 'alembic client list' to see available clients.",
-                client_idx
-            )
-        })?;
+    let client_idx = resolve_client(&client)?;
 
     println!(
         "Scanning for DLL installations for client {}...",
         client_idx
     );
 
-    let clients = SettingsManager::get(|s| s.clients.clone());
+    let (clients, options) = SettingsManager::get(|s| (s.clients.clone(), s.scan.clone()));
+    let client_id = clients[client_idx].id().to_string();
 
     #[cfg(not(target_os = "windows"))]
     let scanned_prefixes = scanner::get_dll_scannable_prefixes(&clients);
 
-    let discovered_dlls = scanner::scan_for_decal_dlls(&clients)?;
+    let discovered_dlls = scanner::scan_for_decal_dlls(&clients, &options)?;
 
     let mut added_dlls: Vec<String> = vec![];
     let mut skipped_dlls: Vec<(String, &str)> = vec![];
@@ -362,15 +421,13 @@ pub fn client_dll_scan(client_idx: usize) -> anyhow::Result<()> {
 
         if response == "y" || response == "yes" {
             SettingsManager::modify(|settings| {
+                let Some(client_idx) = settings.client_index(&client_id) else {
+                    return;
+                };
                 settings.add_dll_to_client(client_idx, dll.clone());
 
-                let current_selected = settings
-                    .clients
-                    .get(client_idx)
-                    .and_then(|c| c.selected_dll());
-
-                if current_selected.is_none() && added_dlls.is_empty() {
-                    settings.select_dll_for_client(client_idx, Some(0));
+                if settings.clients[client_idx].selected_dll().is_none() && added_dlls.is_empty() {
+                    settings.select_client_dll(&client_id, Some(&dll.id));
                 }
             })?;
 
@@ -436,3 +493,68 @@ pub fn client_dll_scan(client_idx: usize) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// List the Decal plugins, services and filters in a client's Wine prefix,
+/// after enabling and disabling the ones given
+pub fn client_dll_plugins(
+    client: String,
+    enable: Vec<String>,
+    disable: Vec<String>,
+) -> anyhow::Result<()> {
+    let client_idx = resolve_client(&client)?;
+    let prefix = SettingsManager::get(|s| s.clients[client_idx].wine_prefix().map(PathBuf::from));
+    let Some(prefix) = prefix else {
+        bail!("Decal plugins can only be managed for Wine clients with a WINEPREFIX.");
+    };
+
+    let mut decal = DecalRegistry::load(&prefix)?;
+
+    if !enable.is_empty() || !disable.is_empty() {
+        // Look everything up first so a bad key doesn't leave a partial change
+        let mut changes = vec![];
+        for (keys, enabled) in [(&enable, true), (&disable, false)] {
+            for key in keys {
+                changes.push((decal.find(key)?, enabled));
+            }
+        }
+
+        for (component, enabled) in &changes {
+            decal.set_enabled(component, *enabled)?;
+        }
+        decal.save()?;
+
+        for (component, enabled) in &changes {
+            println!(
+                "✓ {} {} ({})",
+                if *enabled { "Enabled" } else { "Disabled" },
+                component.name,
+                component.kind
+            );
+        }
+        println!("Changes take effect the next time the game starts.");
+        println!("Close the game first, or Wine may overwrite them when it exits.");
+        println!();
+    }
+
+    let components = decal.components();
+    if components.is_empty() {
+        println!(
+            "No Decal plugins, services or filters are registered in {}.",
+            prefix.display()
+        );
+        return Ok(());
+    }
+
+    for (idx, component) in components.iter().enumerate() {
+        let marker = if component.enabled { "[x]" } else { "[ ]" };
+        println!(
+            "{} {}: {} ({}, {})",
+            marker, idx, component.name, component.kind, component.clsid
+        );
+        if let Some(dll_path) = &component.dll_path {
+            println!("       {}", dll_path.display());
+        }
+    }
+
+    Ok(())
+}
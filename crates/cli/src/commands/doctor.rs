@@ -0,0 +1,55 @@
+use anyhow::bail;
+use libalembic::{
+    doctor,
+    settings::SettingsManager,
+    validation::{Finding, Severity},
+};
+
+fn print_finding(finding: &Finding) {
+    println!(
+        "[{}] {}: {}",
+        finding.severity, finding.subject, finding.message
+    );
+    if let Some(fix) = &finding.fix {
+        println!("    fix: {}", fix);
+    }
+}
+
+pub fn doctor() -> anyhow::Result<()> {
+    let mut result = SettingsManager::get(doctor::check_setup);
+    result.merge(doctor::check_rpc_port());
+
+    for finding in &result.findings {
+        print_finding(finding);
+    }
+
+    let count = |severity| {
+        result
+            .findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    };
+    let errors = count(Severity::Error);
+    let warnings = count(Severity::Warning);
+
+    println!();
+    if errors > 0 {
+        bail!(
+            "Found {} problem(s) that will stop a launch and {} warning(s)",
+            errors,
+            warnings
+        );
+    }
+
+    if warnings > 0 {
+        println!(
+            "No problems that will stop a launch, but {} warning(s)",
+            warnings
+        );
+    } else {
+        println!("Everything looks good");
+    }
+
+    Ok(())
+}
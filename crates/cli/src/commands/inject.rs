@@ -8,14 +8,18 @@ pub fn inject() -> anyhow::Result<()> {
     println!("Running cork to find and inject into acclient.exe...");
     println!();
 
-    // Get selected client config and index
-    let (client_config, client_idx) =
-        SettingsManager::get(|s| (s.get_selected_client().cloned(), s.selected_client));
+    // Get selected client config and its selected DLL
+    let (client_config, dll_config) = SettingsManager::get(|s| {
+        (
+            s.get_selected_client().cloned(),
+            s.get_selected_dll().cloned(),
+        )
+    });
 
     let client_config = match client_config {
         Some(config) => config,
         None => {
-            bail!("No client selected. Use 'alembic client select <index>' to select a client.");
+            bail!("No client selected. Use 'alembic client select <client>' to select a client.");
         }
     };
 
@@ -27,17 +31,11 @@ pub fn inject() -> anyhow::Result<()> {
         }
     };
 
-    // Get selected DLL config for the selected client
-    let dll_config = match client_idx {
-        Some(idx) => SettingsManager::get(|s| s.get_client_selected_dll(idx).cloned()),
-        None => None,
-    };
-
     let dll_path = match dll_config {
         Some(config) => config.dll_path.display().to_string(),
         None => {
             bail!(
-                "No DLL selected. Use 'alembic client dll --client <index> select <dll_index>' to select a DLL for injection."
+                "No DLL selected. Use 'alembic client dll --client <client> select <dll>' to select a DLL for injection."
             );
         }
     };
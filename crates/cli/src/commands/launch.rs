@@ -1,9 +1,17 @@
 use anyhow::{Context, bail};
 use libalembic::{
-    launcher::{Launcher, traits::ClientLauncher},
-    settings::{Account, ServerInfo, SettingsManager},
+    credentials::CredentialRef,
+    launcher::{
+        Launcher,
+        session::{LaunchSession, SessionState},
+        traits::ClientLauncher,
+    },
+    settings::{Account, LaunchTarget, ServerInfo, SettingsManager, new_id},
+    validation::Severity,
 };
 
+use super::{profile::resolve_profile, vault::unlock_vault_for};
+
 #[allow(clippy::too_many_arguments)]
 pub fn exec_launch(
     mode: String,
@@ -22,10 +30,12 @@ pub fn exec_launch(
 
     let client_config = match mode.to_lowercase().as_str() {
         "windows" => ClientConfigType::Windows(WindowsClientConfig {
+            id: new_id(),
             name: "CLI-specified Windows client".to_string(),
             client_path: PathBuf::from(&client_path),
             dlls: vec![],
             selected_dll: None,
+            fingerprint: None,
         }),
         "wine" => {
             let prefix =
@@ -38,11 +48,13 @@ pub fn exec_launch(
             }
 
             ClientConfigType::Wine(WineClientConfig {
+                id: new_id(),
                 name: "CLI-specified Wine client".to_string(),
                 client_path: PathBuf::from(&client_path),
                 launch_command,
                 dlls: vec![],
                 selected_dll: None,
+                fingerprint: None,
             })
         }
         _ => bail!(
@@ -52,15 +64,17 @@ pub fn exec_launch(
     };
 
     let server_info = ServerInfo {
+        id: new_id(),
         name: hostname.clone(),
         hostname,
         port,
     };
 
     let account_info = Account {
-        server_index: 0,
+        id: new_id(),
+        server_id: server_info.id.clone(),
         username,
-        password,
+        credential: CredentialRef::Plaintext { password },
     };
 
     println!("Launch mode: {}", mode);
@@ -72,70 +86,73 @@ pub fn exec_launch(
 }
 
 /// Validate that client and DLL paths exist before launching.
-/// For Wine configs, Windows paths are validated by running a check under Wine.
-#[allow(dead_code)]
+/// For Wine configs, Windows paths are resolved through the prefix's drives.
 pub fn validate_launch_config(
     client_config: &libalembic::settings::ClientConfigType,
     inject_config: &Option<libalembic::inject_config::InjectConfig>,
 ) -> anyhow::Result<()> {
     let result = client_config.validate(inject_config.as_ref());
 
-    if result.is_valid {
+    for warning in result
+        .findings
+        .iter()
+        .filter(|f| f.severity == Severity::Warning)
+    {
+        println!("Warning: {}", warning);
+    }
+
+    if result.is_valid() {
         Ok(())
     } else {
+        let errors: Vec<_> = result.errors().map(|e| e.to_string()).collect();
         bail!(
-            "Launch configuration validation failed:\n  - {}",
-            result.errors.join("\n  - ")
+            "Launch configuration validation failed:\n  - {}\nRun 'alembic doctor' to check the rest of your setup.",
+            errors.join("\n  - ")
         )
     }
 }
 
 pub fn preset_launch(
+    profile: Option<String>,
     server_name: Option<String>,
     account_name: Option<String>,
 ) -> anyhow::Result<()> {
+    if let Some(profile) = profile {
+        return profile_launch(profile);
+    }
+
     // Get selected client config
     let client_config =
         SettingsManager::get(|s| s.get_selected_client().cloned()).ok_or_else(|| {
-            anyhow::anyhow!("No client selected. Use 'alembic client select <index>'")
+            anyhow::anyhow!("No client selected. Use 'alembic client select <client>'")
         })?;
 
-    // Get server (by name override or selected index)
+    // Get server (by override or selection)
     let server_info = if let Some(name) = server_name {
-        SettingsManager::get(|s| s.servers.iter().find(|srv| srv.name == name).cloned())
+        SettingsManager::get(|s| s.find_server(&name).map(|idx| s.servers[idx].clone()))
             .with_context(|| format!("Server '{}' not found in settings", name))?
     } else {
         SettingsManager::get(|s| s.get_selected_server().cloned()).ok_or_else(|| {
-            anyhow::anyhow!("No server selected. Use 'alembic server select <index>'")
+            anyhow::anyhow!("No server selected. Use 'alembic server select <server>'")
         })?
     };
 
-    // Get account (by username override or selected index)
+    // Get account (by override or selection)
     let account_info = if let Some(username) = account_name {
-        SettingsManager::get(|s| {
-            s.accounts
-                .iter()
-                .find(|acc| acc.username == username)
-                .cloned()
-        })
-        .with_context(|| format!("Account '{}' not found in settings", username))?
+        SettingsManager::get(|s| s.find_account(&username).map(|idx| s.accounts[idx].clone()))
+            .with_context(|| format!("Account '{}' not found in settings", username))?
     } else {
         SettingsManager::get(|s| s.get_selected_account().cloned()).ok_or_else(|| {
-            anyhow::anyhow!("No account selected. Use 'alembic account select <index>'")
+            anyhow::anyhow!("No account selected. Use 'alembic account select <account>'")
         })?
     };
 
-    // Get selected client index to access its DLLs
-    let client_idx = SettingsManager::get(|s| s.selected_client);
+    unlock_vault_for(account_info.credential.kind())?;
 
     // Get selected DLL for the selected client (optional - if none selected, no injection will occur)
-    let inject_config = match client_idx {
-        Some(idx) => SettingsManager::get(|s| s.get_client_selected_dll(idx).cloned()),
-        None => None,
-    };
+    let inject_config = SettingsManager::get(|s| s.get_selected_dll().cloned());
 
-    // TODO: Validation doesn't support flatpak yet, skip for now
-    // validate_launch_config(&client_config, &inject_config)?;
+    validate_launch_config(&client_config, &inject_config)?;
 
     println!("Client: {}", client_config.name());
     println!(
@@ -152,26 +169,78 @@ pub fn preset_launch(
     run_launcher(client_config, inject_config, server_info, account_info)
 }
 
+/// Launch the client, DLL, server and account saved in a profile
+pub fn profile_launch(profile: String) -> anyhow::Result<()> {
+    let index = resolve_profile(&profile)?;
+    let LaunchTarget {
+        client,
+        dll,
+        server,
+        account,
+    } = SettingsManager::get(|s| {
+        s.profile_launch_target(&s.profiles[index])
+            .map_err(|e| e.to_string())
+    })
+    .map_err(anyhow::Error::msg)?;
+
+    unlock_vault_for(account.credential.kind())?;
+    validate_launch_config(&client, &dll)?;
+
+    println!(
+        "Profile: {}",
+        SettingsManager::get(|s| s.profiles[index].name.clone())
+    );
+    println!("Client: {}", client.name());
+    println!(
+        "Server: {} ({}:{})",
+        server.name, server.hostname, server.port
+    );
+    println!("Account: {}", account.username);
+    if let Some(ref dll) = dll {
+        println!("DLL: {} ({})", dll.dll_type, dll.dll_path.display());
+    } else {
+        println!("DLL: None (no injection)");
+    }
+
+    run_launcher(client, dll, server, account)
+}
+
 fn run_launcher(
     client_config: libalembic::settings::ClientConfigType,
     inject_config: Option<libalembic::inject_config::InjectConfig>,
     server_info: ServerInfo,
     account_info: Account,
 ) -> anyhow::Result<()> {
-    let mut launcher = Launcher::new(client_config, inject_config, server_info, account_info);
+    let launcher = Launcher::new(client_config, inject_config, server_info, account_info);
 
-    // Launch the client - stdout/stderr are inherited by the child process
-    launcher.launch()?;
+    // cork's output is passed through while the session follows the client,
+    // and the DLL is ejected once the client exits
+    let mut session = LaunchSession::launch(launcher)?;
+    println!(
+        "\nWaiting for the client to exit (launcher pid {})...",
+        session.launcher_pid()
+    );
+
+    let mut announced = false;
+    let report = loop {
+        match session.state() {
+            SessionState::Starting => {}
+            SessionState::Running { client_pid } => {
+                if !announced {
+                    println!("acclient.exe is running (pid {})", client_pid);
+                    announced = true;
+                }
+            }
+            SessionState::Exited(report) => break report,
+        }
 
-    // Wait for user to press Enter to exit (keeps the launcher running)
-    println!("\nPress Enter to eject and exit...");
-    let mut input = String::new();
-    std::io::stdin().read_line(&mut input)?;
+        std::thread::sleep(std::time::Duration::from_millis(250));
+    };
 
-    // Cleanup launcher
-    println!("Ejecting...");
-    launcher.eject()?;
-    println!("Exited.");
+    if report.crash().is_some() || report.client_code.is_none() {
+        bail!("{}", report);
+    }
+    println!("{}", report);
 
     Ok(())
 }
@@ -1,7 +1,22 @@
 pub mod account;
 pub mod client;
+pub mod dev;
 pub mod dll;
+pub mod doctor;
 pub mod inject;
 pub mod launch;
+pub mod profile;
 pub mod scan;
 pub mod server;
+pub mod transfer;
+pub mod vault;
+
+/// Error for an entry that another process removed between resolving it and
+/// changing it
+pub fn removed_meanwhile(kind: &str, key: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "The {} '{}' was removed by another process before it could be changed",
+        kind,
+        key
+    )
+}
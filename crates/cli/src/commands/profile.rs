@@ -0,0 +1,181 @@
+use anyhow::bail;
+use libalembic::settings::{LaunchProfile, SettingsManager, short_id};
+
+use super::{
+    account::resolve_account, client::resolve_client, dll::resolve_dll, removed_meanwhile,
+    server::resolve_server,
+};
+
+/// Resolve a profile given as an index, ID or name
+pub fn resolve_profile(profile: &str) -> anyhow::Result<usize> {
+    match SettingsManager::get(|s| s.find_profile(profile)) {
+        Some(index) => Ok(index),
+        None => bail!(
+            "No profile matches '{}'. Use 'alembic profile list' to see available profiles.",
+            profile
+        ),
+    }
+}
+
+/// Add a profile. Anything not given is taken from the current selections.
+pub fn profile_add(
+    name: String,
+    client: Option<String>,
+    dll: Option<String>,
+    no_dll: bool,
+    server: Option<String>,
+    account: Option<String>,
+) -> anyhow::Result<()> {
+    let client_idx = match client {
+        Some(client) => resolve_client(&client)?,
+        None => SettingsManager::get(|s| s.selected_client_index()).ok_or_else(|| {
+            anyhow::anyhow!("No client selected. Use --client or 'alembic client select <client>'")
+        })?,
+    };
+    let client = SettingsManager::get(|s| s.clients[client_idx].clone());
+
+    let dll = if no_dll {
+        None
+    } else {
+        match dll {
+            Some(dll) => {
+                let dll_idx = resolve_dll(client_idx, &dll)?;
+                Some(client.dlls()[dll_idx].clone())
+            }
+            None => client.selected_dll().map(|idx| client.dlls()[idx].clone()),
+        }
+    };
+
+    let server = match server {
+        Some(server) => {
+            let server_idx = resolve_server(&server)?;
+            SettingsManager::get(|s| s.servers[server_idx].clone())
+        }
+        None => SettingsManager::get(|s| s.get_selected_server().cloned()).ok_or_else(|| {
+            anyhow::anyhow!("No server selected. Use --server or 'alembic server select <server>'")
+        })?,
+    };
+
+    let account = match account {
+        Some(account) => {
+            let account_idx = resolve_account(&account)?;
+            SettingsManager::get(|s| s.accounts[account_idx].clone())
+        }
+        None => SettingsManager::get(|s| s.get_selected_account().cloned()).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No account selected. Use --account or 'alembic account select <account>'"
+            )
+        })?,
+    };
+
+    if account.server_id != server.id {
+        bail!(
+            "Account '{}' doesn't belong to server '{}'",
+            account.username,
+            server.name
+        );
+    }
+
+    let profile = LaunchProfile::new(&name, &client, dll.as_ref(), &server, &account);
+    let replaced = SettingsManager::get(|s| s.profiles.iter().any(|p| p.name == name));
+
+    SettingsManager::modify(|settings| settings.add_profile(profile.clone()))?;
+
+    if replaced {
+        println!("✓ Profile '{}' updated!", name);
+    } else {
+        println!("✓ Profile '{}' added!", name);
+    }
+    println!();
+    println!("  Client:  {}", client.name());
+    match &dll {
+        Some(dll) => println!("  DLL:     {} ({})", dll.dll_type, dll.dll_path.display()),
+        None => println!("  DLL:     None (no injection)"),
+    }
+    println!("  Server:  {}", server.name);
+    println!("  Account: {}", account.username);
+
+    Ok(())
+}
+
+pub fn profile_list() -> anyhow::Result<()> {
+    let (profiles, active) =
+        SettingsManager::get(|s| (s.profiles.clone(), s.active_profile_index()));
+
+    if profiles.is_empty() {
+        println!("No profiles configured. Use 'profile add' to add a profile.");
+        return Ok(());
+    }
+
+    for (index, profile) in profiles.iter().enumerate() {
+        let marker = if Some(index) == active { " * " } else { "   " };
+
+        let summary = SettingsManager::get(|s| match s.profile_launch_target(profile) {
+            Ok(target) => format!(
+                "{} / {} / {}@{}",
+                target.client.name(),
+                target
+                    .dll
+                    .map(|dll| dll.dll_type.to_string())
+                    .unwrap_or_else(|| "no DLL".to_string()),
+                target.account.username,
+                target.server.name
+            ),
+            Err(e) => format!("broken: {}", e),
+        });
+
+        println!(
+            "{}{}: {} ({}) - {}",
+            marker,
+            index,
+            profile.name,
+            short_id(&profile.id),
+            summary
+        );
+    }
+
+    Ok(())
+}
+
+pub fn profile_remove(profile: String) -> anyhow::Result<()> {
+    let index = resolve_profile(&profile)?;
+    let (id, name) =
+        SettingsManager::get(|s| (s.profiles[index].id.clone(), s.profiles[index].name.clone()));
+
+    println!("Removing profile '{}'...", name);
+
+    let mut found = false;
+    SettingsManager::modify(|settings| {
+        if let Some(index) = settings.profile_index(&id) {
+            settings.remove_profile(index);
+            found = true;
+        }
+    })?;
+
+    if !found {
+        return Err(removed_meanwhile("profile", &profile));
+    }
+
+    println!("✓ Profile removed!");
+
+    Ok(())
+}
+
+/// Make a profile's client, DLL, server and account the current selections
+pub fn profile_select(profile: String) -> anyhow::Result<()> {
+    let index = resolve_profile(&profile)?;
+    let (id, name) =
+        SettingsManager::get(|s| (s.profiles[index].id.clone(), s.profiles[index].name.clone()));
+
+    let mut result = Err(removed_meanwhile("profile", &profile));
+    SettingsManager::modify(|settings| {
+        if let Some(index) = settings.profile_index(&id) {
+            result = settings.apply_profile(index);
+        }
+    })?;
+    result?;
+
+    println!("✓ Selected profile: {}", name);
+
+    Ok(())
+}
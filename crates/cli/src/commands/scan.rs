@@ -1,49 +1,146 @@
-use libalembic::{scanner, settings::SettingsManager};
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+};
+
+use anyhow::{anyhow, bail};
+use libalembic::{
+    scanner::{self, ScanCancel, ScanOptions, ScanProgress},
+    settings::{ClientConfigType, ScanEntry, ScanStatus, SettingsManager},
+};
+
+/// Run a scan, printing its progress as it goes. Ctrl-C cancels the scan;
+/// pressing it again, or after the scan is done, exits as usual.
+fn scan_with_progress(options: &ScanOptions) -> anyhow::Result<Vec<ClientConfigType>> {
+    let cancel = ScanCancel::default();
+    let scanning = Arc::new(AtomicBool::new(true));
+
+    let handler_cancel = cancel.clone();
+    let handler_scanning = scanning.clone();
+    ctrlc::set_handler(move || {
+        if handler_scanning.load(Ordering::Relaxed) && !handler_cancel.is_cancelled() {
+            eprintln!("Cancelling scan...");
+            handler_cancel.cancel();
+        } else {
+            std::process::exit(130);
+        }
+    })?;
+
+    let (sender, receiver) = mpsc::channel();
+    let progress = ScanProgress::new(sender, cancel);
+
+    let result = std::thread::scope(|scope| {
+        let scan = scope.spawn(move || scanner::scan_all_with_progress(options, &progress));
+
+        // The channel closes once the scan is done with its progress
+        for event in receiver {
+            println!("  {}", event);
+        }
+
+        scan.join()
+            .unwrap_or_else(|_| Err(anyhow!("The scan stopped unexpectedly")))
+    });
+    scanning.store(false, Ordering::Relaxed);
+
+    result
+}
 
-pub fn client_scan() -> anyhow::Result<()> {
+/// Add the items in `items` that `list` doesn't already have
+fn add_missing<T: PartialEq + Clone>(list: &mut Vec<T>, items: &[T]) {
+    for item in items {
+        if !list.contains(item) {
+            list.push(item.clone());
+        }
+    }
+}
+
+pub fn client_scan(
+    roots: Vec<PathBuf>,
+    save_roots: bool,
+    client_globs: Vec<String>,
+    dll_globs: Vec<String>,
+    save_globs: bool,
+) -> anyhow::Result<()> {
     use std::io::{self, Write};
 
+    let roots = roots
+        .into_iter()
+        .map(std::path::absolute)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if save_globs && client_globs.is_empty() && dll_globs.is_empty() {
+        bail!("--save-globs needs at least one --client-glob or --dll-glob");
+    }
+
+    // Check the globs before saving them
+    ScanOptions {
+        client_globs: client_globs.clone(),
+        dll_globs: dll_globs.clone(),
+        ..Default::default()
+    }
+    .check_globs()?;
+
+    if save_roots || save_globs {
+        SettingsManager::modify(|settings| {
+            if save_roots {
+                add_missing(&mut settings.scan.roots, &roots);
+            }
+            if save_globs {
+                add_missing(&mut settings.scan.client_globs, &client_globs);
+                add_missing(&mut settings.scan.dll_globs, &dll_globs);
+            }
+        })?;
+    }
+
+    let mut options = SettingsManager::get(|s| s.scan.clone());
+    add_missing(&mut options.roots, &roots);
+    add_missing(&mut options.client_globs, &client_globs);
+    add_missing(&mut options.dll_globs, &dll_globs);
+
     println!("Scanning for AC client installations...");
 
     // Get the prefixes that will be scanned (for reporting)
-    let scanned_prefixes = scanner::WineScanner::get_scannable_prefixes();
-
-    let discovered = scanner::scan_all()?;
-
-    let mut added_count = 0;
-    let mut skipped_configs: Vec<String> = vec![];
-    let mut added_configs: Vec<String> = vec![];
-
-    // Check if there are any existing clients before we start adding
-    let had_no_clients = SettingsManager::get(|s| s.clients.is_empty());
-
-    for config in &discovered {
-        // Check if already exists
-        let already_exists = SettingsManager::get(|s| {
-            s.clients
-                .iter()
-                .any(|existing| existing.install_path() == config.install_path())
-        });
-
-        if already_exists {
-            skipped_configs.push(format!(
-                "{} ({})",
-                config.name(),
-                config.install_path().display()
-            ));
-            continue;
-        }
+    let scanned_prefixes = scanner::WineScanner::get_scannable_prefixes(&options);
 
-        // Show details and prompt
-        println!();
-        println!("Found: {}", config.name());
-        println!("Path: {}", config.install_path().display());
-        println!(
-            "Type: {}",
-            if config.is_wine() { "Wine" } else { "Windows" }
-        );
-
-        print!("Add this client? (y/n): ");
+    let discovered = scan_with_progress(&options)?;
+    let entries = SettingsManager::get(|s| s.reconcile_scan(&discovered));
+
+    let mut accepted: Vec<ScanEntry> = vec![];
+    let mut skipped: Vec<&ScanEntry> = vec![];
+
+    for entry in &entries {
+        let Some(config) = &entry.scanned else {
+            continue;
+        };
+
+        let question = match &entry.status {
+            ScanStatus::New => {
+                println!();
+                println!("Found: {}", config.name());
+                println!("Path: {}", entry.install_path.display());
+                println!(
+                    "Type: {}",
+                    if config.is_wine() { "Wine" } else { "Windows" }
+                );
+                "Add this client?"
+            }
+            ScanStatus::Changed(changes) => {
+                println!();
+                println!("Changed: {}", entry.name);
+                println!("Path: {}", entry.install_path.display());
+                for change in changes {
+                    println!("  ~ {}", change);
+                }
+                "Update this client?"
+            }
+            ScanStatus::Unchanged | ScanStatus::Missing => continue,
+        };
+
+        print!("{} (y/n): ", question);
         io::stdout().flush()?;
 
         let mut input = String::new();
@@ -51,61 +148,92 @@ pub fn client_scan() -> anyhow::Result<()> {
         let response = input.trim().to_lowercase();
 
         if response == "y" || response == "yes" {
-            let should_select = had_no_clients && added_count == 0;
-
-            SettingsManager::modify(|settings| {
-                settings.add_client(config.clone(), should_select);
-                settings.is_configured = true;
-            })?;
-
-            added_configs.push(format!(
-                "{} ({})",
-                config.name(),
-                config.install_path().display()
-            ));
-            added_count += 1;
+            accepted.push(entry.clone());
         } else {
-            skipped_configs.push(format!(
-                "{} ({})",
-                config.name(),
-                config.install_path().display()
-            ));
+            skipped.push(entry);
         }
     }
 
+    if !accepted.is_empty() {
+        // Settings may have changed while we were asking, so compare against
+        // them again rather than saving what we compared against before
+        SettingsManager::modify(|settings| {
+            let current: Vec<ScanEntry> = settings
+                .reconcile_scan(&discovered)
+                .into_iter()
+                .filter(|entry| {
+                    accepted
+                        .iter()
+                        .any(|a| a.install_path == entry.install_path)
+                })
+                .collect();
+            settings.merge_scan(&current);
+        })?;
+    }
+
+    let count =
+        |matches: fn(&ScanStatus) -> bool| entries.iter().filter(|e| matches(&e.status)).count();
+    let describe = |entry: &ScanEntry| format!("{} ({})", entry.name, entry.install_path.display());
+
     // Print summary report
     println!();
     println!("=== Scan Report ===");
     println!();
+    if !options.roots.is_empty() {
+        println!("Scan roots:");
+        for root in &options.roots {
+            println!("  - {}", root.display());
+        }
+        println!();
+    }
     println!("Scanned {} wine prefix(es):", scanned_prefixes.len());
     for prefix in &scanned_prefixes {
         println!("  - {}", prefix.display());
     }
     println!();
     println!(
-        "Found {} client(s), added {}, skipped {}",
-        discovered.len(),
-        added_configs.len(),
-        skipped_configs.len()
+        "Found {} client(s): {} new, {} changed, {} unchanged",
+        entries.iter().filter(|e| e.scanned.is_some()).count(),
+        count(|s| *s == ScanStatus::New),
+        count(|s| matches!(s, ScanStatus::Changed(_))),
+        count(|s| *s == ScanStatus::Unchanged),
     );
 
-    if !added_configs.is_empty() {
+    if !accepted.is_empty() {
         println!();
-        println!("Added:");
-        for name in &added_configs {
-            println!("  + {}", name);
+        println!("Added or updated:");
+        for entry in &accepted {
+            let sign = if entry.status == ScanStatus::New {
+                "+"
+            } else {
+                "~"
+            };
+            println!("  {} {}", sign, describe(entry));
         }
     }
 
-    if !skipped_configs.is_empty() {
+    if !skipped.is_empty() {
         println!();
         println!("Skipped:");
-        for name in &skipped_configs {
-            println!("  - {}", name);
+        for entry in &skipped {
+            println!("  - {}", describe(entry));
+        }
+    }
+
+    let missing: Vec<_> = entries
+        .iter()
+        .filter(|e| e.status == ScanStatus::Missing)
+        .collect();
+    if !missing.is_empty() {
+        println!();
+        println!("Configured but not found by this scan:");
+        for entry in &missing {
+            println!("  ? {}", describe(entry));
         }
+        println!("Remove clients that no longer exist with: alembic config client remove");
     }
 
-    if discovered.is_empty() {
+    if entries.iter().all(|e| e.scanned.is_none()) {
         println!();
         println!("No client installations found.");
         println!("You can add a client manually with: alembic config client add");
@@ -1,5 +1,7 @@
 use anyhow::bail;
-use libalembic::settings::{ServerInfo, SettingsManager};
+use libalembic::settings::{Account, ServerInfo, SettingsManager, new_id, short_id};
+
+use super::{account::forget_passwords, removed_meanwhile};
 
 pub fn server_add(name: String, hostname: String, port: String) -> anyhow::Result<()> {
     println!("Adding server...");
@@ -7,16 +9,19 @@ pub fn server_add(name: String, hostname: String, port: String) -> anyhow::Resul
     let had_no_servers = SettingsManager::get(|s| s.servers.is_empty());
 
     SettingsManager::modify(|settings| {
-        settings.servers.push(ServerInfo {
+        let server = ServerInfo {
+            id: new_id(),
             name: name.clone(),
             hostname: hostname.clone(),
             port: port.clone(),
-        });
+        };
 
         // Auto-select if this is the first server
         if had_no_servers && settings.selected_server.is_none() {
-            settings.selected_server = Some(0);
+            settings.selected_server = Some(server.id.clone());
         }
+
+        settings.servers.push(server);
     })?;
 
     if had_no_servers {
@@ -34,7 +39,7 @@ pub fn server_add(name: String, hostname: String, port: String) -> anyhow::Resul
 
 pub fn server_list() -> anyhow::Result<()> {
     let servers = SettingsManager::get(|s| s.servers.clone());
-    let selected_server = SettingsManager::get(|s| s.selected_server);
+    let selected_server = SettingsManager::get(|s| s.selected_server.clone());
 
     if servers.is_empty() {
         println!("No servers configured. Use 'server add' to add a server.");
@@ -42,31 +47,40 @@ pub fn server_list() -> anyhow::Result<()> {
     }
 
     for (index, server) in servers.iter().enumerate() {
-        let is_selected = Some(index) == selected_server;
+        let is_selected = selected_server.as_deref() == Some(server.id.as_str());
         let marker = if is_selected { " * " } else { "   " };
-        println!("{}{}: {}", marker, index, server.name);
+        println!(
+            "{}{}: {} ({})",
+            marker,
+            index,
+            server.name,
+            short_id(&server.id)
+        );
     }
 
     Ok(())
 }
 
-pub fn server_select(index: usize) -> anyhow::Result<()> {
-    let servers = SettingsManager::get(|s| s.servers.clone());
-
-    if index >= servers.len() {
-        bail!(
-            "Invalid server index: {}. Use 'alembic server list' to see available servers.",
-            index
-        );
+/// Resolve a server given as an index, ID or name
+pub fn resolve_server(server: &str) -> anyhow::Result<usize> {
+    match SettingsManager::get(|s| s.find_server(server)) {
+        Some(index) => Ok(index),
+        None => bail!(
+            "No server matches '{}'. Run 'alembic server list' to see available servers.",
+            server
+        ),
     }
+}
 
-    let server_name = servers[index].name.clone();
+pub fn server_select(server: String) -> anyhow::Result<()> {
+    let index = resolve_server(&server)?;
+    let ServerInfo { id, name, .. } = SettingsManager::get(|s| s.servers[index].clone());
 
     SettingsManager::modify(|settings| {
-        settings.selected_server = Some(index);
+        settings.selected_server = Some(id);
     })?;
 
-    println!("✓ Selected server: {}", server_name);
+    println!("✓ Selected server: {}", name);
 
     Ok(())
 }
@@ -87,62 +101,41 @@ pub fn server_reset() -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn server_remove(index: usize) -> anyhow::Result<()> {
-    let servers = SettingsManager::get(|s| s.servers.clone());
-
-    if index >= servers.len() {
-        bail!(
-            "Invalid index {}. No server exists at that index. Run 'alembic server list' to see available servers.",
-            index
-        );
-    }
-
-    let server_name = servers[index].name.clone();
+pub fn server_remove(server: String) -> anyhow::Result<()> {
+    let index = resolve_server(&server)?;
+    let server = SettingsManager::get(|s| s.servers[index].clone());
 
-    println!("Removing server '{}'...", server_name);
+    println!("Removing server '{}'...", server.name);
 
+    // Removing a server also removes the accounts that were associated with
+    // it, so their passwords are forgotten too
+    let mut accounts: Vec<Account> = vec![];
     SettingsManager::modify(|settings| {
-        settings.servers.remove(index);
-
-        // Update selected_server if it was this one or needs adjustment
-        if let Some(selected) = settings.selected_server {
-            if selected == index {
-                settings.selected_server = None;
-            } else if selected > index {
-                settings.selected_server = Some(selected - 1);
-            }
-        }
-
-        // Remove accounts that were associated with the deleted server
-        settings.accounts.retain(|a| a.server_index != index);
-
-        // Update account server indices (after removing deleted accounts)
-        for account in &mut settings.accounts {
-            if account.server_index > index {
-                account.server_index -= 1;
-            }
+        accounts = settings
+            .accounts
+            .iter()
+            .filter(|a| a.server_id == server.id)
+            .cloned()
+            .collect();
+        if let Some(index) = settings.server_index(&server.id) {
+            settings.remove_server(index);
         }
     })?;
 
+    forget_passwords(&accounts);
+
     println!("✓ Server removed!");
 
     Ok(())
 }
 
 pub fn server_edit(
-    index: usize,
+    server: String,
     name: Option<String>,
     hostname: Option<String>,
     port: Option<String>,
 ) -> anyhow::Result<()> {
-    let servers = SettingsManager::get(|s| s.servers.clone());
-
-    if index >= servers.len() {
-        bail!(
-            "Invalid index {}. No server exists at that index. Run 'alembic server list' to see available servers.",
-            index
-        );
-    }
+    let index = resolve_server(&server)?;
 
     if name.is_none() && hostname.is_none() && port.is_none() {
         println!("No changes specified. Use --name, --hostname, or --port to modify the server.");
@@ -151,7 +144,13 @@ pub fn server_edit(
 
     println!("Editing server at index {}...", index);
 
+    let id = SettingsManager::get(|s| s.servers[index].id.clone());
+    let mut found = false;
     SettingsManager::modify(|settings| {
+        let Some(index) = settings.server_index(&id) else {
+            return;
+        };
+        found = true;
         let server = &mut settings.servers[index];
 
         if let Some(n) = name {
@@ -168,6 +167,10 @@ pub fn server_edit(
         }
     })?;
 
+    if !found {
+        return Err(removed_meanwhile("server", &server));
+    }
+
     println!("✓ Server updated!");
 
     Ok(())
@@ -0,0 +1,178 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, bail};
+use libalembic::{
+    credentials::CredentialStoreKind,
+    importers::Launcher,
+    settings::{
+        ExportOptions, SettingsExport, SettingsManager, export_settings, persist,
+        save_imported_passwords,
+    },
+};
+
+use super::{server::resolve_server, vault::unlock_vault_for};
+
+pub fn config_export(
+    output: Option<PathBuf>,
+    include_passwords: bool,
+    skip_clients: bool,
+) -> anyhow::Result<()> {
+    if include_passwords {
+        let uses_vault = SettingsManager::get(|s| {
+            s.accounts
+                .iter()
+                .any(|a| a.credential.kind() == CredentialStoreKind::Vault)
+        });
+        if uses_vault {
+            unlock_vault_for(CredentialStoreKind::Vault)?;
+        }
+    }
+
+    let options = ExportOptions {
+        include_passwords,
+        skip_clients,
+    };
+    let export = SettingsManager::get(|s| export_settings(s, options).map_err(|e| e.to_string()))
+        .map_err(anyhow::Error::msg)?;
+    let contents = export.to_json()?;
+
+    match output {
+        Some(path) => {
+            if include_passwords {
+                persist::write_private(&path, &contents)?;
+            } else {
+                fs::write(&path, &contents)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+
+            eprintln!(
+                "✓ Exported {} servers, {} accounts, {} clients and {} profiles to {}",
+                export.servers.len(),
+                export.accounts.len(),
+                export.clients.len(),
+                export.profiles.len(),
+                path.display()
+            );
+        }
+        None => println!("{}", contents),
+    }
+
+    if include_passwords {
+        eprintln!("Warning: The export contains account passwords in plain text.");
+    } else {
+        eprintln!("Passwords were left out. Use --include-passwords to export them.");
+    }
+
+    Ok(())
+}
+
+pub fn config_import(path: PathBuf, store: Option<String>) -> anyhow::Result<()> {
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let import = SettingsExport::from_json(&contents)?;
+
+    println!("Importing {}...", path.display());
+
+    merge_import(import, store)
+}
+
+/// Import servers and accounts from another launcher's files
+pub fn config_import_launcher(
+    launcher: String,
+    path: Option<PathBuf>,
+    store: Option<String>,
+    server: Option<String>,
+) -> anyhow::Result<()> {
+    let launcher: Launcher = launcher.parse()?;
+    let Some(path) = path.or_else(|| launcher.default_path()) else {
+        bail!(
+            "Couldn't find {}'s settings. Pass the path to its settings directory or files.",
+            launcher
+        );
+    };
+
+    println!("Importing from {} ({})...", launcher, path.display());
+
+    let import = launcher.read(&path)?;
+
+    // Accounts that aren't tied to a server go to --server, which can name a
+    // server being imported or one that's already configured
+    let fallback_server_id = match server {
+        Some(server) => match import.servers.iter().find(|s| {
+            s.name.eq_ignore_ascii_case(&server) || s.hostname.eq_ignore_ascii_case(&server)
+        }) {
+            Some(imported) => Some(imported.id.clone()),
+            None => {
+                let index = resolve_server(&server)?;
+                Some(SettingsManager::get(|s| s.servers[index].id.clone()))
+            }
+        },
+        None => None,
+    };
+
+    let (export, skipped) = import.into_export(fallback_server_id.as_deref());
+
+    merge_import(export, store)?;
+
+    if skipped > 0 {
+        println!();
+        println!(
+            "Warning: {} accounts aren't tied to a server and were skipped. Use --server to choose the server to add them to.",
+            skipped
+        );
+    }
+
+    Ok(())
+}
+
+fn merge_import(import: SettingsExport, store: Option<String>) -> anyhow::Result<()> {
+    let store: CredentialStoreKind = match store {
+        Some(store) => store.parse()?,
+        None => CredentialStoreKind::default(),
+    };
+
+    let has_passwords = import.accounts.iter().any(|a| a.password.is_some());
+    if has_passwords {
+        unlock_vault_for(store)?;
+    }
+
+    let mut summary = None;
+    SettingsManager::modify(|settings| summary = Some(settings.merge_import(&import)))?;
+    let summary = summary.expect("modify always runs its closure");
+
+    if !summary.passwords.is_empty() {
+        let credentials = save_imported_passwords(&summary.passwords, store).with_context(|| {
+            format!(
+                "Settings were imported, but passwords couldn't be saved to the {}. Set them with 'account edit --password' or import again with another --store.",
+                store
+            )
+        })?;
+        SettingsManager::modify(|settings| settings.apply_imported_credentials(&credentials))?;
+    }
+
+    println!("✓ Import complete!");
+    println!();
+    println!(
+        "  Servers:  {} added, {} updated",
+        summary.servers_added, summary.servers_updated
+    );
+    println!(
+        "  Accounts: {} added, {} updated",
+        summary.accounts_added, summary.accounts_updated
+    );
+    println!(
+        "  Clients:  {} added, {} merged",
+        summary.clients_added, summary.clients_merged
+    );
+    println!(
+        "  Profiles: {} added, {} updated",
+        summary.profiles_added, summary.profiles_updated
+    );
+    if summary.passwords.is_empty() {
+        println!("  Passwords: none in the export");
+    } else {
+        println!("  Passwords: {} saved ({})", summary.passwords.len(), store);
+    }
+
+    Ok(())
+}
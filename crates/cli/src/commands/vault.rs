@@ -0,0 +1,43 @@
+use anyhow::bail;
+use libalembic::credentials::{
+    CredentialStoreKind, VAULT_PASSPHRASE_ENV, is_vault_unlocked, unlock_vault, vault_exists,
+};
+
+/// Prompt for an account password without echoing it
+pub fn prompt_password() -> anyhow::Result<String> {
+    let password = rpassword::prompt_password("Password: ")?;
+
+    if password.is_empty() {
+        bail!("Password cannot be empty");
+    }
+
+    Ok(password)
+}
+
+/// Unlock the vault if `store` needs it, prompting for the passphrase unless
+/// it was given in the environment. A new vault is created on first use.
+pub fn unlock_vault_for(store: CredentialStoreKind) -> anyhow::Result<()> {
+    if store != CredentialStoreKind::Vault || is_vault_unlocked() {
+        return Ok(());
+    }
+
+    if let Ok(passphrase) = std::env::var(VAULT_PASSPHRASE_ENV) {
+        return unlock_vault(&passphrase);
+    }
+
+    if vault_exists() {
+        let passphrase = rpassword::prompt_password("Vault passphrase: ")?;
+        return unlock_vault(&passphrase);
+    }
+
+    println!("Creating a new password vault.");
+    let passphrase = rpassword::prompt_password("New vault passphrase: ")?;
+    if passphrase.is_empty() {
+        bail!("Vault passphrase cannot be empty");
+    }
+    if rpassword::prompt_password("Confirm vault passphrase: ")? != passphrase {
+        bail!("Passphrases don't match");
+    }
+
+    unlock_vault(&passphrase)
+}
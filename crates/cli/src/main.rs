@@ -1,5 +1,7 @@
 mod commands;
 
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
 #[cfg(debug_assertions)]
@@ -19,6 +21,10 @@ const ABOUT: &str = concat!("Alembic ", env!("CARGO_PKG_VERSION"));
 #[command(about = ABOUT)]
 #[command(version = VERSION)]
 struct Cli {
+    /// Use this settings file instead of the default (or ALEMBIC_CONFIG)
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -73,13 +79,33 @@ enum Commands {
     /// Run cork to find and inject into running acclient.exe
     Inject,
 
+    /// Check the selected client, DLL, server and account, and everything
+    /// else a launch needs
+    Doctor,
+
+    /// Tools for developing Alembic and its hooks
+    Dev {
+        #[command(subcommand)]
+        command: DevCommands,
+    },
+
+    /// Manage launch profiles (saved client, DLL, server and account combinations)
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+
     /// Launch using saved settings with optional overrides
     Launch {
-        /// Server name to use (overrides selected server in settings)
+        /// Profile index, ID or name to launch instead of the current selections
+        #[arg(long, conflicts_with_all = ["server", "account"])]
+        profile: Option<String>,
+
+        /// Server index, ID or name to use (overrides selected server in settings)
         #[arg(long)]
         server: Option<String>,
 
-        /// Account username to use (overrides selected account in settings)
+        /// Account index, ID or username to use (overrides selected account in settings)
         #[arg(long)]
         account: Option<String>,
     },
@@ -104,55 +130,178 @@ enum ConfigCommands {
         #[command(subcommand)]
         command: ServerCommands,
     },
+
+    /// Export servers, accounts, clients and profiles to share with another machine
+    Export {
+        /// File to write the export to (prints to stdout if not given)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Include account passwords in plain text
+        #[arg(long)]
+        include_passwords: bool,
+
+        /// Leave out clients and profiles, whose paths are usually machine-specific
+        #[arg(long)]
+        skip_clients: bool,
+    },
+
+    /// Merge an export, or another launcher's servers and accounts, into the
+    /// current configuration
+    Import {
+        /// Export file to import, or with --from, the other launcher's
+        /// settings directory or files (found automatically if not given)
+        #[arg(required_unless_present = "from")]
+        path: Option<PathBuf>,
+
+        /// Import from another launcher instead: thwarg or mag
+        #[arg(long, value_name = "LAUNCHER")]
+        from: Option<String>,
+
+        /// Where to store imported passwords: keyring (default), vault or plaintext
+        #[arg(long)]
+        store: Option<String>,
+
+        /// Server to add accounts to when the other launcher doesn't tie them
+        /// to one (by index, ID or name)
+        #[arg(long, requires = "from")]
+        server: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DevCommands {
+    /// Find a byte pattern in an executable's code, to build and check
+    /// signatures for hooks
+    Sigscan {
+        /// Path to the executable, like acclient.exe
+        exe: PathBuf,
+
+        /// Hex bytes separated by spaces, with ?? for any byte (e.g., "E8 ?? ?? ?? ?? 8B 0D")
+        pattern: String,
+
+        /// Only scan this section (default: all code sections)
+        #[arg(long)]
+        section: Option<String>,
+
+        /// Follow a 32-bit relative displacement this many bytes into each
+        /// match, like the operand of a call
+        #[arg(long, value_name = "OFFSET", conflicts_with = "abs32")]
+        rel32: Option<usize>,
+
+        /// Where the instruction with the displacement ends, in bytes into
+        /// the match (default: right after the displacement)
+        #[arg(long, value_name = "OFFSET", requires = "rel32")]
+        instruction_end: Option<usize>,
+
+        /// Follow a 32-bit absolute address this many bytes into each match
+        #[arg(long, value_name = "OFFSET")]
+        abs32: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileCommands {
+    /// Add a profile (or update the profile with the same name). Anything not
+    /// specified is taken from the current selections.
+    Add {
+        /// Profile name
+        name: String,
+
+        /// Client index, ID or name (from 'client list')
+        #[arg(long)]
+        client: Option<String>,
+
+        /// DLL index, ID or path of the client (from 'client dll list')
+        #[arg(long, conflicts_with = "no_dll")]
+        dll: Option<String>,
+
+        /// Launch without injecting a DLL
+        #[arg(long)]
+        no_dll: bool,
+
+        /// Server index, ID or name (from 'server list')
+        #[arg(long)]
+        server: Option<String>,
+
+        /// Account index, ID or username (from 'account list')
+        #[arg(long)]
+        account: Option<String>,
+    },
+
+    /// List profiles
+    List,
+
+    /// Launch a profile
+    Launch {
+        /// Index, ID or name of the profile to launch (from 'profile list')
+        profile: String,
+    },
+
+    /// Select a profile's client, DLL, server and account
+    Select {
+        /// Index, ID or name of the profile to select (from 'profile list')
+        profile: String,
+    },
+
+    /// Remove a profile
+    Remove {
+        /// Index, ID or name of the profile to remove (from 'profile list')
+        profile: String,
+    },
 }
 
 #[derive(Subcommand)]
 enum AccountCommands {
     /// Add a new account
     Add {
-        /// Server index (from 'server list')
+        /// Server index, ID or name (from 'server list')
         #[arg(long)]
-        server: usize,
+        server: String,
 
         /// Account username
         #[arg(long)]
         username: String,
 
-        /// Account password
+        /// Account password (prompted for if not given)
         #[arg(long)]
-        password: String,
+        password: Option<String>,
+
+        /// Where to store the password: keyring (default), vault or plaintext
+        #[arg(long)]
+        store: Option<String>,
     },
 
     /// List accounts
     List {
-        /// Filter by server index (optional)
+        /// Filter by server index, ID or name (optional)
         #[arg(long)]
-        server: Option<usize>,
+        server: Option<String>,
     },
 
-    /// Select an account by index
+    /// Select an account
     Select {
-        /// Index of the account to select (from 'account list')
-        index: usize,
+        /// Index, ID or username of the account to select (from 'account list')
+        account: String,
     },
 
     /// Clear the selected account
     Reset,
 
-    /// Remove an account by index
+    /// Remove an account
     Remove {
-        /// Index of the account to remove (from 'account list')
-        index: usize,
+        /// Index, ID or username of the account to remove (from 'account list')
+        account: String,
     },
 
     /// Edit an existing account (only specified fields are updated)
     Edit {
-        /// Index of the account to edit (from 'account list')
-        index: usize,
+        /// Index, ID or username of the account to edit (from 'account list')
+        account: String,
 
-        /// Server index (from 'server list')
+        /// Server index, ID or name (from 'server list')
         #[arg(long)]
-        server: Option<usize>,
+        server: Option<String>,
 
         /// Account username
         #[arg(long)]
@@ -161,6 +310,10 @@ enum AccountCommands {
         /// Account password
         #[arg(long)]
         password: Option<String>,
+
+        /// Move the password to another store: keyring, vault or plaintext
+        #[arg(long)]
+        store: Option<String>,
     },
 }
 
@@ -192,31 +345,31 @@ enum ClientCommands {
     /// List configured clients (brief)
     List,
 
-    /// Select a client by index
+    /// Select a client
     Select {
-        /// Index of the client to select (from 'client list')
-        index: usize,
+        /// Index, ID or name of the client to select (from 'client list')
+        client: String,
     },
 
     /// Clear the selected client
     Reset,
 
-    /// Remove a client by index
+    /// Remove a client
     Remove {
-        /// Index of the client to remove (from 'client list')
-        index: usize,
+        /// Index, ID or name of the client to remove (from 'client list')
+        client: String,
     },
 
     /// Show detailed client configuration
     Show {
-        /// Index of the client to show (from 'client list')
-        index: usize,
+        /// Index, ID or name of the client to show (from 'client list')
+        client: String,
     },
 
     /// Edit an existing client configuration (only specified fields are updated)
     Edit {
-        /// Index of the client to edit (from 'client list')
-        index: usize,
+        /// Index, ID or name of the client to edit (from 'client list')
+        client: String,
 
         /// New name for the client
         #[arg(long)]
@@ -248,13 +401,38 @@ enum ClientCommands {
     },
 
     /// Scan for installed clients and wine prefixes
-    Scan,
+    Scan {
+        /// Also scan this directory, which may be a wine prefix or contain
+        /// prefixes (can be given more than once)
+        #[arg(long = "root", value_name = "DIR")]
+        roots: Vec<PathBuf>,
+
+        /// Remember the --root directories for future scans
+        #[arg(long, requires = "roots")]
+        save_roots: bool,
+
+        /// Also look for acclient.exe at paths matching this glob, like
+        /// 'Games/*/acclient.exe'. Relative patterns are matched under each
+        /// prefix's drive_c (can be given more than once)
+        #[arg(long = "client-glob", value_name = "PATTERN")]
+        client_globs: Vec<String>,
+
+        /// Also pick up DLLs matching this glob, matched like --client-glob.
+        /// Inject.dll is taken to be Decal and anything else Alembic (can be
+        /// given more than once)
+        #[arg(long = "dll-glob", value_name = "PATTERN")]
+        dll_globs: Vec<String>,
+
+        /// Remember the --client-glob and --dll-glob patterns for future scans
+        #[arg(long)]
+        save_globs: bool,
+    },
 
     /// Manage DLL configurations for a client
     Dll {
-        /// Index of the client (from 'client list')
+        /// Index, ID or name of the client (from 'client list')
         #[arg(long)]
-        client: usize,
+        client: String,
 
         #[command(subcommand)]
         command: ClientDllCommands,
@@ -281,31 +459,31 @@ enum ClientDllCommands {
     /// List DLLs for a client
     List,
 
-    /// Select a DLL for a client by index
+    /// Select a DLL for a client
     Select {
-        /// Index of the DLL to select
-        index: usize,
+        /// Index, ID or path of the DLL to select
+        dll: String,
     },
 
     /// Clear the selected DLL for a client
     Reset,
 
-    /// Remove a DLL from a client by index
+    /// Remove a DLL from a client
     Remove {
-        /// Index of the DLL to remove
-        index: usize,
+        /// Index, ID or path of the DLL to remove
+        dll: String,
     },
 
     /// Show detailed DLL configuration for a client
     Show {
-        /// Index of the DLL to show
-        index: usize,
+        /// Index, ID or path of the DLL to show
+        dll: String,
     },
 
     /// Edit an existing DLL configuration for a client
     Edit {
-        /// Index of the DLL to edit
-        index: usize,
+        /// Index, ID or path of the DLL to edit
+        dll: String,
 
         /// DLL type (alembic or decal)
         #[arg(long = "type")]
@@ -322,6 +500,18 @@ enum ClientDllCommands {
 
     /// Scan for installed DLLs (Alembic, Decal)
     Scan,
+
+    /// List the Decal plugins, services and filters in the client's Wine
+    /// prefix, or enable and disable them
+    Plugins {
+        /// Index, CLSID or name of a plugin to enable (can be given more than once)
+        #[arg(long, value_name = "PLUGIN")]
+        enable: Vec<String>,
+
+        /// Index, CLSID or name of a plugin to disable (can be given more than once)
+        #[arg(long, value_name = "PLUGIN")]
+        disable: Vec<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -344,25 +534,25 @@ enum ServerCommands {
     /// List servers
     List,
 
-    /// Select a server by index
+    /// Select a server
     Select {
-        /// Index of the server to select (from 'server list')
-        index: usize,
+        /// Index, ID or name of the server to select (from 'server list')
+        server: String,
     },
 
     /// Clear the selected server
     Reset,
 
-    /// Remove a server by index
+    /// Remove a server
     Remove {
-        /// Index of the server to remove (from 'server list')
-        index: usize,
+        /// Index, ID or name of the server to remove (from 'server list')
+        server: String,
     },
 
     /// Edit an existing server (only specified fields are updated)
     Edit {
-        /// Index of the server to edit (from 'server list')
-        index: usize,
+        /// Index, ID or name of the server to edit (from 'server list')
+        server: String,
 
         /// Server name
         #[arg(long)]
@@ -388,6 +578,10 @@ fn parse_key_val(s: &str) -> Result<(String, String), String> {
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
+    if let Some(config) = cli.config {
+        libalembic::settings::set_settings_file_path(config)?;
+    }
+
     match cli.command {
         Commands::Config { command } => match command {
             ConfigCommands::Account { command } => match command {
@@ -395,17 +589,19 @@ fn main() -> anyhow::Result<()> {
                     server,
                     username,
                     password,
-                } => commands::account::account_add(server, username, password),
+                    store,
+                } => commands::account::account_add(server, username, password, store),
                 AccountCommands::List { server } => commands::account::account_list(server),
-                AccountCommands::Select { index } => commands::account::account_select(index),
+                AccountCommands::Select { account } => commands::account::account_select(account),
                 AccountCommands::Reset => commands::account::account_reset(),
-                AccountCommands::Remove { index } => commands::account::account_remove(index),
+                AccountCommands::Remove { account } => commands::account::account_remove(account),
                 AccountCommands::Edit {
-                    index,
+                    account,
                     server,
                     username,
                     password,
-                } => commands::account::account_edit(index, server, username, password),
+                    store,
+                } => commands::account::account_edit(account, server, username, password, store),
             },
             ConfigCommands::Client { command } => match command {
                 ClientCommands::Add {
@@ -422,12 +618,12 @@ fn main() -> anyhow::Result<()> {
                     env_vars,
                 ),
                 ClientCommands::List => commands::client::client_list(),
-                ClientCommands::Select { index } => commands::client::client_select(index),
+                ClientCommands::Select { client } => commands::client::client_select(client),
                 ClientCommands::Reset => commands::client::client_reset(),
-                ClientCommands::Remove { index } => commands::client::client_remove(index),
-                ClientCommands::Show { index } => commands::client::client_show(index),
+                ClientCommands::Remove { client } => commands::client::client_remove(client),
+                ClientCommands::Show { client } => commands::client::client_show(client),
                 ClientCommands::Edit {
-                    index,
+                    client,
                     name,
                     client_path,
                     wrapper_program,
@@ -436,7 +632,7 @@ fn main() -> anyhow::Result<()> {
                     env_vars,
                     unset_env_vars,
                 } => commands::client::client_edit(
-                    index,
+                    client,
                     name,
                     client_path,
                     wrapper_program,
@@ -445,7 +641,19 @@ fn main() -> anyhow::Result<()> {
                     env_vars,
                     unset_env_vars,
                 ),
-                ClientCommands::Scan => commands::scan::client_scan(),
+                ClientCommands::Scan {
+                    roots,
+                    save_roots,
+                    client_globs,
+                    dll_globs,
+                    save_globs,
+                } => commands::scan::client_scan(
+                    roots,
+                    save_roots,
+                    client_globs,
+                    dll_globs,
+                    save_globs,
+                ),
                 ClientCommands::Dll { client, command } => match command {
                     ClientDllCommands::Add {
                         dll_type,
@@ -453,29 +661,30 @@ fn main() -> anyhow::Result<()> {
                         startup_function,
                     } => commands::dll::client_dll_add(client, dll_type, path, startup_function),
                     ClientDllCommands::List => commands::dll::client_dll_list(client),
-                    ClientDllCommands::Select { index } => {
-                        commands::dll::client_dll_select(client, index)
+                    ClientDllCommands::Select { dll } => {
+                        commands::dll::client_dll_select(client, dll)
                     }
                     ClientDllCommands::Reset => commands::dll::client_dll_reset(client),
-                    ClientDllCommands::Remove { index } => {
-                        commands::dll::client_dll_remove(client, index)
-                    }
-                    ClientDllCommands::Show { index } => {
-                        commands::dll::client_dll_show(client, index)
+                    ClientDllCommands::Remove { dll } => {
+                        commands::dll::client_dll_remove(client, dll)
                     }
+                    ClientDllCommands::Show { dll } => commands::dll::client_dll_show(client, dll),
                     ClientDllCommands::Edit {
-                        index,
+                        dll,
                         dll_type,
                         path,
                         startup_function,
                     } => commands::dll::client_dll_edit(
                         client,
-                        index,
+                        dll,
                         dll_type,
                         path,
                         startup_function,
                     ),
                     ClientDllCommands::Scan => commands::dll::client_dll_scan(client),
+                    ClientDllCommands::Plugins { enable, disable } => {
+                        commands::dll::client_dll_plugins(client, enable, disable)
+                    }
                 },
             },
             ConfigCommands::Server { command } => match command {
@@ -485,15 +694,34 @@ fn main() -> anyhow::Result<()> {
                     port,
                 } => commands::server::server_add(name, hostname, port),
                 ServerCommands::List => commands::server::server_list(),
-                ServerCommands::Select { index } => commands::server::server_select(index),
+                ServerCommands::Select { server } => commands::server::server_select(server),
                 ServerCommands::Reset => commands::server::server_reset(),
-                ServerCommands::Remove { index } => commands::server::server_remove(index),
+                ServerCommands::Remove { server } => commands::server::server_remove(server),
                 ServerCommands::Edit {
-                    index,
+                    server,
                     name,
                     hostname,
                     port,
-                } => commands::server::server_edit(index, name, hostname, port),
+                } => commands::server::server_edit(server, name, hostname, port),
+            },
+            ConfigCommands::Export {
+                output,
+                include_passwords,
+                skip_clients,
+            } => commands::transfer::config_export(output, include_passwords, skip_clients),
+            ConfigCommands::Import {
+                path,
+                from,
+                store,
+                server,
+            } => match from {
+                Some(launcher) => {
+                    commands::transfer::config_import_launcher(launcher, path, store, server)
+                }
+                None => commands::transfer::config_import(
+                    path.expect("clap requires a path without --from"),
+                    store,
+                ),
             },
         },
         Commands::Exec {
@@ -517,7 +745,36 @@ fn main() -> anyhow::Result<()> {
             wine_prefix,
             env_vars,
         ),
-        Commands::Launch { server, account } => commands::launch::preset_launch(server, account),
+        Commands::Launch {
+            profile,
+            server,
+            account,
+        } => commands::launch::preset_launch(profile, server, account),
+        Commands::Profile { command } => match command {
+            ProfileCommands::Add {
+                name,
+                client,
+                dll,
+                no_dll,
+                server,
+                account,
+            } => commands::profile::profile_add(name, client, dll, no_dll, server, account),
+            ProfileCommands::List => commands::profile::profile_list(),
+            ProfileCommands::Launch { profile } => commands::launch::profile_launch(profile),
+            ProfileCommands::Select { profile } => commands::profile::profile_select(profile),
+            ProfileCommands::Remove { profile } => commands::profile::profile_remove(profile),
+        },
         Commands::Inject => commands::inject::inject(),
+        Commands::Doctor => commands::doctor::doctor(),
+        Commands::Dev { command } => match command {
+            DevCommands::Sigscan {
+                exe,
+                pattern,
+                section,
+                rel32,
+                instruction_end,
+                abs32,
+            } => commands::dev::sigscan(exe, pattern, section, rel32, instruction_end, abs32),
+        },
     }
 }
@@ -7,6 +7,7 @@ compile_error!("cork can only be built for Windows targets");
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use libalembic::launcher::session::{CLIENT_EXIT_PREFIX, CLIENT_PID_PREFIX};
 use std::path::Path;
 
 #[cfg(not(all(target_os = "windows", target_env = "msvc")))]
@@ -41,9 +42,18 @@ enum Commands {
         #[arg(long)]
         account: String,
 
-        /// Account password
+        /// Account password. Prefer --password-stdin, since arguments can be
+        /// seen by other users on the same machine.
+        #[arg(
+            long,
+            required_unless_present = "password_stdin",
+            conflicts_with = "password_stdin"
+        )]
+        password: Option<String>,
+
+        /// Read the account password from the first line of stdin
         #[arg(long)]
-        password: String,
+        password_stdin: bool,
 
         /// Path to DLL to inject (e.g., C:\\Program Files (x86)\\Decal 3.0\\Inject.dll)
         #[arg(long)]
@@ -52,6 +62,11 @@ enum Commands {
         /// Optional function name to execute in the DLL after injection (e.g., "DecalStartup")
         #[arg(long)]
         function: Option<String>,
+
+        /// Keep running until the client exits, then report its exit code
+        /// and exit with it
+        #[arg(long)]
+        wait: bool,
     },
 }
 
@@ -64,7 +79,7 @@ fn launch_client_with_injection(
     password: &str,
     dll_path: Option<&str>,
     dll_function: Option<&str>,
-) -> Result<()> {
+) -> Result<libalembic::injector::ClientProcess> {
     if dll_path.is_some() {
         println!("Cork: Launching AC client with DLL injection");
     } else {
@@ -113,25 +128,38 @@ fn launch_client_with_injection(
         }
     }
 
-    // Build the command line arguments
+    // Build the command line arguments. acclient only accepts credentials on
+    // its command line, so this is the one place the password has to appear.
     let arguments = format!("-h {} -p {} -a {} -v {}", hostname, port, account, password);
 
     println!("\nStarting process...");
 
-    if let Some(dll) = dll_path {
-        libalembic::injector::launch_suspended_inject_and_resume(
+    let process = if let Some(dll) = dll_path {
+        let process = libalembic::injector::launch_suspended_inject_and_resume(
             client_path,
             &arguments,
             dll,
             dll_function,
         )?;
         println!("Client launched and DLL injected successfully!");
+        process
     } else {
-        libalembic::injector::launch_without_injection(client_path, &arguments)?;
+        let process = libalembic::injector::launch_without_injection(client_path, &arguments)?;
         println!("Client launched successfully!");
-    }
+        process
+    };
+    println!("{}{}", CLIENT_PID_PREFIX, process.pid());
 
-    Ok(())
+    Ok(process)
+}
+
+/// Wait for the client to exit and exit with its code, so whatever launched
+/// cork can tell how the client exited
+#[cfg(target_os = "windows")]
+fn wait_for_client(process: libalembic::injector::ClientProcess) -> Result<()> {
+    let code = process.wait()?;
+    println!("{}0x{:08X}", CLIENT_EXIT_PREFIX, code);
+    std::process::exit(code as i32);
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -147,6 +175,24 @@ fn launch_client_with_injection(
     anyhow::bail!("Cork client launching is only supported on Windows");
 }
 
+#[cfg(not(target_os = "windows"))]
+fn wait_for_client(_process: ()) -> Result<()> {
+    anyhow::bail!("Cork client launching is only supported on Windows");
+}
+
+/// Read a password written by the launcher, without the trailing newline
+fn read_password_stdin() -> Result<String> {
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+
+    let password = line.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        anyhow::bail!("ERROR: No password was received on stdin");
+    }
+
+    Ok(password.to_string())
+}
+
 fn main() -> Result<()> {
     let args = Args::parse();
 
@@ -157,16 +203,31 @@ fn main() -> Result<()> {
             port,
             account,
             password,
+            password_stdin: _,
             dll,
             function,
-        } => launch_client_with_injection(
-            &client,
-            &hostname,
-            &port,
-            &account,
-            &password,
-            dll.as_deref(),
-            function.as_deref(),
-        ),
+            wait,
+        } => {
+            let password = match password {
+                Some(password) => password,
+                None => read_password_stdin()?,
+            };
+
+            let process = launch_client_with_injection(
+                &client,
+                &hostname,
+                &port,
+                &account,
+                &password,
+                dll.as_deref(),
+                function.as_deref(),
+            )?;
+
+            if wait {
+                wait_for_client(process)?;
+            }
+
+            Ok(())
+        }
     }
 }
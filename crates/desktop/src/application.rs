@@ -1,11 +1,12 @@
 use std::{
     sync::{Arc, Mutex},
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use crate::{
     backend::{Backend, ChatMessage, LogEntry, PacketInfo},
     fetching::{BackgroundFetchRequest, BackgroundFetchUpdateMessage},
+    settings_watcher::spawn_settings_watcher,
     widgets::{about::About, settings::Settings, tabs::TabContainer, wizard::Wizard},
 };
 
@@ -50,7 +51,7 @@ impl Application {
         // Inject a new, shared Backend object into the egui_ctx (Context)
         let backend: Arc<Mutex<Backend>> = Arc::new(Mutex::new(Backend::new()));
         cc.egui_ctx
-            .data_mut(|data| data.insert_persisted(egui::Id::new("backend"), backend));
+            .data_mut(|data| data.insert_persisted(egui::Id::new("backend"), backend.clone()));
 
         // Inject a new, shared Settings object into the egui_ctx (Context)
         let alembic_settings: Arc<Mutex<AlembicSettings>> =
@@ -60,8 +61,14 @@ impl Application {
             Err(error) => eprintln!("Error loading settings: {error}"),
         }
 
-        cc.egui_ctx
-            .data_mut(|data| data.insert_persisted(egui::Id::new("settings"), alembic_settings));
+        cc.egui_ctx.data_mut(|data| {
+            data.insert_persisted(egui::Id::new("settings"), alembic_settings.clone())
+        });
+
+        // Reload settings when they're changed by another process
+        if let Err(error) = spawn_settings_watcher(cc.egui_ctx.clone(), alembic_settings, backend) {
+            eprintln!("Error watching settings for changes: {error}");
+        }
 
         // Background data fetching
         cc.egui_ctx.data_mut(|data| {
@@ -130,6 +137,14 @@ impl Application {
     }
 
     fn ui(&mut self, ctx: &egui::Context) {
+        // Keep the launched client's status live while it's running
+        if let Some(backend_ref) =
+            ctx.data_mut(|data| data.get_persisted::<Arc<Mutex<Backend>>>(egui::Id::new("backend")))
+            && backend_ref.lock().unwrap().poll_session()
+        {
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+
         let mut current_app_page = AppPage::Wizard;
 
         ctx.memory_mut(|mem| {
@@ -249,6 +264,59 @@ impl Application {
                     });
                 });
         }
+
+        self.settings_conflict_ui(ctx);
+    }
+
+    fn settings_conflict_ui(&mut self, ctx: &egui::Context) {
+        let Some(backend_ref) = ctx
+            .data_mut(|data| data.get_persisted::<Arc<Mutex<Backend>>>(egui::Id::new("backend")))
+        else {
+            return;
+        };
+
+        if !backend_ref.lock().unwrap().settings_conflict {
+            return;
+        }
+
+        egui::Window::new("Settings Changed")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(320.0);
+
+                ui.label("Your settings were changed by another program while you had unsaved changes here. Which version do you want to keep?");
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    let reload = ui.button("Reload from disk").clicked();
+                    let keep_mine = ui.button("Keep my changes").clicked();
+
+                    if !reload && !keep_mine {
+                        return;
+                    }
+
+                    if let Some(settings) = ctx.data_mut(|data| {
+                        data.get_persisted::<Arc<Mutex<AlembicSettings>>>(egui::Id::new(
+                            "settings",
+                        ))
+                    }) {
+                        let mut settings = settings.lock().unwrap();
+                        let result = if reload {
+                            settings.load()
+                        } else {
+                            settings.save()
+                        };
+
+                        if let Err(error) = result {
+                            eprintln!("Error resolving settings conflict: {error}");
+                        }
+                    }
+
+                    backend_ref.lock().unwrap().settings_conflict = false;
+                });
+            });
     }
 }
 
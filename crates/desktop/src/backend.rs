@@ -1,5 +1,6 @@
-use std::{fmt::Display, num::NonZero};
+use std::fmt::Display;
 
+use libalembic::launcher::session::{LaunchSession, SessionState};
 use ringbuffer::AllocRingBuffer;
 use serde::Deserialize;
 use serde_with::serde_as;
@@ -87,12 +88,6 @@ pub struct CommunityServers {
     pub servers: Vec<CommunityServersServerItem>,
 }
 
-#[derive(Clone)]
-pub struct Client {
-    #[allow(dead_code)]
-    pub pid: NonZero<u32>,
-}
-
 #[derive(Clone)]
 pub struct NetworkStatistics {
     pub incoming_count: usize,
@@ -122,11 +117,13 @@ pub struct AppModal {
 
 pub struct Backend {
     pub current_modal: Option<AppModal>,
+    /// config.json changed on disk while the GUI had unsaved edits
+    pub settings_conflict: bool,
     pub status_message: Option<String>,
     pub news: FetchWrapper<News>,
     pub community_servers: FetchWrapper<CommunityServers>,
-    pub client: Option<Client>,
-    pub is_injected: bool,
+    /// The launched client, until it exits
+    pub session: Option<LaunchSession>,
     pub logs: AllocRingBuffer<LogEntry>,
     pub packets_incoming: AllocRingBuffer<PacketInfo>,
     pub packets_outgoing: AllocRingBuffer<PacketInfo>,
@@ -138,11 +135,11 @@ impl Backend {
     pub fn new() -> Self {
         Self {
             current_modal: None,
+            settings_conflict: false,
             status_message: None,
             news: FetchWrapper::NotStarted,
             community_servers: FetchWrapper::NotStarted,
-            client: None,
-            is_injected: false,
+            session: None,
             logs: AllocRingBuffer::<LogEntry>::new(10000),
             packets_incoming: AllocRingBuffer::<PacketInfo>::new(10000),
             packets_outgoing: AllocRingBuffer::<PacketInfo>::new(10000),
@@ -150,4 +147,30 @@ impl Backend {
             statistics: Statistics::default(),
         }
     }
+
+    /// Check on the launched client and show how it's doing in the status
+    /// bar. Returns whether it's still running.
+    pub fn poll_session(&mut self) -> bool {
+        let Some(session) = &mut self.session else {
+            return false;
+        };
+
+        match session.state() {
+            SessionState::Exited(report) => {
+                if report.crash().is_some() {
+                    self.current_modal = Some(AppModal {
+                        title: "Client Crashed".to_string(),
+                        text: report.to_string(),
+                    });
+                }
+                self.status_message = Some(report.to_string());
+                self.session = None;
+                false
+            }
+            state => {
+                self.status_message = Some(state.to_string());
+                true
+            }
+        }
+    }
 }
@@ -3,9 +3,9 @@ pub fn try_launch(
     server_info: &Option<libalembic::settings::ServerInfo>,
     account_info: &Option<libalembic::settings::Account>,
     inject_config: &Option<libalembic::inject_config::InjectConfig>,
-) -> anyhow::Result<std::num::NonZero<u32>> {
+) -> anyhow::Result<libalembic::launcher::session::LaunchSession> {
     use anyhow::bail;
-    use libalembic::launcher::{Launcher, traits::ClientLauncher};
+    use libalembic::launcher::{Launcher, session::LaunchSession, traits::ClientLauncher};
 
     // Validate arguments
     let client_config = match client_config {
@@ -27,6 +27,12 @@ pub fn try_launch(
         );
     }
 
+    let result = client_config.validate(inject_config.as_ref());
+    if !result.is_valid() {
+        let errors: Vec<_> = result.errors().map(|e| e.to_string()).collect();
+        bail!("{}", errors.join("\n"));
+    }
+
     let server_info = match server_info {
         Some(info) => info.clone(),
         None => bail!("No server selected."),
@@ -37,14 +43,13 @@ pub fn try_launch(
         None => bail!("No account selected."),
     };
 
-    let mut launcher = Launcher::new(
+    let launcher = Launcher::new(
         client_config,
         inject_config.clone(),
         server_info,
         account_info,
     );
-    let pid = launcher.launch()?;
-    // TODO: How to handle deinject. i.e., store the launch app-wide
 
-    Ok(pid)
+    // The session ejects through the launcher once the client exits
+    Ok(LaunchSession::launch(launcher)?)
 }
@@ -4,11 +4,13 @@ mod application;
 mod backend;
 mod fetching;
 mod launch;
+mod settings_watcher;
 mod simulator;
 mod widgets;
 
 use std::{
     net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
     sync::Arc,
     thread,
     time::Duration,
@@ -16,6 +18,7 @@ use std::{
 
 use application::Application;
 use backend::News;
+use clap::Parser;
 use eframe::egui::IconData;
 use fetching::{
     BackgroundFetchRequest, BackgroundFetchUpdateMessage, FetchWrapper,
@@ -24,7 +27,7 @@ use fetching::{
 use futures::{StreamExt, future};
 use libalembic::{
     msg::{client_server::ClientServerMessage, server_gui::ServerGuiMessage},
-    rpc::{HelloServer, World, spawn},
+    rpc::{HelloServer, RPC_PORT, World, spawn},
 };
 use tarpc::{
     server::{self, Channel},
@@ -35,9 +38,24 @@ use tokio::sync::{
     mpsc::{channel, error::TryRecvError},
 };
 
+#[derive(Parser)]
+#[command(name = "alembic-desktop")]
+struct Args {
+    /// Use this settings file instead of the default (or ALEMBIC_CONFIG)
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+}
+
 fn main() -> eframe::Result {
     env_logger::init();
 
+    let args = Args::parse();
+    if let Some(config) = args.config
+        && let Err(error) = libalembic::settings::set_settings_file_path(config)
+    {
+        log::error!("Ignoring --config: {error}");
+    }
+
     // Channel: Client (i.e., plugin) to Server
     let (client_server_tx, client_server_rx) = channel::<ClientServerMessage>(32);
     let client_server_tx_ref = Arc::new(Mutex::new(client_server_tx));
@@ -106,7 +124,7 @@ fn main() -> eframe::Result {
     // tarpc
     let runtime = tokio::runtime::Runtime::new().unwrap();
     runtime.spawn(async move {
-        let addr = (IpAddr::V4(Ipv4Addr::LOCALHOST), 5000);
+        let addr = (IpAddr::V4(Ipv4Addr::LOCALHOST), RPC_PORT);
 
         let listener = tarpc::serde_transport::tcp::listen(&addr, Json::default)
             .await
@@ -0,0 +1,84 @@
+//! Picks up changes made to config.json by other processes (e.g., the CLI) so
+//! they show up in the GUI without a restart.
+
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use eframe::egui;
+use libalembic::settings::{AlembicSettings, get_settings_file_path};
+use serde_json::Value;
+
+use crate::backend::Backend;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+fn read_settings_value(path: &Path) -> Option<Value> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Poll config.json in the background and reload the shared settings when it
+/// changes on disk.
+///
+/// Settings are compared as parsed JSON so formatting and map ordering don't
+/// count as changes. If the GUI has edits that haven't been saved when the
+/// file changes, the reload is held back and `Backend::settings_conflict` is
+/// set so the user can pick which copy to keep.
+pub fn spawn_settings_watcher(
+    ctx: egui::Context,
+    settings: Arc<Mutex<AlembicSettings>>,
+    backend: Arc<Mutex<Backend>>,
+) -> anyhow::Result<()> {
+    let path = get_settings_file_path()?;
+
+    // What we last saw on disk, which is what the GUI is showing until the
+    // user edits something
+    let mut last_seen = read_settings_value(&path);
+
+    thread::Builder::new()
+        .name("settings-watcher".to_string())
+        .spawn(move || {
+            loop {
+                thread::sleep(POLL_INTERVAL);
+
+                let Some(on_disk) = read_settings_value(&path) else {
+                    continue;
+                };
+
+                if last_seen.as_ref() == Some(&on_disk) {
+                    continue;
+                }
+
+                let mut settings = settings.lock().unwrap();
+                // Leave out ALEMBIC_* overrides, which are never saved
+                let in_memory = settings.to_saved_value().ok();
+
+                if in_memory.as_ref() == Some(&on_disk) {
+                    // This was our own save
+                } else if in_memory == last_seen {
+                    // Nothing unsaved in the GUI, so take the new file
+                    if let Err(error) = settings.load() {
+                        eprintln!("Error reloading settings: {error}");
+                        continue;
+                    }
+
+                    ctx.request_repaint();
+                } else {
+                    // Leave last_seen alone so this keeps being flagged until
+                    // the user resolves it
+                    backend.lock().unwrap().settings_conflict = true;
+                    ctx.request_repaint();
+                    continue;
+                }
+
+                last_seen = Some(on_disk);
+            }
+        })?;
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use libalembic::rpc::WorldClient;
+use libalembic::rpc::{RPC_PORT, WorldClient};
 use rand::RngExt;
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -23,7 +23,7 @@ async fn main() -> anyhow::Result<()> {
 
     let mut rng = rand::rng();
 
-    let addr: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 5000);
+    let addr: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), RPC_PORT);
     let transport = tarpc::serde_transport::tcp::connect(&addr, Json::default);
     let wc: WorldClient =
         WorldClient::new(client::Config::default(), transport.await.expect("oops")).spawn();
@@ -5,7 +5,7 @@ use std::{
 
 use eframe::egui::{self, Id, Response, ScrollArea, Ui, Widget};
 use egui_extras::{Column, TableBuilder};
-use libalembic::settings::{AlembicSettings, ServerInfo};
+use libalembic::settings::{AlembicSettings, ServerInfo, new_id};
 
 use crate::{
     backend::Backend,
@@ -168,22 +168,23 @@ impl Widget for &mut AccountsCommunityServersTab {
                                                     {
                                                         let mut settings = s.lock().unwrap();
 
-                                                        let to_import = ServerInfo {
-                                                            name: server.name.clone(),
-                                                            hostname: server.server_host.clone(),
-                                                            port: server.server_port.clone(),
-                                                        };
-
-                                                        match settings.servers.iter().position(|s| s.name == server.name) {
-                                                            Some(idx) => {
-                                                                settings.servers[idx] = to_import;
-                                                            },
-                                                            None =>  {
-                                                                settings.servers.push(to_import);
+                                                        let _ = settings.modify(|settings| {
+                                                            match settings.servers.iter_mut().find(|s| s.name == server.name) {
+                                                                // Update in place so accounts on this server stay attached
+                                                                Some(existing) => {
+                                                                    existing.hostname = server.server_host.clone();
+                                                                    existing.port = server.server_port.clone();
+                                                                },
+                                                                None =>  {
+                                                                    settings.servers.push(ServerInfo {
+                                                                        id: new_id(),
+                                                                        name: server.name.clone(),
+                                                                        hostname: server.server_host.clone(),
+                                                                        port: server.server_port.clone(),
+                                                                    });
+                                                                }
                                                             }
-                                                        }
-
-                                                        let _ = settings.save();
+                                                        });
                                                     }
                                                 });
 
@@ -1,13 +1,61 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use eframe::egui::{self, Response, Ui, Widget};
 use egui_extras::{Column, TableBuilder};
-use libalembic::settings::{Account, AlembicSettings};
+use libalembic::{
+    credentials::{self, CredentialRef, CredentialStoreKind},
+    settings::{Account, AlembicSettings, new_id},
+};
 
 use super::components::centered_text;
 
+#[derive(Default)]
 pub struct AccountsMainTab {
-    pub selected_server: Option<usize>,
+    pub selected_server: Option<String>,
+    /// Passwords being typed, by account ID. They are only written to the
+    /// account's credential store once the field loses focus.
+    password_edits: HashMap<String, String>,
+    vault_passphrase: String,
+    error: Option<String>,
+}
+
+impl AccountsMainTab {
+    fn vault_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Password vault:");
+
+            if credentials::is_vault_unlocked() {
+                ui.label("Unlocked");
+                if ui.button("Lock").clicked() {
+                    credentials::lock_vault();
+                }
+                return;
+            }
+
+            let passphrase_edit = egui::TextEdit::singleline(&mut self.vault_passphrase)
+                .password(true)
+                .hint_text("Passphrase");
+            let response = ui.add(passphrase_edit);
+
+            let button_text = if credentials::vault_exists() {
+                "Unlock"
+            } else {
+                "Create"
+            };
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+            if (ui.button(button_text).clicked() || submitted) && !self.vault_passphrase.is_empty()
+            {
+                self.error = credentials::unlock_vault(&self.vault_passphrase)
+                    .err()
+                    .map(|e| format!("{:#}", e));
+                self.vault_passphrase.clear();
+            }
+        });
+    }
 }
 
 impl Widget for &mut AccountsMainTab {
@@ -31,18 +79,20 @@ impl Widget for &mut AccountsMainTab {
                         // Server Picker
                         ui.horizontal(|ui| {
                             ui.label("Server:");
-                            let selected_text = match self.selected_server {
-                                Some(index) => settings.servers[index].name.clone(),
-                                None => "Pick a server".to_string(),
-                            };
+                            let selected_text = settings
+                                .servers
+                                .iter()
+                                .find(|server| self.selected_server.as_ref() == Some(&server.id))
+                                .map(|server| server.name.clone())
+                                .unwrap_or_else(|| "Pick a server".to_string());
 
                             egui::ComboBox::from_id_salt("AccountServer")
                                 .selected_text(selected_text)
                                 .show_ui(ui, |ui| {
-                                    for (index, server) in settings.servers.iter().enumerate() {
+                                    for server in &settings.servers {
                                         ui.selectable_value(
                                             &mut self.selected_server,
-                                            Some(index),
+                                            Some(server.id.clone()),
                                             server.name.clone(),
                                         );
                                     }
@@ -52,21 +102,28 @@ impl Widget for &mut AccountsMainTab {
                         ui.add_space(8.0);
 
                         // Add Account
-                        if self.selected_server.is_some() {
+                        if let Some(server_id) = &self.selected_server {
                             if ui.button("New Account").clicked() {
                                 let new_account = Account {
-                                    server_index: self.selected_server.unwrap_or_default(),
+                                    id: new_id(),
+                                    server_id: server_id.clone(),
                                     username: "username".to_string(),
-                                    password: "password".to_string(),
+                                    credential: CredentialRef::Keyring,
                                 };
 
-                                settings.accounts.push(new_account);
-                                let _ = settings.save();
+                                let _ =
+                                    settings.modify(|settings| settings.accounts.push(new_account));
                             }
 
                             ui.add_space(8.0);
                         }
 
+                        self.vault_ui(ui);
+                        if let Some(error) = &self.error {
+                            ui.colored_label(ui.visuals().error_fg_color, error);
+                        }
+                        ui.add_space(8.0);
+
                         if self.selected_server.is_some() {
                             // Accounts Listing
                             let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
@@ -78,8 +135,7 @@ impl Widget for &mut AccountsMainTab {
                                     .iter_mut()
                                     .enumerate()
                                     .filter(|(_, account)| {
-                                        self.selected_server.is_some()
-                                            && account.server_index == self.selected_server.unwrap()
+                                        self.selected_server.as_ref() == Some(&account.server_id)
                                     });
 
                             // Easy way to get a count from the above iterator
@@ -91,6 +147,7 @@ impl Widget for &mut AccountsMainTab {
                                 .cell_layout(egui::Layout::left_to_right(egui::Align::Center)) // Cell layout
                                 .column(Column::auto()) // Username column
                                 .column(Column::auto()) // Password column
+                                .column(Column::auto()) // Store column
                                 .header(text_height, |mut header| {
                                     header.col(|ui| {
                                         ui.strong("Username");
@@ -98,6 +155,9 @@ impl Widget for &mut AccountsMainTab {
                                     header.col(|ui| {
                                         ui.strong("Password");
                                     });
+                                    header.col(|ui| {
+                                        ui.strong("Stored In");
+                                    });
                                 })
                                 .body(|mut body| {
                                     for (_index, account) in accounts {
@@ -111,23 +171,78 @@ impl Widget for &mut AccountsMainTab {
                                                     .changed();
                                             });
 
-                                            // Editable Password field (masked by default)
+                                            // Password field (masked by default). The
+                                            // stored password is never shown.
                                             table_row.col(|ui| {
                                                 let password_id = ui.make_persistent_id(format!(
                                                     "password_{}",
-                                                    account.username
+                                                    account.id
                                                 ));
                                                 let is_focused =
                                                     ui.memory(|m| m.has_focus(password_id));
 
-                                                let password_edit = egui::TextEdit::singleline(
-                                                    &mut account.password,
-                                                )
-                                                .id(password_id)
-                                                .password(!is_focused);
+                                                let pending = self
+                                                    .password_edits
+                                                    .entry(account.id.clone())
+                                                    .or_default();
+                                                let password_edit =
+                                                    egui::TextEdit::singleline(pending)
+                                                        .id(password_id)
+                                                        .hint_text("(unchanged)")
+                                                        .password(!is_focused);
+
+                                                if ui.add(password_edit).lost_focus()
+                                                    && !pending.is_empty()
+                                                {
+                                                    match credentials::set_password(
+                                                        &account.id,
+                                                        account.credential.kind(),
+                                                        pending,
+                                                    ) {
+                                                        Ok(credential) => {
+                                                            account.credential = credential;
+                                                            did_update = true;
+                                                            self.error = None;
+                                                        }
+                                                        Err(e) => {
+                                                            self.error = Some(format!("{:#}", e));
+                                                        }
+                                                    }
+                                                    pending.clear();
+                                                }
+                                            });
+
+                                            // Credential store picker
+                                            table_row.col(|ui| {
+                                                let mut store = account.credential.kind();
+
+                                                egui::ComboBox::from_id_salt(format!(
+                                                    "store_{}",
+                                                    account.id
+                                                ))
+                                                .selected_text(store.to_string())
+                                                .show_ui(ui, |ui| {
+                                                    for kind in CredentialStoreKind::ALL {
+                                                        ui.selectable_value(
+                                                            &mut store,
+                                                            kind,
+                                                            kind.to_string(),
+                                                        );
+                                                    }
+                                                });
 
-                                                if ui.add(password_edit).changed() {
-                                                    did_update = true;
+                                                if store != account.credential.kind() {
+                                                    match credentials::move_password(account, store)
+                                                    {
+                                                        Ok(credential) => {
+                                                            account.credential = credential;
+                                                            did_update = true;
+                                                            self.error = None;
+                                                        }
+                                                        Err(e) => {
+                                                            self.error = Some(format!("{:#}", e));
+                                                        }
+                                                    }
                                                 }
                                             });
                                         });
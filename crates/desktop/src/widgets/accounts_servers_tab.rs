@@ -2,7 +2,10 @@ use std::sync::{Arc, Mutex};
 
 use eframe::egui::{self, Response, Ui, Widget};
 use egui_extras::{Column, TableBuilder};
-use libalembic::settings::{AlembicSettings, ServerInfo};
+use libalembic::{
+    credentials,
+    settings::{AlembicSettings, ServerInfo, new_id},
+};
 
 use super::components::centered_text;
 
@@ -24,13 +27,13 @@ impl Widget for &mut AccountsServersTab {
                     // Add
                     if ui.button("New Server").clicked() {
                         let new_server = ServerInfo {
+                            id: new_id(),
                             name: "Server".to_string(),
                             hostname: "hostname or IP address".to_string(),
                             port: "9000".to_string(),
                         };
 
-                        settings.servers.push(new_server);
-                        let _ = settings.save();
+                        let _ = settings.modify(|settings| settings.servers.push(new_server));
                     }
 
                     ui.add_space(8.0);
@@ -102,8 +105,29 @@ impl Widget for &mut AccountsServersTab {
                             }
 
                             if let Some(i) = delete_index {
-                                settings.servers.remove(i);
-                                did_update = true;
+                                let id = settings.servers[i].id.clone();
+                                let mut removed_accounts = vec![];
+                                let _ = settings.modify(|settings| {
+                                    if let Some(index) = settings.server_index(&id) {
+                                        removed_accounts = settings
+                                            .accounts
+                                            .iter()
+                                            .filter(|a| a.server_id == id)
+                                            .cloned()
+                                            .collect();
+                                        settings.remove_server(index);
+                                    }
+                                });
+
+                                // Don't leave the removed accounts' passwords behind
+                                for account in &removed_accounts {
+                                    if let Err(e) = credentials::delete_password(account) {
+                                        eprintln!(
+                                            "Error removing the stored password for '{}': {:#}",
+                                            account.username, e
+                                        );
+                                    }
+                                }
                             }
                         });
 
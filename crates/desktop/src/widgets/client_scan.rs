@@ -0,0 +1,112 @@
+use std::{
+    sync::mpsc::{self, Receiver},
+    thread::JoinHandle,
+};
+
+use eframe::egui::Ui;
+use libalembic::{
+    scanner::{self, ScanCancel, ScanEvent, ScanProgress},
+    settings::{AlembicSettings, ClientConfigType},
+};
+
+/// A button that scans for clients in the background, showing progress while
+/// it runs and merging what it found into settings when it's done
+pub struct ClientScan {
+    label: &'static str,
+    running: Option<RunningScan>,
+    status: Option<String>,
+}
+
+struct RunningScan {
+    events: Receiver<ScanEvent>,
+    cancel: ScanCancel,
+    handle: JoinHandle<anyhow::Result<Vec<ClientConfigType>>>,
+    last_event: Option<String>,
+}
+
+impl ClientScan {
+    pub fn new(label: &'static str) -> Self {
+        Self {
+            label,
+            running: None,
+            status: None,
+        }
+    }
+
+    fn start(&mut self, settings: &AlembicSettings) {
+        let (sender, events) = mpsc::channel();
+        let cancel = ScanCancel::default();
+        let progress = ScanProgress::new(sender, cancel.clone());
+        let options = settings.scan.clone();
+
+        let handle =
+            std::thread::spawn(move || scanner::scan_all_with_progress(&options, &progress));
+
+        self.status = None;
+        self.running = Some(RunningScan {
+            events,
+            cancel,
+            handle,
+            last_event: None,
+        });
+    }
+
+    fn finish(running: RunningScan, settings: &mut AlembicSettings) -> String {
+        let discovered = match running.handle.join() {
+            Ok(Ok(discovered)) => discovered,
+            Ok(Err(e)) => return e.to_string(),
+            Err(_) => return "The scan stopped unexpectedly.".to_string(),
+        };
+
+        // Reconcile against the freshly loaded settings, so clients changed
+        // elsewhere during the scan aren't overwritten
+        let mut summary = None;
+        if let Err(e) = settings.modify(|settings| {
+            let entries = settings.reconcile_scan(&discovered);
+            summary = Some(settings.merge_scan(&entries));
+        }) {
+            return format!("Failed to save scanned clients: {}", e);
+        }
+
+        match summary {
+            Some(summary) if summary.added + summary.updated > 0 => format!(
+                "Added {} new client(s), updated {}.",
+                summary.added, summary.updated
+            ),
+            _ => "No new or changed clients found.".to_string(),
+        }
+    }
+
+    /// Show the scan button, or the progress of a running scan
+    pub fn show(&mut self, ui: &mut Ui, settings: &mut AlembicSettings) {
+        if let Some(running) = &mut self.running {
+            if let Some(event) = running.events.try_iter().last() {
+                running.last_event = Some(event.to_string());
+            }
+
+            if !running.handle.is_finished() {
+                ui.spinner();
+                ui.label(running.last_event.as_deref().unwrap_or("Scanning..."));
+                if ui.button("Cancel").clicked() {
+                    running.cancel.cancel();
+                }
+
+                // Keep polling for progress while the scan runs
+                ui.ctx().request_repaint();
+                return;
+            }
+
+            if let Some(running) = self.running.take() {
+                self.status = Some(Self::finish(running, settings));
+            }
+        }
+
+        if ui.button(self.label).clicked() {
+            self.start(settings);
+        }
+
+        if let Some(status) = &self.status {
+            ui.label(status);
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use std::sync::{Arc, Mutex};
 
 use eframe::egui::{self, Layout, Response, RichText, Ui, Widget};
+use libalembic::credentials::CredentialStoreKind;
+use libalembic::importers::Launcher;
 use libalembic::inject_config::{DllType, InjectConfig};
-use libalembic::settings::AlembicSettings;
+use libalembic::settings::{AlembicSettings, new_id, save_imported_passwords};
+
+use super::client_scan::ClientScan;
 
 pub fn centered_text(ui: &mut Ui, text: &str) -> Response {
     ui.with_layout(
@@ -13,7 +17,7 @@ pub fn centered_text(ui: &mut Ui, text: &str) -> Response {
 }
 
 pub struct AccountPicker {
-    pub selected_server: Option<usize>,
+    pub selected_server: Option<String>,
 }
 
 impl Widget for &mut AccountPicker {
@@ -23,20 +27,18 @@ impl Widget for &mut AccountPicker {
         }) {
             let mut settings = s.lock().unwrap();
 
-            let account_names: Vec<String> = settings
+            let accounts: Vec<(String, String)> = settings
                 .accounts
                 .iter()
-                .filter(|account| {
-                    self.selected_server.is_some()
-                        && account.server_index == self.selected_server.unwrap()
-                })
-                .map(|account| account.username.clone())
+                .filter(|account| self.selected_server.as_deref() == Some(&account.server_id))
+                .map(|account| (account.id.clone(), account.username.clone()))
                 .collect();
 
-            let selected_text = if !account_names.is_empty() {
-                settings
-                    .selected_account
-                    .and_then(|index| account_names.get(index).cloned())
+            let selected_text = if !accounts.is_empty() {
+                accounts
+                    .iter()
+                    .find(|(id, _)| settings.selected_account.as_ref() == Some(id))
+                    .map(|(_, name)| name.clone())
                     .unwrap_or_else(|| "Pick an account".to_string())
             } else {
                 "Pick a server".to_string()
@@ -45,16 +47,17 @@ impl Widget for &mut AccountPicker {
             egui::ComboBox::from_id_salt("Account")
                 .selected_text(selected_text)
                 .show_ui(ui, |ui| {
-                    for (index, name) in account_names.iter().enumerate() {
+                    for (id, name) in &accounts {
                         if ui
                             .selectable_value(
                                 &mut settings.selected_account,
-                                Some(index),
+                                Some(id.clone()),
                                 name.clone(),
                             )
                             .changed()
                         {
-                            let _ = settings.save();
+                            let selected = settings.selected_account.clone();
+                            let _ = settings.modify(|s| s.selected_account = selected);
                         };
                     }
                 })
@@ -74,16 +77,16 @@ impl Widget for &mut ClientPicker {
         }) {
             let mut settings = s.lock().unwrap();
 
-            let client_names: Vec<String> = settings
+            let clients: Vec<(String, String)> = settings
                 .clients
                 .iter()
-                .map(|client| client.name().to_string())
+                .map(|client| (client.id().to_string(), client.name().to_string()))
                 .collect();
 
-            let selected_text = if !client_names.is_empty() {
+            let selected_text = if !clients.is_empty() {
                 settings
-                    .selected_client
-                    .and_then(|index| client_names.get(index).cloned())
+                    .get_selected_client()
+                    .map(|client| client.name().to_string())
                     .unwrap_or_else(|| "Pick a client".to_string())
             } else {
                 "No clients".to_string()
@@ -98,16 +101,17 @@ impl Widget for &mut ClientPicker {
             egui::ComboBox::from_id_salt("Client")
                 .selected_text(truncated_text)
                 .show_ui(ui, |ui| {
-                    for (index, name) in client_names.iter().enumerate() {
+                    for (id, name) in &clients {
                         if ui
                             .selectable_value(
                                 &mut settings.selected_client,
-                                Some(index),
+                                Some(id.clone()),
                                 name.clone(),
                             )
                             .changed()
                         {
-                            let _ = settings.save();
+                            let selected = settings.selected_client.clone();
+                            let _ = settings.modify(|s| s.selected_client = selected);
                         };
                     }
                 })
@@ -128,21 +132,23 @@ impl Widget for &mut DllPicker {
             let mut settings = s.lock().unwrap();
 
             // Get the currently selected client
-            if let Some(client_idx) = settings.selected_client {
+            if let Some(client_idx) = settings.selected_client_index() {
                 // Get DLLs for the selected client
-                let dll_names: Vec<String> = settings
-                    .get_client_dlls(client_idx)
-                    .map(|dlls| dlls.iter().map(|dll| format!("{}", dll.dll_type)).collect())
-                    .unwrap_or_default();
+                let client_id = settings.clients[client_idx].id().to_string();
+                let dlls: Vec<(String, String)> = settings.clients[client_idx]
+                    .dlls()
+                    .iter()
+                    .map(|dll| (dll.id.clone(), dll.dll_type.to_string()))
+                    .collect();
 
                 let current_selected_dll = settings
                     .clients
                     .get(client_idx)
                     .and_then(|c| c.selected_dll());
 
-                let selected_text = if !dll_names.is_empty() {
+                let selected_text = if !dlls.is_empty() {
                     current_selected_dll
-                        .and_then(|index| dll_names.get(index).cloned())
+                        .and_then(|index| dlls.get(index).map(|(_, name)| name.clone()))
                         .unwrap_or_else(|| "No DLL selected".to_string())
                 } else {
                     "No DLLs".to_string()
@@ -157,18 +163,20 @@ impl Widget for &mut DllPicker {
                             .selectable_value(&mut selected_none, true, "None")
                             .changed()
                         {
-                            settings.select_dll_for_client(client_idx, None);
-                            let _ = settings.save();
+                            let _ = settings.modify(|s| {
+                                s.select_client_dll(&client_id, None);
+                            });
                         };
 
-                        for (index, name) in dll_names.iter().enumerate() {
+                        for (index, (dll_id, name)) in dlls.iter().enumerate() {
                             let mut selected = current_selected_dll == Some(index);
                             if ui
                                 .selectable_value(&mut selected, true, name.clone())
                                 .changed()
                             {
-                                settings.select_dll_for_client(client_idx, Some(index));
-                                let _ = settings.save();
+                                let _ = settings.modify(|s| {
+                                    s.select_client_dll(&client_id, Some(dll_id));
+                                });
                             };
                         }
                     })
@@ -191,16 +199,16 @@ impl Widget for &mut ServerPicker {
         }) {
             let mut settings = s.lock().unwrap();
 
-            let server_names: Vec<String> = settings
+            let servers: Vec<(String, String)> = settings
                 .servers
                 .iter()
-                .map(|server| server.name.clone())
+                .map(|server| (server.id.clone(), server.name.clone()))
                 .collect();
 
-            let selected_text = if !server_names.is_empty() {
+            let selected_text = if !servers.is_empty() {
                 settings
-                    .selected_server
-                    .and_then(|index| server_names.get(index).cloned())
+                    .get_selected_server()
+                    .map(|server| server.name.clone())
                     .unwrap_or_else(|| "Pick a server".to_string())
             } else {
                 "No servers".to_string()
@@ -209,16 +217,17 @@ impl Widget for &mut ServerPicker {
             egui::ComboBox::from_id_salt("Server")
                 .selected_text(selected_text)
                 .show_ui(ui, |ui| {
-                    for (index, name) in server_names.iter().enumerate() {
+                    for (id, name) in &servers {
                         if ui
                             .selectable_value(
                                 &mut settings.selected_server,
-                                Some(index),
+                                Some(id.clone()),
                                 name.clone(),
                             )
                             .changed()
                         {
-                            let _ = settings.save();
+                            let selected = settings.selected_server.clone();
+                            let _ = settings.modify(|s| s.selected_server = selected);
                         };
                     }
                 })
@@ -229,7 +238,95 @@ impl Widget for &mut ServerPicker {
     }
 }
 
-pub struct SettingsGameClientPathEdit {}
+#[derive(Default)]
+pub struct ProfilePicker {
+    new_profile_name: String,
+}
+
+impl Widget for &mut ProfilePicker {
+    fn ui(self, ui: &mut Ui) -> Response {
+        if let Some(s) = ui.data_mut(|data| {
+            data.get_persisted::<Arc<Mutex<AlembicSettings>>>(egui::Id::new("settings"))
+        }) {
+            let mut settings = s.lock().unwrap();
+
+            let profiles: Vec<(String, String)> = settings
+                .profiles
+                .iter()
+                .map(|profile| (profile.id.clone(), profile.name.clone()))
+                .collect();
+            let active = settings.active_profile_index();
+
+            let selected_text = if !profiles.is_empty() {
+                active
+                    .map(|idx| profiles[idx].1.clone())
+                    .unwrap_or_else(|| "Custom".to_string())
+            } else {
+                "No profiles".to_string()
+            };
+
+            ui.vertical(|ui| {
+                let mut picked = None;
+
+                egui::ComboBox::from_id_salt("Profile")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        for (index, (id, name)) in profiles.iter().enumerate() {
+                            if ui.selectable_label(active == Some(index), name).clicked() {
+                                picked = Some(id.clone());
+                            }
+                        }
+                    });
+
+                // Picking a profile just selects its client, DLL, server and account
+                if let Some(id) = picked {
+                    let _ = settings.modify(|s| {
+                        if let Some(index) = s.profile_index(&id) {
+                            let _ = s.apply_profile(index);
+                        }
+                    });
+                }
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_profile_name)
+                            .hint_text("Profile name")
+                            .desired_width(120.0),
+                    );
+
+                    let name = self.new_profile_name.trim().to_string();
+                    let profile = settings.profile_from_selection(&name).ok();
+                    let can_save = !name.is_empty() && profile.is_some();
+
+                    if ui
+                        .add_enabled(can_save, egui::Button::new("Save"))
+                        .on_hover_text("Save the current selections as a profile")
+                        .clicked()
+                        && let Some(profile) = profile
+                    {
+                        let _ = settings.modify(|s| s.add_profile(profile));
+                        self.new_profile_name.clear();
+                    }
+                });
+            })
+            .response
+        } else {
+            ui.label("TODO: Bug, please report.")
+        }
+    }
+}
+
+pub struct SettingsGameClientPathEdit {
+    client_scan: ClientScan,
+}
+
+impl Default for SettingsGameClientPathEdit {
+    fn default() -> Self {
+        Self {
+            client_scan: ClientScan::new("Scan for Clients"),
+        }
+    }
+}
 
 impl Widget for &mut SettingsGameClientPathEdit {
     fn ui(self, ui: &mut Ui) -> Response {
@@ -245,39 +342,7 @@ impl Widget for &mut SettingsGameClientPathEdit {
                 let mut settings = s.lock().unwrap();
                 let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
 
-                // Scan button
-                if ui.button("Scan for Clients").clicked() {
-                    match libalembic::scanner::scan_all() {
-                        Ok(discovered) => {
-                            let had_no_clients = settings.clients.is_empty();
-                            let mut added_count = 0;
-
-                            for config in discovered {
-                                // Check if already exists
-                                let already_exists = settings.clients.iter().any(|existing| {
-                                    existing.install_path() == config.install_path()
-                                });
-
-                                if !already_exists {
-                                    let should_select = had_no_clients && added_count == 0;
-                                    settings.add_client(config, should_select);
-                                    settings.is_configured = true;
-                                    added_count += 1;
-                                }
-                            }
-
-                            if added_count > 0 {
-                                let _ = settings.save();
-                                println!("Added {} new client(s)", added_count);
-                            } else {
-                                println!("No new clients found");
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Error scanning for clients: {}", e);
-                        }
-                    }
-                }
+                ui.horizontal(|ui| self.client_scan.show(ui, &mut settings));
 
                 ui.add_space(8.0);
 
@@ -312,11 +377,11 @@ impl Widget for &mut SettingsGameClientPathEdit {
                             });
                         })
                         .body(|mut body| {
-                            let selected_client = settings.selected_client;
-                            let mut new_selection: Option<usize> = None;
+                            let selected_client = settings.selected_client.clone();
+                            let mut new_selection: Option<String> = None;
 
                             for (index, client) in settings.clients.iter().enumerate() {
-                                let is_selected = Some(index) == selected_client;
+                                let is_selected = selected_client.as_deref() == Some(client.id());
 
                                 body.row(text_height, |mut row| {
                                     // Index column
@@ -327,7 +392,7 @@ impl Widget for &mut SettingsGameClientPathEdit {
                                             RichText::new(format!("  {}", index))
                                         };
                                         if ui.selectable_label(is_selected, text).clicked() {
-                                            new_selection = Some(index);
+                                            new_selection = Some(client.id().to_string());
                                         }
                                     });
 
@@ -353,26 +418,19 @@ impl Widget for &mut SettingsGameClientPathEdit {
                             }
 
                             // Handle selection change
-                            if let Some(index) = new_selection {
-                                settings.selected_client = Some(index);
-                                let _ = settings.save();
+                            if let Some(id) = new_selection {
+                                let _ = settings.modify(|s| s.selected_client = Some(id));
                             }
                         });
 
                     // Handle deletion
                     if let Some(index) = to_remove {
-                        settings.clients.remove(index);
-
-                        // Update selected_client if needed
-                        if let Some(selected) = settings.selected_client {
-                            if selected == index {
-                                settings.selected_client = None;
-                            } else if selected > index {
-                                settings.selected_client = Some(selected - 1);
+                        let id = settings.clients[index].id().to_string();
+                        let _ = settings.modify(|s| {
+                            if let Some(index) = s.client_index(&id) {
+                                s.remove_client(index);
                             }
-                        }
-
-                        let _ = settings.save();
+                        });
                     }
                 }
             } else {
@@ -395,7 +453,7 @@ impl Widget for &mut SettingsDLLPathEdit {
                 let mut settings = s.lock().unwrap();
 
                 // Get the selected client
-                let client_idx = settings.selected_client;
+                let client_idx = settings.selected_client_index();
                 if client_idx.is_none() {
                     return;
                 }
@@ -406,30 +464,37 @@ impl Widget for &mut SettingsDLLPathEdit {
                 ui.add_space(8.0);
 
                 let client_idx = client_idx.unwrap();
+                // settings.modify reloads config.json, so changes find the
+                // client by ID rather than by its index here
+                let client_id = settings.clients[client_idx].id().to_string();
 
                 // Scan button
                 ui.horizontal(|ui| {
                     if ui.button("Discover DLLs").clicked() {
-                        match libalembic::scanner::scan_for_decal_dlls(&settings.clients) {
+                        match libalembic::scanner::scan_for_decal_dlls(&settings.clients, &settings.scan) {
                             Ok(discovered_dlls) => {
                                 if discovered_dlls.is_empty() {
                                     println!("No Decal installations found");
                                 } else {
-                                    let had_no_dlls = settings
-                                        .get_client_dlls(client_idx)
-                                        .map(|dlls| dlls.is_empty())
-                                        .unwrap_or(true);
-
-                                    for dll in discovered_dlls {
-                                        settings.add_dll_to_client(client_idx, dll);
-                                    }
+                                    let _ = settings.modify(|settings| {
+                                        let Some(client_idx) = settings.client_index(&client_id)
+                                        else {
+                                            return;
+                                        };
+                                        let had_no_dlls = settings
+                                            .get_client_dlls(client_idx)
+                                            .map(|dlls| dlls.is_empty())
+                                            .unwrap_or(true);
 
-                                    // Auto-select first DLL if there were no DLLs before
-                                    if had_no_dlls {
-                                        settings.select_dll_for_client(client_idx, Some(0));
-                                    }
+                                        for dll in discovered_dlls {
+                                            settings.add_dll_to_client(client_idx, dll);
+                                        }
 
-                                    let _ = settings.save();
+                                        // Auto-select first DLL if there were no DLLs before
+                                        if had_no_dlls {
+                                            settings.select_dll_for_client(client_idx, Some(0));
+                                        }
+                                    });
                                     println!("DLL scan complete");
                                 }
                             }
@@ -441,21 +506,26 @@ impl Widget for &mut SettingsDLLPathEdit {
 
                     if ui.button("New DLL").clicked() {
                         let new_dll = InjectConfig {
+                            id: new_id(),
                             dll_path: std::path::PathBuf::from(
                                 "C:\\Program Files\\Alembic\\Alembic.dll",
                             ),
                             dll_type: DllType::Alembic,
                             startup_function: None,
                         };
-                        settings.add_dll_to_client(client_idx, new_dll);
-                        let dlls = settings
-                            .get_client_dlls(client_idx)
-                            .map(|d| d.len())
-                            .unwrap_or(0);
-                        if dlls == 1 {
-                            settings.select_dll_for_client(client_idx, Some(0));
-                        }
-                        let _ = settings.save();
+                        let _ = settings.modify(|settings| {
+                            let Some(client_idx) = settings.client_index(&client_id) else {
+                                return;
+                            };
+                            settings.add_dll_to_client(client_idx, new_dll);
+                            let dlls = settings
+                                .get_client_dlls(client_idx)
+                                .map(|d| d.len())
+                                .unwrap_or(0);
+                            if dlls == 1 {
+                                settings.select_dll_for_client(client_idx, Some(0));
+                            }
+                        });
                     }
                 });
 
@@ -541,17 +611,25 @@ impl Widget for &mut SettingsDLLPathEdit {
                                 }
 
                                 // Handle selection change
-                                if let Some(index) = new_selection {
-                                    settings.select_dll_for_client(client_idx, Some(index));
-                                    let _ = settings.save();
+                                if let Some(dll) =
+                                    new_selection.and_then(|index| dlls_cloned.get(index))
+                                {
+                                    let _ = settings.modify(|s| {
+                                        s.select_client_dll(&client_id, Some(&dll.id));
+                                    });
                                 }
                             });
                     }
 
                     // Handle deletion
-                    if let Some(index) = to_remove {
-                        settings.remove_dll_from_client(client_idx, index);
-                        let _ = settings.save();
+                    if let Some(dll) = to_remove.and_then(|index| dlls_cloned.get(index)) {
+                        let _ = settings.modify(|s| {
+                            if let Some(client_idx) = s.client_index(&client_id)
+                                && let Some(index) = s.clients[client_idx].dll_index(&dll.id)
+                            {
+                                s.remove_dll_from_client(client_idx, index);
+                            }
+                        });
                     }
                 }
             } else {
@@ -561,3 +639,111 @@ impl Widget for &mut SettingsDLLPathEdit {
         .response
     }
 }
+
+/// Import servers and accounts from another launcher
+pub struct LauncherImportEdit {
+    launcher: Launcher,
+    path: String,
+    status: Option<String>,
+}
+
+impl Default for LauncherImportEdit {
+    fn default() -> Self {
+        Self {
+            launcher: Launcher::Thwarg,
+            path: default_launcher_path(Launcher::Thwarg),
+            status: None,
+        }
+    }
+}
+
+fn default_launcher_path(launcher: Launcher) -> String {
+    launcher
+        .default_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_default()
+}
+
+impl LauncherImportEdit {
+    fn import(&self, settings: &mut AlembicSettings) -> anyhow::Result<String> {
+        let import = self.launcher.read(std::path::Path::new(self.path.trim()))?;
+        // Accounts that aren't tied to a server go to the selected server
+        let (export, skipped) = import.into_export(settings.selected_server.as_deref());
+
+        let mut summary = None;
+        settings.modify(|s| summary = Some(s.merge_import(&export)))?;
+        let summary = summary.expect("modify always runs its closure");
+
+        if !summary.passwords.is_empty() {
+            let credentials =
+                save_imported_passwords(&summary.passwords, CredentialStoreKind::default())?;
+            settings.modify(|s| s.apply_imported_credentials(&credentials))?;
+        }
+
+        let mut message = format!(
+            "Imported {} servers and {} accounts.",
+            summary.servers_added + summary.servers_updated,
+            summary.accounts_added + summary.accounts_updated
+        );
+        if skipped > 0 {
+            message.push_str(&format!(
+                " {} accounts weren't tied to a server and were skipped. Select a server and import again to add them to it.",
+                skipped
+            ));
+        }
+
+        Ok(message)
+    }
+}
+
+impl Widget for &mut LauncherImportEdit {
+    fn ui(self, ui: &mut Ui) -> Response {
+        ui.vertical(|ui| {
+            ui.label("Import From Another Launcher");
+            ui.add_space(8.0);
+
+            let Some(s) = ui.data_mut(|data| {
+                data.get_persisted::<Arc<Mutex<AlembicSettings>>>(egui::Id::new("settings"))
+            }) else {
+                ui.label("Failed to reach application backend.");
+                return;
+            };
+            let mut settings = s.lock().unwrap();
+
+            ui.horizontal(|ui| {
+                let previous = self.launcher;
+                egui::ComboBox::from_id_salt("ImportLauncher")
+                    .selected_text(self.launcher.to_string())
+                    .show_ui(ui, |ui| {
+                        for launcher in Launcher::ALL {
+                            ui.selectable_value(&mut self.launcher, launcher, launcher.to_string());
+                        }
+                    });
+                if self.launcher != previous {
+                    self.path = default_launcher_path(self.launcher);
+                }
+
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.path)
+                        .hint_text("Settings directory or file")
+                        .desired_width(300.0),
+                );
+
+                if ui
+                    .add_enabled(!self.path.trim().is_empty(), egui::Button::new("Import"))
+                    .clicked()
+                {
+                    self.status = Some(match self.import(&mut settings) {
+                        Ok(message) => message,
+                        Err(e) => format!("Import failed: {:#}", e),
+                    });
+                }
+            });
+
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+        })
+        .response
+    }
+}
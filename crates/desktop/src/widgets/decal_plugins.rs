@@ -0,0 +1,118 @@
+use std::path::{Path, PathBuf};
+
+use eframe::egui::{self, Ui};
+use libalembic::{
+    decal::{DecalComponent, DecalComponentKind, DecalRegistry},
+    settings::ClientConfigType,
+};
+
+/// Decal's plugins, services and filters in a Wine client's prefix, with a
+/// checkbox to turn each on or off
+#[derive(Default)]
+pub struct DecalPlugins {
+    /// The prefix `loaded` was read from
+    prefix: Option<PathBuf>,
+    loaded: Option<Result<Vec<DecalComponent>, String>>,
+    status: Option<String>,
+}
+
+impl DecalPlugins {
+    fn load(&mut self, prefix: PathBuf) {
+        self.loaded = Some(
+            DecalRegistry::load(&prefix)
+                .map(|decal| decal.components())
+                .map_err(|e| format!("{:#}", e)),
+        );
+        self.prefix = Some(prefix);
+        self.status = None;
+    }
+
+    /// Turn a component on or off, returning the components as they are now.
+    /// The registry is read again first, so changes Wine or Decal made since
+    /// it was shown aren't overwritten.
+    fn set_enabled(
+        prefix: &Path,
+        component: &DecalComponent,
+        enabled: bool,
+    ) -> anyhow::Result<Vec<DecalComponent>> {
+        let mut decal = DecalRegistry::load(prefix)?;
+        decal.set_enabled(component, enabled)?;
+        decal.save()?;
+
+        Ok(decal.components())
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, client: &ClientConfigType) {
+        let Some(prefix) = client.wine_prefix().map(PathBuf::from) else {
+            return;
+        };
+
+        egui::CollapsingHeader::new("Decal Plugins").show(ui, |ui| {
+            if self.prefix.as_ref() != Some(&prefix) || ui.button("Reload").clicked() {
+                self.load(prefix.clone());
+            }
+
+            let components = match &mut self.loaded {
+                Some(Ok(components)) => components,
+                Some(Err(e)) => {
+                    ui.label(format!("Couldn't read the prefix's registry: {}", e));
+                    return;
+                }
+                None => return,
+            };
+
+            if components.is_empty() {
+                ui.label("No Decal plugins, services or filters are registered in this prefix.");
+                return;
+            }
+
+            ui.label("Changes take effect the next time the game starts. Close the game first, or Wine may overwrite them.");
+
+            let mut updated = None;
+
+            for kind in DecalComponentKind::ALL {
+                if !components.iter().any(|c| c.kind == kind) {
+                    continue;
+                }
+
+                ui.add_space(4.0);
+                ui.strong(format!("{}s", kind));
+
+                for component in components.iter().filter(|c| c.kind == kind) {
+                    let mut enabled = component.enabled;
+                    let dll = component
+                        .dll_path
+                        .as_ref()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_else(|| "DLL not registered".to_string());
+
+                    if ui
+                        .checkbox(&mut enabled, &component.name)
+                        .on_hover_text(format!("{}\n{}", component.clsid, dll))
+                        .changed()
+                    {
+                        match Self::set_enabled(&prefix, component, enabled) {
+                            Ok(current) => {
+                                updated = Some(current);
+                                self.status = None;
+                            }
+                            Err(e) => {
+                                self.status =
+                                    Some(format!("Failed to update {}: {:#}", component.name, e))
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(current) = updated {
+                *components = current;
+            }
+
+            if let Some(status) = &self.status {
+                ui.add_space(4.0);
+                ui.label(status);
+            }
+        });
+    }
+}
@@ -0,0 +1,76 @@
+use eframe::egui::{self, Ui};
+use libalembic::{
+    dll_inspect::{DllInspection, validate_dll},
+    inject_config::InjectConfig,
+    settings::ClientConfigType,
+    validation::{Severity, ValidationResult},
+};
+
+/// What the selected DLL of a client is built for, exports and imports, and
+/// whether it can be injected as configured
+#[derive(Default)]
+pub struct DllInspectionView {
+    /// The DLL `inspected` was read for
+    dll: Option<InjectConfig>,
+    inspected: Option<(Result<DllInspection, String>, ValidationResult)>,
+}
+
+impl DllInspectionView {
+    fn inspect(&mut self, client: &ClientConfigType, dll: &InjectConfig) {
+        self.inspected = Some((
+            DllInspection::for_client(client, dll).map_err(|e| format!("{:#}", e)),
+            validate_dll(client, dll),
+        ));
+        self.dll = Some(dll.clone());
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, client: &ClientConfigType, dll: &InjectConfig) {
+        egui::CollapsingHeader::new("DLL Inspection").show(ui, |ui| {
+            if self.dll.as_ref() != Some(dll) || ui.button("Inspect again").clicked() {
+                self.inspect(client, dll);
+            }
+
+            let Some((inspection, result)) = &self.inspected else {
+                return;
+            };
+
+            match inspection {
+                Ok(inspection) => {
+                    ui.label(format!("Architecture: {}", inspection.machine));
+                    for (heading, names) in [
+                        ("Exports", &inspection.exports),
+                        ("Imports", &inspection.imports),
+                    ] {
+                        egui::CollapsingHeader::new(format!("{} ({})", heading, names.len())).show(
+                            ui,
+                            |ui| {
+                                egui::ScrollArea::vertical()
+                                    .id_salt(heading)
+                                    .max_height(120.0)
+                                    .show(ui, |ui| {
+                                        for name in names {
+                                            ui.label(name);
+                                        }
+                                    });
+                            },
+                        );
+                    }
+                }
+                Err(e) => {
+                    ui.label(format!("Couldn't read the DLL: {}", e));
+                }
+            }
+
+            ui.add_space(4.0);
+            for finding in &result.findings {
+                let color = match finding.severity {
+                    Severity::Info => ui.visuals().text_color(),
+                    Severity::Warning => ui.visuals().warn_fg_color,
+                    Severity::Error => ui.visuals().error_fg_color,
+                };
+
+                ui.colored_label(color, finding.to_string());
+            }
+        });
+    }
+}
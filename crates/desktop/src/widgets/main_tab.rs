@@ -1,20 +1,21 @@
 use std::sync::{Arc, Mutex};
 
 use crate::{
-    backend::{AppModal, Backend, Client},
+    backend::{AppModal, Backend},
     launch::try_launch,
 };
 use eframe::egui::{self, Align, Button, Layout, Response, Ui, Vec2, Widget};
 use libalembic::settings::AlembicSettings;
 
 use super::{
-    components::{AccountPicker, ClientPicker, DllPicker, ServerPicker},
+    components::{AccountPicker, ClientPicker, DllPicker, ProfilePicker, ServerPicker},
     news::News,
 };
 
 pub struct MainTab {
     sidebar_width: f32,
     news: News,
+    profile_picker: ProfilePicker,
 }
 
 impl MainTab {
@@ -22,6 +23,7 @@ impl MainTab {
         Self {
             sidebar_width: 200.0,
             news: News::default(),
+            profile_picker: ProfilePicker::default(),
         }
     }
 }
@@ -40,21 +42,12 @@ impl Widget for &mut MainTab {
             });
             ui.with_layout(Layout::bottom_up(Align::Max), |ui| {
                 ui.set_max_width(self.sidebar_width);
-                let _have_client = if let Some(s) = ui.data_mut(|data| {
+                let client_running = if let Some(s) = ui.data_mut(|data| {
                     data.get_persisted::<Arc<Mutex<Backend>>>(egui::Id::new("backend"))
                 }) {
                     let backend = s.lock().unwrap();
 
-                    backend.client.is_some()
-                } else {
-                    false
-                };
-                let _is_injected = if let Some(s) = ui.data_mut(|data| {
-                    data.get_persisted::<Arc<Mutex<Backend>>>(egui::Id::new("backend"))
-                }) {
-                    let backend = s.lock().unwrap();
-
-                    backend.is_injected
+                    backend.session.is_some()
                 } else {
                     false
                 };
@@ -64,12 +57,12 @@ impl Widget for &mut MainTab {
                 }) {
                     let settings = s.lock().unwrap();
 
-                    settings.selected_account.is_some()
+                    settings.get_selected_account().is_some()
                 } else {
                     false
                 };
 
-                ui.add_enabled_ui(can_launch, |ui| {
+                ui.add_enabled_ui(can_launch && !client_running, |ui| {
                     if ui
                         .add_sized(Vec2::new(140.0, 70.0), Button::new("Launch"))
                         .clicked()
@@ -82,10 +75,7 @@ impl Widget for &mut MainTab {
                         }) {
                             let settings = s.lock().unwrap();
 
-                            match settings.selected_client {
-                                Some(index) => settings.clients.get(index).cloned(),
-                                None => None,
-                            }
+                            settings.get_selected_client().cloned()
                         } else {
                             None
                         };
@@ -98,7 +88,7 @@ impl Widget for &mut MainTab {
                         }) {
                             let settings = s.lock().unwrap();
 
-                            settings.selected_server.map(|index| settings.servers[index].clone())
+                            settings.get_selected_server().cloned()
                         } else {
                             None
                         };
@@ -111,7 +101,7 @@ impl Widget for &mut MainTab {
                         }) {
                             let settings = s.lock().unwrap();
 
-                            settings.selected_account.map(|index| settings.accounts[index].clone())
+                            settings.get_selected_account().cloned()
                         } else {
                             None
                         };
@@ -124,10 +114,7 @@ impl Widget for &mut MainTab {
                          }) {
                              let settings = s.lock().unwrap();
 
-                             match settings.selected_client {
-                                 Some(client_idx) => settings.get_client_selected_dll(client_idx).cloned(),
-                                 None => None,
-                             }
+                             settings.get_selected_dll().cloned()
                          } else {
                              None
                          };
@@ -144,8 +131,11 @@ impl Widget for &mut MainTab {
                         );
 
                         match try_launch(&client_info, &server_info, &account_info, &dll_config) {
-                            Ok(val) => {
-                                println!("Launch succeeded. Launched pid is {val}!");
+                            Ok(session) => {
+                                println!(
+                                    "Launch succeeded. Launcher pid is {}!",
+                                    session.launcher_pid()
+                                );
 
                                 if let Some(backend_ref) = ui.data_mut(|data| {
                                     data.get_persisted::<Arc<Mutex<Backend>>>(egui::Id::new(
@@ -154,8 +144,7 @@ impl Widget for &mut MainTab {
                                 }) {
                                     let mut backend = backend_ref.lock().unwrap();
 
-                                    backend.client = Some(Client { pid: val });
-                                    backend.is_injected = true;
+                                    backend.session = Some(session);
                                 }
                             }
                             Err(error) => {
@@ -183,7 +172,7 @@ impl Widget for &mut MainTab {
                 let selected_server = if let Some(s) = ui.data_mut(|data| {
                     data.get_persisted::<Arc<Mutex<AlembicSettings>>>(egui::Id::new("settings"))
                 }) {
-                    s.lock().unwrap().selected_server
+                    s.lock().unwrap().selected_server.clone()
                 } else {
                     None
                 };
@@ -194,6 +183,7 @@ impl Widget for &mut MainTab {
                 ui.add(&mut ServerPicker {});
                 ui.add(&mut DllPicker {});
                 ui.add(&mut ClientPicker {});
+                ui.add(&mut self.profile_picker);
             });
         })
         .response
@@ -4,19 +4,24 @@ pub mod accounts_main_tab;
 pub mod accounts_servers_tab;
 pub mod accounts_tab;
 pub mod binary_viewer;
+pub mod client_scan;
 pub mod components;
+pub mod decal_plugins;
 pub mod developer_logs_tab;
 pub mod developer_main_tab_;
 pub mod developer_network_incoming_tab;
 pub mod developer_network_outgoing_tab;
 pub mod developer_network_tab;
 pub mod developer_tab;
+pub mod dll_inspection;
 pub mod game_chat_tab;
 pub mod game_main_tab;
 pub mod game_tab;
 pub mod main_tab;
 pub mod news;
 pub mod settings;
+pub mod settings_check_setup;
 pub mod settings_clients_tab;
+pub mod settings_transfer;
 pub mod tabs;
 pub mod wizard;
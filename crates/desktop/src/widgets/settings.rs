@@ -2,10 +2,16 @@ use eframe::egui::{self, Align, Layout, Response, Ui, Widget};
 
 use crate::application::AppPage;
 
-use super::settings_clients_tab::SettingsClientsTab;
+use super::{
+    client_scan::ClientScan, decal_plugins::DecalPlugins, dll_inspection::DllInspectionView,
+    settings_check_setup::SettingsCheckSetup, settings_clients_tab::SettingsClientsTab,
+    settings_transfer::SettingsTransfer,
+};
 
 pub struct Settings {
     clients_tab: SettingsClientsTab,
+    check_setup: SettingsCheckSetup,
+    transfer: SettingsTransfer,
 }
 
 impl Default for Settings {
@@ -19,7 +25,12 @@ impl Settings {
         Self {
             clients_tab: SettingsClientsTab {
                 selected_index: None,
+                client_scan: ClientScan::new("Discover Clients"),
+                decal_plugins: DecalPlugins::default(),
+                dll_inspection: DllInspectionView::default(),
             },
+            check_setup: SettingsCheckSetup::default(),
+            transfer: SettingsTransfer::default(),
         }
     }
 }
@@ -45,6 +56,10 @@ impl Widget for &mut Settings {
                 ui.with_layout(Layout::top_down(Align::LEFT), |ui| {
                     ui.heading("Settings");
                     ui.add_space(16.0);
+                    ui.add(&mut self.check_setup);
+                    ui.add_space(8.0);
+                    ui.add(&mut self.transfer);
+                    ui.add_space(8.0);
                     ui.add(&mut self.clients_tab);
                 });
             });
@@ -0,0 +1,70 @@
+use std::sync::{Arc, Mutex};
+
+use eframe::egui::{self, Response, Ui, Widget};
+use libalembic::{
+    doctor,
+    settings::AlembicSettings,
+    validation::{Severity, ValidationResult},
+};
+
+/// Check the selected client, DLL, server and account, and show what's
+/// wrong with them and how to fix it
+#[derive(Default)]
+pub struct SettingsCheckSetup {
+    result: Option<ValidationResult>,
+}
+
+impl Widget for &mut SettingsCheckSetup {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::CollapsingHeader::new("Check Setup")
+            .show(ui, |ui| {
+                let Some(s) = ui.data_mut(|data| {
+                    data.get_persisted::<Arc<Mutex<AlembicSettings>>>(egui::Id::new("settings"))
+                }) else {
+                    ui.label("Failed to reach application backend.");
+                    return;
+                };
+
+                if ui.button("Check setup").clicked() {
+                    self.result = Some(doctor::check_setup(&s.lock().unwrap()));
+                }
+
+                let Some(result) = &self.result else {
+                    return;
+                };
+
+                ui.add_space(4.0);
+                for finding in &result.findings {
+                    let color = match finding.severity {
+                        Severity::Info => ui.visuals().text_color(),
+                        Severity::Warning => ui.visuals().warn_fg_color,
+                        Severity::Error => ui.visuals().error_fg_color,
+                    };
+
+                    ui.horizontal_wrapped(|ui| {
+                        ui.colored_label(color, format!("[{}]", finding.severity));
+                        ui.strong(&finding.subject);
+                        ui.label(&finding.message);
+                    })
+                    .response
+                    .on_hover_text(finding.code);
+
+                    if let Some(fix) = &finding.fix {
+                        ui.indent(finding.code, |ui| ui.label(format!("Fix: {}", fix)));
+                    }
+                }
+
+                ui.add_space(4.0);
+                match result.worst() {
+                    Some(Severity::Error) => {
+                        ui.label("Launching will fail until the errors are fixed.")
+                    }
+                    Some(Severity::Warning) => {
+                        ui.label("Launching should work, but check the warnings.")
+                    }
+                    _ => ui.label("Everything looks good."),
+                };
+            })
+            .header_response
+    }
+}
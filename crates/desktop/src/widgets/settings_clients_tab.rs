@@ -5,15 +5,20 @@ use libalembic::{
     client_config::{LaunchCommand, WindowsClientConfig, WineClientConfig},
     inject_config::DllType,
     inject_config::InjectConfig,
-    scanner,
-    settings::{AlembicSettings, ClientConfigType},
+    settings::{AlembicSettings, ClientConfigType, new_id},
 };
 
-use super::components::centered_text;
+use super::{
+    client_scan::ClientScan, components::centered_text, decal_plugins::DecalPlugins,
+    dll_inspection::DllInspectionView,
+};
 use egui_extras::{Column, TableBuilder};
 
 pub struct SettingsClientsTab {
     pub selected_index: Option<usize>,
+    pub client_scan: ClientScan,
+    pub decal_plugins: DecalPlugins,
+    pub dll_inspection: DllInspectionView,
 }
 
 impl Widget for &mut SettingsClientsTab {
@@ -33,7 +38,8 @@ impl Widget for &mut SettingsClientsTab {
                         let mut env = std::collections::HashMap::new();
                         env.insert("WINEPREFIX".to_string(), "/path/to/prefix".to_string());
 
-                        settings.clients.push(ClientConfigType::Wine(WineClientConfig {
+                        let new_client = ClientConfigType::Wine(WineClientConfig {
+                            id: new_id(),
                             name: "New Client".to_string(),
                             client_path: std::path::PathBuf::from(
                                 "C:\\Turbine\\Asheron's Call\\acclient.exe",
@@ -45,45 +51,17 @@ impl Widget for &mut SettingsClientsTab {
                             },
                             dlls: Vec::new(),
                             selected_dll: None,
-                        }));
+                            fingerprint: None,
+                        });
 
+                        let _ = settings.modify(|settings| {
+                            settings.selected_client = Some(new_client.id().to_string());
+                            settings.clients.push(new_client);
+                        });
                         self.selected_index = Some(settings.clients.len() - 1);
-                        let _ = settings.save();
                     }
 
-                    if ui.button("Discover Clients").clicked() {
-                        match scanner::scan_all() {
-                            Ok(discovered) => {
-                                let had_no_clients = settings.clients.is_empty();
-                                let mut added_count = 0;
-
-                                for config in discovered {
-                                    let already_exists =
-                                        settings.clients.iter().any(|existing| {
-                                            existing.install_path() == config.install_path()
-                                        });
-
-                                    if !already_exists {
-                                        let should_select =
-                                            had_no_clients && added_count == 0;
-                                        settings.add_client(config, should_select);
-                                        settings.is_configured = true;
-                                        added_count += 1;
-                                    }
-                                }
-
-                                if added_count > 0 {
-                                    let _ = settings.save();
-                                    println!("Added {} new client(s)", added_count);
-                                } else {
-                                    println!("No new clients found");
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Error scanning for clients: {}", e);
-                            }
-                        }
-                    }
+                    self.client_scan.show(ui, &mut settings);
                 });
 
                 ui.add_space(8.0);
@@ -103,6 +81,8 @@ impl Widget for &mut SettingsClientsTab {
                 let mut did_update = false;
                 let mut delete_client = false;
 
+                let mut clicked_client: Option<String> = None;
+
                 egui::SidePanel::left("clients_list")
                     .resizable(true)
                     .default_width(180.0)
@@ -115,11 +95,17 @@ impl Widget for &mut SettingsClientsTab {
                                 let label = format!("{} ({})", client.name(), type_str);
                                 if ui.selectable_label(selected, label).clicked() {
                                     self.selected_index = Some(i);
+                                    clicked_client = Some(client.id().to_string());
                                 }
                             }
                         });
                     });
 
+                // Picking a client to edit also makes it the selected client
+                if let Some(id) = clicked_client {
+                    let _ = settings.modify(|s| s.selected_client = Some(id));
+                }
+
                 egui::CentralPanel::default().show_inside(ui, |ui| {
                     let Some(idx) = self.selected_index else {
                         ui.centered_and_justified(|ui| {
@@ -130,6 +116,9 @@ impl Widget for &mut SettingsClientsTab {
                     if idx >= settings.clients.len() {
                         return;
                     }
+                    // settings.modify reloads config.json, so changes find
+                    // the client by ID rather than by its index here
+                    let client_id = settings.clients[idx].id().to_string();
 
                     egui::ScrollArea::vertical().show(ui, |ui| {
                         // Delete button at top
@@ -152,12 +141,14 @@ impl Widget for &mut SettingsClientsTab {
                                 ui.selectable_value(&mut selected_wine, false, "Windows");
                             });
                         if selected_wine != current_is_wine {
+                            let old_id = settings.clients[idx].id().to_string();
                             let old_name = settings.clients[idx].name().to_string();
                             let old_path = settings.clients[idx].client_path().to_path_buf();
                             if selected_wine {
                                 let mut env = std::collections::HashMap::new();
                                 env.insert("WINEPREFIX".to_string(), "/path/to/prefix".to_string());
                                 settings.clients[idx] = ClientConfigType::Wine(WineClientConfig {
+                                    id: old_id,
                                     name: old_name,
                                     client_path: old_path,
                                     launch_command: LaunchCommand {
@@ -167,13 +158,16 @@ impl Widget for &mut SettingsClientsTab {
                                     },
                                     dlls: Vec::new(),
                                     selected_dll: None,
+                                    fingerprint: None,
                                 });
                             } else {
                                 settings.clients[idx] = ClientConfigType::Windows(WindowsClientConfig {
+                                    id: old_id,
                                     name: old_name,
                                     client_path: old_path,
                                     dlls: Vec::new(),
                                     selected_dll: None,
+                                    fingerprint: None,
                                 });
                             }
                             did_update = true;
@@ -288,6 +282,19 @@ impl Widget for &mut SettingsClientsTab {
                             did_update = true;
                         }
 
+                        ui.horizontal(|ui| {
+                            match settings.clients[idx].fingerprint() {
+                                Some(fingerprint) => ui.label(format!("Build: {}", fingerprint)),
+                                None => ui.label("Build: not recorded"),
+                            };
+                            if ui.button("Refresh").clicked() {
+                                match settings.clients[idx].refresh_fingerprint() {
+                                    Ok(_) => did_update = true,
+                                    Err(e) => eprintln!("Error reading client build: {:#}", e),
+                                }
+                            }
+                        });
+
                         // DLL section
                         ui.add_space(12.0);
                         ui.separator();
@@ -301,23 +308,26 @@ impl Widget for &mut SettingsClientsTab {
                         // Scan button
                         ui.horizontal(|ui| {
                             if ui.button("Discover DLLs").clicked() {
-                                match libalembic::scanner::scan_for_decal_dlls(&settings.clients) {
+                                match libalembic::scanner::scan_for_decal_dlls(&settings.clients, &settings.scan) {
                                     Ok(discovered_dlls) => {
                                         if !discovered_dlls.is_empty() {
-                                            let had_no_dlls = settings
-                                                .get_client_dlls(idx)
-                                                .map(|dlls| dlls.is_empty())
-                                                .unwrap_or(true);
-
-                                            for dll in discovered_dlls {
-                                                settings.add_dll_to_client(idx, dll);
-                                            }
-
-                                            if had_no_dlls {
-                                                settings.select_dll_for_client(idx, Some(0));
-                                            }
-
-                                            let _ = settings.save();
+                                            let _ = settings.modify(|settings| {
+                                                let Some(idx) = settings.client_index(&client_id) else {
+                                                    return;
+                                                };
+                                                let had_no_dlls = settings
+                                                    .get_client_dlls(idx)
+                                                    .map(|dlls| dlls.is_empty())
+                                                    .unwrap_or(true);
+
+                                                for dll in discovered_dlls {
+                                                    settings.add_dll_to_client(idx, dll);
+                                                }
+
+                                                if had_no_dlls {
+                                                    settings.select_dll_for_client(idx, Some(0));
+                                                }
+                                            });
                                         }
                                     }
                                     Err(e) => {
@@ -328,16 +338,21 @@ impl Widget for &mut SettingsClientsTab {
 
                             if ui.button("New DLL").clicked() {
                                 let new_dll = InjectConfig {
+                                    id: new_id(),
                                     dll_path: std::path::PathBuf::from("C:\\Program Files\\Alembic\\Alembic.dll"),
                                     dll_type: DllType::Alembic,
                                     startup_function: None,
                                 };
-                                settings.add_dll_to_client(idx, new_dll);
-                                let dlls = settings.get_client_dlls(idx).map(|d| d.len()).unwrap_or(0);
-                                if dlls == 1 {
-                                    settings.select_dll_for_client(idx, Some(0));
-                                }
-                                let _ = settings.save();
+                                let _ = settings.modify(|settings| {
+                                    let Some(idx) = settings.client_index(&client_id) else {
+                                        return;
+                                    };
+                                    settings.add_dll_to_client(idx, new_dll);
+                                    let dlls = settings.get_client_dlls(idx).map(|d| d.len()).unwrap_or(0);
+                                    if dlls == 1 {
+                                        settings.select_dll_for_client(idx, Some(0));
+                                    }
+                                });
                             }
                         });
 
@@ -425,29 +440,49 @@ impl Widget for &mut SettingsClientsTab {
                             }
 
                             // Handle deletion
-                            if let Some(index) = to_remove {
-                                settings.remove_dll_from_client(idx, index);
-                                let _ = settings.save();
+                            if let Some(dll_id) = to_remove.and_then(|index| {
+                                settings.get_client_dlls(idx)?.get(index).map(|dll| dll.id.clone())
+                            }) {
+                                let _ = settings.modify(|settings| {
+                                    if let Some(idx) = settings.client_index(&client_id)
+                                        && let Some(index) = settings.clients[idx].dll_index(&dll_id)
+                                    {
+                                        settings.remove_dll_from_client(idx, index);
+                                    }
+                                });
                             }
                         }
+
+                        // Inspection of the selected DLL
+                        if let Some(dll) = settings.get_client_selected_dll(idx) {
+                            ui.add_space(12.0);
+                            self.dll_inspection.show(ui, &settings.clients[idx], dll);
+                        }
+
+                        // Decal plugins, for Wine clients that use Decal
+                        if settings.clients[idx].dlls().iter().any(|dll| dll.dll_type == DllType::Decal) {
+                            ui.add_space(12.0);
+                            self.decal_plugins.show(ui, &settings.clients[idx]);
+                        }
                     });
                 });
 
-                // Set selected_client before releasing the lock
-                settings.selected_client = self.selected_index;
-
                 // Handle deletion
                 if delete_client
                     && let Some(idx) = self.selected_index
                     && idx < settings.clients.len()
                 {
-                    settings.clients.remove(idx);
+                    let id = settings.clients[idx].id().to_string();
+                    let _ = settings.modify(|settings| {
+                        if let Some(index) = settings.client_index(&id) {
+                            settings.remove_client(index);
+                        }
+                    });
                     self.selected_index = if settings.clients.is_empty() {
                         None
                     } else {
                         Some(idx.min(settings.clients.len() - 1))
                     };
-                    did_update = true;
                 }
 
                 if did_update {
@@ -0,0 +1,149 @@
+use std::sync::{Arc, Mutex};
+
+use eframe::egui::{self, Response, Ui, Widget};
+use libalembic::{
+    credentials::CredentialStoreKind,
+    settings::{
+        AlembicSettings, ExportOptions, SettingsExport, export_settings, save_imported_passwords,
+    },
+};
+
+/// Export settings to a file, or merge in a file exported on another machine
+pub struct SettingsTransfer {
+    path: String,
+    include_passwords: bool,
+    skip_clients: bool,
+    password_store: CredentialStoreKind,
+    status: Option<String>,
+}
+
+impl Default for SettingsTransfer {
+    fn default() -> Self {
+        let path = std::env::home_dir()
+            .map(|home| home.join("alembic-export.json").display().to_string())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            include_passwords: false,
+            skip_clients: false,
+            password_store: CredentialStoreKind::default(),
+            status: None,
+        }
+    }
+}
+
+impl SettingsTransfer {
+    fn export(&self, settings: &AlembicSettings) -> anyhow::Result<String> {
+        let options = ExportOptions {
+            include_passwords: self.include_passwords,
+            skip_clients: self.skip_clients,
+        };
+        let export = export_settings(settings, options)?;
+        std::fs::write(&self.path, export.to_json()?)?;
+
+        Ok(format!(
+            "Exported {} servers, {} accounts, {} clients and {} profiles.",
+            export.servers.len(),
+            export.accounts.len(),
+            export.clients.len(),
+            export.profiles.len()
+        ))
+    }
+
+    fn import(&self, settings: &mut AlembicSettings) -> anyhow::Result<String> {
+        let import = SettingsExport::from_json(&std::fs::read_to_string(&self.path)?)?;
+
+        let mut summary = None;
+        settings.modify(|s| summary = Some(s.merge_import(&import)))?;
+        let summary = summary.expect("modify always runs its closure");
+
+        if !summary.passwords.is_empty() {
+            let credentials = save_imported_passwords(&summary.passwords, self.password_store)?;
+            settings.modify(|s| s.apply_imported_credentials(&credentials))?;
+        }
+
+        Ok(format!(
+            "Imported {} new servers, {} new accounts, {} new clients and {} new profiles. {} existing entries were updated.",
+            summary.servers_added,
+            summary.accounts_added,
+            summary.clients_added,
+            summary.profiles_added,
+            summary.servers_updated
+                + summary.accounts_updated
+                + summary.clients_merged
+                + summary.profiles_updated
+        ))
+    }
+}
+
+impl Widget for &mut SettingsTransfer {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::CollapsingHeader::new("Import / Export")
+            .show(ui, |ui| {
+                let Some(s) = ui.data_mut(|data| {
+                    data.get_persisted::<Arc<Mutex<AlembicSettings>>>(egui::Id::new("settings"))
+                }) else {
+                    ui.label("Failed to reach application backend.");
+                    return;
+                };
+                let mut settings = s.lock().unwrap();
+
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.add(egui::TextEdit::singleline(&mut self.path).desired_width(300.0));
+                });
+
+                ui.checkbox(&mut self.include_passwords, "Include passwords")
+                    .on_hover_text("Passwords are written to the file in plain text");
+                ui.checkbox(
+                    &mut self.skip_clients,
+                    "Leave out clients and profiles (their paths are usually machine-specific)",
+                );
+
+                ui.horizontal(|ui| {
+                    ui.label("Save imported passwords to:");
+                    egui::ComboBox::from_id_salt("ImportPasswordStore")
+                        .selected_text(self.password_store.to_string())
+                        .show_ui(ui, |ui| {
+                            for kind in CredentialStoreKind::ALL {
+                                ui.selectable_value(
+                                    &mut self.password_store,
+                                    kind,
+                                    kind.to_string(),
+                                );
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    let has_path = !self.path.trim().is_empty();
+
+                    if ui
+                        .add_enabled(has_path, egui::Button::new("Export"))
+                        .clicked()
+                    {
+                        self.status = Some(match self.export(&settings) {
+                            Ok(message) => message,
+                            Err(e) => format!("Export failed: {:#}", e),
+                        });
+                    }
+
+                    if ui
+                        .add_enabled(has_path, egui::Button::new("Import"))
+                        .clicked()
+                    {
+                        self.status = Some(match self.import(&mut settings) {
+                            Ok(message) => message,
+                            Err(e) => format!("Import failed: {:#}", e),
+                        });
+                    }
+                });
+
+                if let Some(status) = &self.status {
+                    ui.label(status);
+                }
+            })
+            .header_response
+    }
+}
@@ -43,9 +43,7 @@ impl TabContainer {
                 TabContent::Accounts(AccountsTab {
                     selected_tab: 0,
                     tabs: vec![
-                        AccountsTabContent::Main(AccountsMainTab {
-                            selected_server: None,
-                        }),
+                        AccountsTabContent::Main(AccountsMainTab::default()),
                         AccountsTabContent::Servers(AccountsServersTab {}),
                         AccountsTabContent::CommunityServers(AccountsCommunityServersTab::default()),
                     ],
@@ -5,9 +5,12 @@ use libalembic::settings::AlembicSettings;
 
 use crate::application::{AppPage, WizardPage};
 
-use super::components::{SettingsDLLPathEdit, SettingsGameClientPathEdit};
+use super::components::{LauncherImportEdit, SettingsDLLPathEdit, SettingsGameClientPathEdit};
 
-pub struct Wizard {}
+pub struct Wizard {
+    client_paths: SettingsGameClientPathEdit,
+    launcher_import: LauncherImportEdit,
+}
 
 impl Default for Wizard {
     fn default() -> Self {
@@ -17,7 +20,10 @@ impl Default for Wizard {
 
 impl Wizard {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            client_paths: SettingsGameClientPathEdit::default(),
+            launcher_import: LauncherImportEdit::default(),
+        }
     }
 }
 
@@ -49,8 +55,7 @@ impl Widget for &mut Wizard {
                                     )
                                 }) {
                                     let mut settings = s.lock().unwrap();
-                                    settings.is_configured = true;
-                                    let _ = settings.save();
+                                    let _ = settings.modify(|s| s.is_configured = true);
                                 };
 
                                 ui.memory_mut(|mem| {
@@ -93,8 +98,7 @@ impl Widget for &mut Wizard {
                                         )
                                     }) {
                                         let mut settings = s.lock().unwrap();
-                                        settings.is_configured = true;
-                                        let _ = settings.save();
+                                        let _ = settings.modify(|s| s.is_configured = true);
                                     };
 
                                     ui.memory_mut(|mem| {
@@ -124,9 +128,11 @@ impl Widget for &mut Wizard {
                 egui::CentralPanel::default().show_inside(ui, |ui| {
                     ui.heading("Setup");
                     ui.add_space(16.0);
-                    ui.add(&mut SettingsGameClientPathEdit {});
+                    ui.add(&mut self.client_paths);
                     ui.add_space(16.0);
                     ui.add(&mut SettingsDLLPathEdit {});
+                    ui.add_space(16.0);
+                    ui.add(&mut self.launcher_import);
                 });
             }
             WizardPage::Done => {
@@ -143,8 +149,7 @@ impl Widget for &mut Wizard {
                                     )
                                 }) {
                                     let mut settings = s.lock().unwrap();
-                                    settings.is_configured = true;
-                                    let _ = settings.save();
+                                    let _ = settings.modify(|s| s.is_configured = true);
                                 };
 
                                 ui.memory_mut(|mem| {
@@ -169,8 +174,7 @@ impl Widget for &mut Wizard {
                             ))
                         }) {
                             let mut settings = s.lock().unwrap();
-                            settings.is_configured = true;
-                            let _ = settings.save();
+                            let _ = settings.modify(|s| s.is_configured = true);
                         };
 
                         ui.memory_mut(|mem| {
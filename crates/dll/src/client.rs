@@ -4,7 +4,7 @@ use std::thread;
 use std::time::Duration;
 
 use libalembic::msg::client_server::ClientServerMessage;
-use libalembic::rpc::WorldClient;
+use libalembic::rpc::{RPC_PORT, WorldClient};
 use tarpc::{client as tarpc_client, context, tokio_serde::formats::Json};
 use tokio::sync::mpsc::error::TryRecvError;
 
@@ -21,7 +21,7 @@ pub fn ensure_client() -> anyhow::Result<()> {
     SHUTDOWN.store(false, Ordering::SeqCst);
 
     runtime.spawn(async move {
-        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 5000);
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), RPC_PORT);
         let transport = tarpc::serde_transport::tcp::connect(&addr, Json::default);
         let client: WorldClient = WorldClient::new(
             tarpc_client::Config::default(),
@@ -1,11 +1,98 @@
-use std::ffi::c_void;
-
-use libalembic::{acclient::PStringBase, msg::client_server::ClientServerMessage};
-use once_cell::sync::Lazy;
+use std::{
+    ffi::c_void,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use anyhow::bail;
+use libalembic::{
+    acclient::PStringBase,
+    hook_table::{CallingConvention, ValueType},
+    msg::client_server::ClientServerMessage,
+};
+use once_cell::sync::OnceCell;
 use retour::GenericDetour;
 
+use super::{DetourSignature, hook_address};
 use crate::ensure_channel;
 
+/// All three AddTextToScroll overloads are declared the same way
+const ADD_TEXT_TO_SCROLL_ARGS: &[ValueType] = &[
+    ValueType::Ptr,
+    ValueType::Ptr,
+    ValueType::U32,
+    ValueType::U8,
+    ValueType::U32,
+];
+
+/// The detours below, to check against the hook table before enabling them
+pub const DETOURS: &[DetourSignature] = &[
+    DetourSignature {
+        name: "AddTextToScroll_char_ptr",
+        calling_convention: CallingConvention::Thiscall,
+        args: ADD_TEXT_TO_SCROLL_ARGS,
+        returns: ValueType::I32,
+    },
+    DetourSignature {
+        name: "AddTextToScroll_char_ptr_ptr",
+        calling_convention: CallingConvention::Thiscall,
+        args: ADD_TEXT_TO_SCROLL_ARGS,
+        returns: ValueType::I32,
+    },
+    DetourSignature {
+        name: "AddTextToScroll_ushort_ptr_ptr",
+        calling_convention: CallingConvention::Thiscall,
+        args: ADD_TEXT_TO_SCROLL_ARGS,
+        returns: ValueType::I32,
+    },
+];
+
+/// Held while the chat detour is enabled or disabled. `enable` runs on its
+/// own thread, so without it the DLL could detach partway through.
+static TOGGLE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Set once `disable` runs as the DLL detaches, so a late `enable` doesn't
+/// hook the client after it's been cleaned up
+static DETACHING: AtomicBool = AtomicBool::new(false);
+
+/// Create and enable the chat detour Alembic uses, at the address
+/// `load_hooks` looked up
+pub fn enable() -> anyhow::Result<()> {
+    let address = hook_address("AddTextToScroll_ushort_ptr_ptr")?;
+
+    let _guard = TOGGLE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    if DETACHING.load(Ordering::SeqCst) {
+        bail!("The DLL is detaching");
+    }
+
+    unsafe {
+        let original: fn_AddTextToScroll_Impl_ushort_ptr_ptr = std::mem::transmute(address);
+        let hook = Hook_AddTextToScroll_ushort_ptr_ptr.get_or_try_init(|| {
+            GenericDetour::new(original, Hook_AddTextToScroll_Impl_ushort_ptr_ptr)
+        })?;
+        hook.enable()?;
+    }
+
+    Ok(())
+}
+
+/// Disable the chat detour `enable` enabled, if it got that far, and stop
+/// it from being enabled again
+pub fn disable() -> anyhow::Result<()> {
+    DETACHING.store(true, Ordering::SeqCst);
+    let _guard = TOGGLE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if let Some(hook) = Hook_AddTextToScroll_ushort_ptr_ptr.get()
+        && hook.is_enabled()
+    {
+        unsafe { hook.disable()? };
+    }
+
+    Ok(())
+}
+
 // char_ptr
 type fn_AddTextToScroll_Impl_char_ptr =
     extern "thiscall" fn(This: *mut c_void, text: *mut c_void, a: u32, b: u8, c: u32) -> i32;
@@ -35,15 +122,15 @@ extern "thiscall" fn Hook_AddTextToScroll_Impl_char_ptr(
             Err(err) => println!("error is {err}"),
         }
     }
-    Hook_AddTextToScroll_char_ptr.call(This, text, a, b, c)
+    // The detour can only be running if it was created
+    Hook_AddTextToScroll_char_ptr
+        .get()
+        .map_or(0, |hook| hook.call(This, text, a, b, c))
 }
 
-pub static Hook_AddTextToScroll_char_ptr: Lazy<GenericDetour<fn_AddTextToScroll_Impl_char_ptr>> =
-    Lazy::new(|| {
-        let address: isize = 0x004882F0 as isize;
-        let ori: fn_AddTextToScroll_Impl_char_ptr = unsafe { std::mem::transmute(address) };
-        return unsafe { GenericDetour::new(ori, Hook_AddTextToScroll_Impl_char_ptr).unwrap() };
-    });
+pub static Hook_AddTextToScroll_char_ptr: OnceCell<
+    GenericDetour<fn_AddTextToScroll_Impl_char_ptr>,
+> = OnceCell::new();
 
 // char_ptr_ptr
 type fn_AddTextToScroll_Impl_char_ptr_ptr =
@@ -75,16 +162,15 @@ extern "thiscall" fn Hook_AddTextToScroll_Impl_char_ptr_ptr(
         }
     }
 
-    Hook_AddTextToScroll_char_ptr_ptr.call(This, text, a, b, c)
+    // The detour can only be running if it was created
+    Hook_AddTextToScroll_char_ptr_ptr
+        .get()
+        .map_or(0, |hook| hook.call(This, text, a, b, c))
 }
 
-pub static Hook_AddTextToScroll_char_ptr_ptr: Lazy<
+pub static Hook_AddTextToScroll_char_ptr_ptr: OnceCell<
     GenericDetour<fn_AddTextToScroll_Impl_char_ptr_ptr>,
-> = Lazy::new(|| {
-    let address: isize = 0x004C3010 as isize;
-    let ori: fn_AddTextToScroll_Impl_char_ptr_ptr = unsafe { std::mem::transmute(address) };
-    return unsafe { GenericDetour::new(ori, Hook_AddTextToScroll_Impl_char_ptr_ptr).unwrap() };
-});
+> = OnceCell::new();
 
 // ushort_ptr_ptr
 type fn_AddTextToScroll_Impl_ushort_ptr_ptr =
@@ -116,13 +202,12 @@ extern "thiscall" fn Hook_AddTextToScroll_Impl_ushort_ptr_ptr(
         }
     }
 
-    Hook_AddTextToScroll_ushort_ptr_ptr.call(This, text, a, b, c)
+    // The detour can only be running if it was created
+    Hook_AddTextToScroll_ushort_ptr_ptr
+        .get()
+        .map_or(0, |hook| hook.call(This, text, a, b, c))
 }
 
-pub static Hook_AddTextToScroll_ushort_ptr_ptr: Lazy<
+pub static Hook_AddTextToScroll_ushort_ptr_ptr: OnceCell<
     GenericDetour<fn_AddTextToScroll_Impl_ushort_ptr_ptr>,
-> = Lazy::new(|| {
-    let address: isize = 0x005649F0 as isize;
-    let ori: fn_AddTextToScroll_Impl_ushort_ptr_ptr = unsafe { std::mem::transmute(address) };
-    return unsafe { GenericDetour::new(ori, Hook_AddTextToScroll_Impl_ushort_ptr_ptr).unwrap() };
-});
+> = OnceCell::new();
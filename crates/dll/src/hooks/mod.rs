@@ -1,2 +1,47 @@
 pub mod chat;
 pub mod net;
+
+use anyhow::Context;
+use libalembic::hook_table::{BuildHooks, CallingConvention, HookTable, ValueType};
+use once_cell::sync::OnceCell;
+
+/// How a detour in this crate is declared, to check against the hook table
+pub struct DetourSignature {
+    pub name: &'static str,
+    pub calling_convention: CallingConvention,
+    pub args: &'static [ValueType],
+    pub returns: ValueType,
+}
+
+/// The hooks for the client build this DLL was injected into
+static BUILD_HOOKS: OnceCell<&'static BuildHooks> = OnceCell::new();
+
+/// Look up the hooks for the client this DLL was injected into and check
+/// each detour matches its definition. Hooks with addresses from the table
+/// can only be enabled once this succeeds.
+pub fn load_hooks() -> anyhow::Result<()> {
+    let exe = std::env::current_exe().context("Failed to find the client executable")?;
+    let build = HookTable::builtin().for_client(&exe)?;
+
+    for detour in chat::DETOURS {
+        build
+            .hook(detour.name)
+            .with_context(|| format!("{} isn't defined for {}", detour.name, build.name))?
+            .check(detour.calling_convention, detour.args, detour.returns)?;
+    }
+
+    let _ = BUILD_HOOKS.set(build);
+    Ok(())
+}
+
+/// Address of a hook that `load_hooks` checked
+pub fn hook_address(name: &str) -> anyhow::Result<isize> {
+    let build = BUILD_HOOKS
+        .get()
+        .context("Hooks have to be loaded before they're enabled")?;
+    let hook = build
+        .hook(name)
+        .with_context(|| format!("{} isn't defined for {}", name, build.name))?;
+
+    Ok(hook.address as isize)
+}
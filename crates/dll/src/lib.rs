@@ -42,11 +42,16 @@ fn on_attach() -> Result<(), anyhow::Error> {
 
     unsafe { crate::hooks::net::Hook_Network_RecvFrom.enable().unwrap() }
     unsafe { crate::hooks::net::Hook_Network_SendTo.enable().unwrap() }
-    unsafe {
-        crate::hooks::chat::Hook_AddTextToScroll_ushort_ptr_ptr
-            .enable()
-            .unwrap()
-    }
+
+    // The chat hooks are at addresses that depend on the client build, and
+    // working out the build means reading acclient.exe. That's too slow to do
+    // here in DllMain, under the loader lock, so it happens on its own thread.
+    thread::spawn(
+        || match crate::hooks::load_hooks().and_then(|_| crate::hooks::chat::enable()) {
+            Ok(_) => unsafe { log_message("Chat hooks enabled") },
+            Err(error) => unsafe { log_message(&format!("Chat hooks not enabled: {error:#}")) },
+        },
+    );
 
     Ok(())
 }
@@ -54,11 +59,7 @@ fn on_attach() -> Result<(), anyhow::Error> {
 fn on_detach() -> anyhow::Result<()> {
     unsafe { crate::hooks::net::Hook_Network_RecvFrom.disable().unwrap() }
     unsafe { crate::hooks::net::Hook_Network_SendTo.disable().unwrap() }
-    unsafe {
-        crate::hooks::chat::Hook_AddTextToScroll_ushort_ptr_ptr
-            .disable()
-            .unwrap()
-    }
+    crate::hooks::chat::disable()?;
 
     shutdown_client()?;
 
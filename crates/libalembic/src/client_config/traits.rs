@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::path::{Path, PathBuf};
 
+use crate::fingerprint::ClientFingerprint;
 use crate::inject_config::InjectConfig;
 use crate::validation::ValidationResult;
 
@@ -61,6 +62,7 @@ pub trait ClientConfig {
     fn name(&self) -> &str;
     fn client_path(&self) -> &Path;
     fn launch_command(&self) -> Option<&LaunchCommand>;
+    fn fingerprint(&self) -> Option<&ClientFingerprint>;
 
     /// Validate that all paths in this config and the optional inject config exist.
     fn validate(&self, inject_config: Option<&InjectConfig>) -> ValidationResult;
@@ -72,6 +74,9 @@ pub trait ClientConfig {
     fn fmt_display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Name: {}", self.name())?;
         writeln!(f, "Client path: {}", self.client_path().display())?;
+        if let Some(fingerprint) = self.fingerprint() {
+            writeln!(f, "Client build: {}", fingerprint)?;
+        }
 
         if let Some(cmd) = self.launch_command() {
             writeln!(f, "Type: Wine")?;
@@ -1,4 +1,5 @@
 use super::traits::{ClientConfig, LaunchCommand};
+use crate::fingerprint::ClientFingerprint;
 use crate::inject_config::InjectConfig;
 use crate::validation::{ValidationResult, validate_native_path};
 use serde::{Deserialize, Serialize};
@@ -6,14 +7,19 @@ use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowsClientConfig {
+    /// Stable identifier used to reference this client from elsewhere in settings
+    pub id: String,
     pub name: String,
     pub client_path: PathBuf,
     /// DLL configurations for this client
     #[serde(default)]
     pub dlls: Vec<InjectConfig>,
-    /// Index of the currently selected DLL for this client
+    /// ID of the currently selected DLL for this client
     #[serde(default)]
-    pub selected_dll: Option<usize>,
+    pub selected_dll: Option<String>,
+    /// The acclient.exe build, as of when the client was added or last scanned
+    #[serde(default)]
+    pub fingerprint: Option<ClientFingerprint>,
 }
 
 impl ClientConfig for WindowsClientConfig {
@@ -25,16 +31,21 @@ impl ClientConfig for WindowsClientConfig {
         &self.client_path
     }
 
+    fn fingerprint(&self) -> Option<&ClientFingerprint> {
+        self.fingerprint.as_ref()
+    }
+
     fn launch_command(&self) -> Option<&LaunchCommand> {
         // Windows launches directly, no wrapper needed
         None
     }
 
     fn validate(&self, inject_config: Option<&InjectConfig>) -> ValidationResult {
-        let mut result = validate_native_path(&self.client_path, "Client executable");
+        let mut result =
+            validate_native_path(&self.client_path, "client-path", "Client executable");
 
         if let Some(dll) = inject_config {
-            result.merge(validate_native_path(&dll.dll_path, "DLL"));
+            result.merge(validate_native_path(&dll.dll_path, "dll-path", "DLL"));
         }
 
         result
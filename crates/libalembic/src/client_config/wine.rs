@@ -1,13 +1,17 @@
 use super::traits::{ClientConfig, LaunchCommand};
+use crate::fingerprint::ClientFingerprint;
 use crate::inject_config::InjectConfig;
 use crate::validation::{
     ValidationResult, is_windows_path, validate_native_path, validate_wine_path,
 };
+use crate::wine_paths::windows_to_unix_path;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WineClientConfig {
+    /// Stable identifier used to reference this client from elsewhere in settings
+    pub id: String,
     pub name: String,
     pub client_path: PathBuf,
     /// The launch command specifying program, args, and env vars
@@ -15,9 +19,12 @@ pub struct WineClientConfig {
     /// DLL configurations for this client
     #[serde(default)]
     pub dlls: Vec<InjectConfig>,
-    /// Index of the currently selected DLL for this client
+    /// ID of the currently selected DLL for this client
     #[serde(default)]
-    pub selected_dll: Option<usize>,
+    pub selected_dll: Option<String>,
+    /// The acclient.exe build, as of when the client was added or last scanned
+    #[serde(default)]
+    pub fingerprint: Option<ClientFingerprint>,
 }
 
 impl ClientConfig for WineClientConfig {
@@ -29,41 +36,46 @@ impl ClientConfig for WineClientConfig {
         &self.client_path
     }
 
+    fn fingerprint(&self) -> Option<&ClientFingerprint> {
+        self.fingerprint.as_ref()
+    }
+
     fn launch_command(&self) -> Option<&LaunchCommand> {
         Some(&self.launch_command)
     }
 
     fn validate(&self, inject_config: Option<&InjectConfig>) -> ValidationResult {
-        // For validation, we need to find the wine executable
-        // It could be the program itself (e.g., /usr/bin/wine) or
-        // passed via --command= arg (e.g., flatpak run --command=wine)
-        let wine_exe = self.get_wine_executable();
+        let prefix = self.wine_prefix();
 
         let mut result = ValidationResult::ok();
 
         // Validate client path
         if is_windows_path(&self.client_path) {
             result.merge(validate_wine_path(
-                &wine_exe,
+                &prefix,
                 &self.client_path,
-                &self.launch_command.env,
+                "client-path",
                 "Client executable",
             ));
         } else {
-            result.merge(validate_native_path(&self.client_path, "Client executable"));
+            result.merge(validate_native_path(
+                &self.client_path,
+                "client-path",
+                "Client executable",
+            ));
         }
 
         // Validate DLL if present
         if let Some(dll) = inject_config {
             if is_windows_path(&dll.dll_path) {
                 result.merge(validate_wine_path(
-                    &wine_exe,
+                    &prefix,
                     &dll.dll_path,
-                    &self.launch_command.env,
+                    "dll-path",
                     "DLL",
                 ));
             } else {
-                result.merge(validate_native_path(&dll.dll_path, "DLL"));
+                result.merge(validate_native_path(&dll.dll_path, "dll-path", "DLL"));
             }
         }
 
@@ -72,18 +84,23 @@ impl ClientConfig for WineClientConfig {
 }
 
 impl WineClientConfig {
-    /// Get the wine executable path for validation purposes.
-    /// Handles both direct wine paths and flatpak --command=wine patterns.
-    fn get_wine_executable(&self) -> PathBuf {
-        // Check if any arg contains --command=wine (flatpak pattern)
-        for arg in &self.launch_command.args {
-            if arg.starts_with("--command=") {
-                let cmd = arg.strip_prefix("--command=").unwrap();
-                return PathBuf::from(cmd);
-            }
+    /// The prefix this client runs in: `WINEPREFIX`, or `~/.wine` like Wine
+    /// itself when it isn't set
+    pub fn wine_prefix(&self) -> PathBuf {
+        match self.launch_command.env.get("WINEPREFIX") {
+            Some(prefix) => PathBuf::from(prefix),
+            None => PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".wine"),
+        }
+    }
+
+    /// Where a client or DLL path is on the host. Windows paths are resolved
+    /// through the prefix's drives.
+    pub fn host_path(&self, path: &Path) -> Option<PathBuf> {
+        if is_windows_path(path) {
+            windows_to_unix_path(&self.wine_prefix(), path).ok()
+        } else {
+            Some(path.to_path_buf())
         }
-        // Otherwise, the program itself is wine
-        self.launch_command.program.clone()
     }
 }
 
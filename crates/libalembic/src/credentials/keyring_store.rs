@@ -0,0 +1,33 @@
+use anyhow::Context;
+
+use super::CredentialStore;
+
+/// Service name passwords are filed under in the OS keyring
+const KEYRING_SERVICE: &str = "alembic";
+
+/// Passwords kept in the platform keyring (Keychain, Credential Manager or the
+/// Secret Service)
+pub struct KeyringStore;
+
+impl CredentialStore for KeyringStore {
+    fn get(&self, account_id: &str) -> anyhow::Result<Option<String>> {
+        match keyring::Entry::new(KEYRING_SERVICE, account_id)?.get_password() {
+            Ok(password) => Ok(Some(password)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).context("Failed to read password from the system keyring"),
+        }
+    }
+
+    fn set(&self, account_id: &str, password: &str) -> anyhow::Result<()> {
+        keyring::Entry::new(KEYRING_SERVICE, account_id)?
+            .set_password(password)
+            .context("Failed to save password to the system keyring")
+    }
+
+    fn delete(&self, account_id: &str) -> anyhow::Result<()> {
+        match keyring::Entry::new(KEYRING_SERVICE, account_id)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e).context("Failed to remove password from the system keyring"),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Storage for account passwords.
+//!
+//! Settings only hold a `CredentialRef` for each account. The password itself
+//! lives in the OS keyring or in an encrypted vault file next to config.json
+//! and is looked up when a client is launched.
+
+mod keyring_store;
+mod vault;
+
+use std::{fmt, str::FromStr};
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Account;
+
+pub use keyring_store::KeyringStore;
+pub use vault::{
+    VAULT_PASSPHRASE_ENV, VaultStore, get_vault_path, is_vault_unlocked, lock_vault, unlock_vault,
+    vault_exists,
+};
+
+/// A place passwords can be kept, keyed by account ID
+pub trait CredentialStore {
+    /// Look up a password, returning `None` if nothing is stored for the account
+    fn get(&self, account_id: &str) -> anyhow::Result<Option<String>>;
+    fn set(&self, account_id: &str, password: &str) -> anyhow::Result<()>;
+    fn delete(&self, account_id: &str) -> anyhow::Result<()>;
+}
+
+/// Where an account's password is stored
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(tag = "store", rename_all = "lowercase")]
+pub enum CredentialRef {
+    /// Stored directly in config.json. Only used for settings written before
+    /// credential stores existed, or when explicitly requested.
+    Plaintext { password: String },
+    /// Stored in the OS keyring
+    Keyring,
+    /// Stored in the encrypted vault file
+    Vault,
+}
+
+impl CredentialRef {
+    pub fn kind(&self) -> CredentialStoreKind {
+        match self {
+            CredentialRef::Plaintext { .. } => CredentialStoreKind::Plaintext,
+            CredentialRef::Keyring => CredentialStoreKind::Keyring,
+            CredentialRef::Vault => CredentialStoreKind::Vault,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CredentialStoreKind {
+    Plaintext,
+    #[default]
+    Keyring,
+    Vault,
+}
+
+impl CredentialStoreKind {
+    pub const ALL: [CredentialStoreKind; 3] = [
+        CredentialStoreKind::Keyring,
+        CredentialStoreKind::Vault,
+        CredentialStoreKind::Plaintext,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CredentialStoreKind::Plaintext => "plaintext",
+            CredentialStoreKind::Keyring => "keyring",
+            CredentialStoreKind::Vault => "vault",
+        }
+    }
+
+    /// Reference to this store for an account with no password saved yet
+    pub fn empty_ref(&self) -> CredentialRef {
+        match self {
+            CredentialStoreKind::Plaintext => CredentialRef::Plaintext {
+                password: String::new(),
+            },
+            CredentialStoreKind::Keyring => CredentialRef::Keyring,
+            CredentialStoreKind::Vault => CredentialRef::Vault,
+        }
+    }
+}
+
+impl fmt::Display for CredentialStoreKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for CredentialStoreKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "plaintext" => Ok(CredentialStoreKind::Plaintext),
+            "keyring" => Ok(CredentialStoreKind::Keyring),
+            "vault" => Ok(CredentialStoreKind::Vault),
+            _ => bail!(
+                "Invalid credential store '{}'. Must be 'keyring', 'vault' or 'plaintext'.",
+                s
+            ),
+        }
+    }
+}
+
+fn open_store(kind: CredentialStoreKind) -> anyhow::Result<Box<dyn CredentialStore>> {
+    match kind {
+        CredentialStoreKind::Keyring => Ok(Box::new(KeyringStore)),
+        CredentialStoreKind::Vault => Ok(Box::new(VaultStore::unlocked()?)),
+        CredentialStoreKind::Plaintext => bail!("Plaintext passwords aren't kept in a store"),
+    }
+}
+
+fn find_password(account: &Account) -> anyhow::Result<Option<String>> {
+    match &account.credential {
+        CredentialRef::Plaintext { password } => Ok(Some(password.clone())),
+        other => open_store(other.kind())?.get(&account.id),
+    }
+}
+
+/// Look up the password for an account
+pub fn get_password(account: &Account) -> anyhow::Result<String> {
+    match find_password(account)? {
+        Some(password) => Ok(password),
+        None => bail!(
+            "No password is saved in the {} for account '{}'",
+            account.credential.kind(),
+            account.username
+        ),
+    }
+}
+
+/// Save the password for an account in `kind`, returning the reference to
+/// keep in settings
+pub fn set_password(
+    account_id: &str,
+    kind: CredentialStoreKind,
+    password: &str,
+) -> anyhow::Result<CredentialRef> {
+    match kind {
+        CredentialStoreKind::Plaintext => Ok(CredentialRef::Plaintext {
+            password: password.to_string(),
+        }),
+        CredentialStoreKind::Keyring => {
+            KeyringStore.set(account_id, password)?;
+            Ok(CredentialRef::Keyring)
+        }
+        CredentialStoreKind::Vault => {
+            VaultStore::unlocked()?.set(account_id, password)?;
+            Ok(CredentialRef::Vault)
+        }
+    }
+}
+
+/// Remove an account's password from whichever store holds it
+pub fn delete_password(account: &Account) -> anyhow::Result<()> {
+    match &account.credential {
+        CredentialRef::Plaintext { .. } => Ok(()),
+        other => open_store(other.kind())?.delete(&account.id),
+    }
+}
+
+/// Move an account's password to another store, returning the new reference.
+///
+/// The old copy is only removed once the new one has been written. Accounts
+/// without a saved password just switch stores.
+pub fn move_password(
+    account: &Account,
+    kind: CredentialStoreKind,
+) -> anyhow::Result<CredentialRef> {
+    if account.credential.kind() == kind {
+        return Ok(account.credential.clone());
+    }
+
+    let Some(password) = find_password(account)? else {
+        return Ok(kind.empty_ref());
+    };
+    let credential = set_password(&account.id, kind, &password)?;
+    delete_password(account)?;
+
+    Ok(credential)
+}
+
+/// What `secure_plaintext_passwords` managed to do
+#[derive(Debug, Default)]
+pub struct SecuredPasswords {
+    /// Indexes of the accounts whose passwords were moved
+    pub moved: Vec<usize>,
+    /// How many passwords are still in plaintext because of `error`
+    pub remaining: usize,
+    pub error: Option<anyhow::Error>,
+}
+
+/// Move accounts' plaintext passwords into the default store, so settings
+/// only hold references. Stops moving at the first failure, like when
+/// there's no keyring on this machine, leaving the rest in plaintext.
+pub fn secure_plaintext_passwords(accounts: &mut [Account]) -> SecuredPasswords {
+    let mut secured = SecuredPasswords::default();
+
+    for (index, account) in accounts.iter_mut().enumerate() {
+        let CredentialRef::Plaintext { password } = &account.credential else {
+            continue;
+        };
+        if password.is_empty() {
+            continue;
+        }
+
+        if secured.error.is_some() {
+            secured.remaining += 1;
+            continue;
+        }
+
+        match move_password(account, CredentialStoreKind::default()) {
+            Ok(credential) => {
+                account.credential = credential;
+                secured.moved.push(index);
+            }
+            Err(e) => {
+                secured.error = Some(e);
+                secured.remaining += 1;
+            }
+        }
+    }
+
+    secured
+}
@@ -0,0 +1,332 @@
+//! Password vault encrypted with a master passphrase.
+//!
+//! The vault is a JSON file next to config.json. Each entry is encrypted on
+//! its own with XChaCha20-Poly1305, using a key derived from the passphrase
+//! with Argon2id and the account ID as associated data so entries can't be
+//! swapped between accounts. A known value encrypted with the same key lets a
+//! wrong passphrase be reported as such.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::{Context, anyhow, bail};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chacha20poly1305::{
+    Key, KeyInit, XChaCha20Poly1305, XNonce,
+    aead::{Aead, Payload},
+};
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use super::CredentialStore;
+use crate::settings::{get_settings_file_path, persist};
+
+/// Environment variable checked for the passphrase when the vault is locked
+pub const VAULT_PASSPHRASE_ENV: &str = "ALEMBIC_VAULT_PASSPHRASE";
+
+const VAULT_FILE_NAME: &str = "vault.json";
+const VAULT_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const CHECK_PLAINTEXT: &[u8] = b"alembic vault";
+const CHECK_AAD: &[u8] = b"check";
+
+type VaultKey = [u8; 32];
+
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    salt: String,
+    /// `CHECK_PLAINTEXT` encrypted with the vault key
+    check: Sealed,
+    /// Encrypted passwords keyed by account ID
+    entries: BTreeMap<String, Sealed>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct KdfParams {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Key for the vault unlocked in this process, if any
+static UNLOCKED: Mutex<Option<(PathBuf, VaultKey)>> = Mutex::new(None);
+
+/// Passwords kept in the encrypted vault file
+pub struct VaultStore {
+    path: PathBuf,
+    key: VaultKey,
+}
+
+impl VaultStore {
+    /// Create a new, empty vault at `path`
+    pub fn create(path: &Path, passphrase: &str) -> anyhow::Result<Self> {
+        let _lock = persist::lock_settings_file(path)?;
+
+        if path.exists() {
+            bail!("A vault already exists at {}", path.display());
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        let kdf = KdfParams::default();
+        let key = derive_key(passphrase, &salt, kdf)?;
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            kdf,
+            salt: BASE64.encode(salt),
+            check: seal(&key, CHECK_AAD, CHECK_PLAINTEXT)?,
+            entries: BTreeMap::new(),
+        };
+        write_vault_file(path, &file)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            key,
+        })
+    }
+
+    /// Open the vault at `path`, failing if the passphrase is wrong
+    pub fn open(path: &Path, passphrase: &str) -> anyhow::Result<Self> {
+        let file = read_vault_file(path)?;
+        let salt = BASE64.decode(&file.salt).context("Vault salt is corrupt")?;
+        let key = derive_key(passphrase, &salt, file.kdf)?;
+
+        if unseal(&key, CHECK_AAD, &file.check).is_err() {
+            bail!("Incorrect vault passphrase");
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            key,
+        })
+    }
+
+    /// The vault unlocked in this process.
+    ///
+    /// If it hasn't been unlocked yet, the passphrase is taken from
+    /// `ALEMBIC_VAULT_PASSPHRASE` when that is set.
+    pub fn unlocked() -> anyhow::Result<Self> {
+        let path = get_vault_path()?;
+
+        if let Some((unlocked_path, key)) = UNLOCKED.lock().unwrap().as_ref()
+            && *unlocked_path == path
+        {
+            return Ok(Self { path, key: *key });
+        }
+
+        match std::env::var(VAULT_PASSPHRASE_ENV) {
+            Ok(passphrase) => {
+                unlock_vault(&passphrase)?;
+                Self::unlocked()
+            }
+            Err(_) => bail!(
+                "The password vault is locked. Unlock it first or set {}.",
+                VAULT_PASSPHRASE_ENV
+            ),
+        }
+    }
+
+    fn modify(&self, f: impl FnOnce(&mut BTreeMap<String, Sealed>)) -> anyhow::Result<()> {
+        let _lock = persist::lock_settings_file(&self.path)?;
+
+        let mut file = read_vault_file(&self.path)?;
+        f(&mut file.entries);
+
+        write_vault_file(&self.path, &file)
+    }
+}
+
+impl CredentialStore for VaultStore {
+    fn get(&self, account_id: &str) -> anyhow::Result<Option<String>> {
+        let file = read_vault_file(&self.path)?;
+        let Some(sealed) = file.entries.get(account_id) else {
+            return Ok(None);
+        };
+        let password = unseal(&self.key, account_id.as_bytes(), sealed)?;
+
+        String::from_utf8(password)
+            .map(Some)
+            .context("Vault entry is not valid UTF-8")
+    }
+
+    fn set(&self, account_id: &str, password: &str) -> anyhow::Result<()> {
+        let sealed = seal(&self.key, account_id.as_bytes(), password.as_bytes())?;
+
+        self.modify(|entries| {
+            entries.insert(account_id.to_string(), sealed);
+        })
+    }
+
+    fn delete(&self, account_id: &str) -> anyhow::Result<()> {
+        self.modify(|entries| {
+            entries.remove(account_id);
+        })
+    }
+}
+
+/// Path to vault.json. The file may not exist yet.
+pub fn get_vault_path() -> anyhow::Result<PathBuf> {
+    Ok(get_settings_file_path()?.with_file_name(VAULT_FILE_NAME))
+}
+
+pub fn vault_exists() -> bool {
+    get_vault_path().is_ok_and(|path| path.exists())
+}
+
+/// Unlock the vault for the rest of this process, creating it with
+/// `passphrase` if it doesn't exist yet
+pub fn unlock_vault(passphrase: &str) -> anyhow::Result<()> {
+    let path = get_vault_path()?;
+
+    let vault = if path.exists() {
+        VaultStore::open(&path, passphrase)?
+    } else {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        VaultStore::create(&path, passphrase)?
+    };
+
+    *UNLOCKED.lock().unwrap() = Some((vault.path, vault.key));
+
+    Ok(())
+}
+
+/// Forget the unlocked vault key
+pub fn lock_vault() {
+    *UNLOCKED.lock().unwrap() = None;
+}
+
+pub fn is_vault_unlocked() -> bool {
+    let Ok(path) = get_vault_path() else {
+        return false;
+    };
+
+    UNLOCKED
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|(unlocked_path, _)| *unlocked_path == path)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], kdf: KdfParams) -> anyhow::Result<VaultKey> {
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, None)
+        .map_err(|e| anyhow!("Invalid vault key derivation parameters: {}", e))?;
+
+    let mut key = VaultKey::default();
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Failed to derive vault key: {}", e))?;
+
+    Ok(key)
+}
+
+fn seal(key: &VaultKey, aad: &[u8], plaintext: &[u8]) -> anyhow::Result<Sealed> {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let ciphertext = XChaCha20Poly1305::new(Key::from_slice(key))
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| anyhow!("Failed to encrypt vault entry"))?;
+
+    Ok(Sealed {
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn unseal(key: &VaultKey, aad: &[u8], sealed: &Sealed) -> anyhow::Result<Vec<u8>> {
+    let nonce = BASE64.decode(&sealed.nonce)?;
+    let ciphertext = BASE64.decode(&sealed.ciphertext)?;
+
+    if nonce.len() != NONCE_LEN {
+        bail!("Vault entry has an invalid nonce");
+    }
+
+    XChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| anyhow!("Failed to decrypt vault entry"))
+}
+
+fn read_vault_file(path: &Path) -> anyhow::Result<VaultFile> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let file: VaultFile = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    if file.version > VAULT_VERSION {
+        bail!(
+            "Vault is version {} but this version of Alembic only supports up to version {}. Please upgrade Alembic.",
+            file.version,
+            VAULT_VERSION
+        );
+    }
+
+    Ok(file)
+}
+
+fn write_vault_file(path: &Path, file: &VaultFile) -> anyhow::Result<()> {
+    persist::write_private(path, &serde_json::to_string_pretty(file)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn vault_round_trips_and_rejects_wrong_passphrase() {
+        let dir = TempDir::new("vault");
+        let path = dir.join(VAULT_FILE_NAME);
+
+        let vault = VaultStore::create(&path, "correct horse").unwrap();
+        vault.set("account", "hunter2").unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains("hunter2"));
+
+        let reopened = VaultStore::open(&path, "correct horse").unwrap();
+        assert_eq!(reopened.get("account").unwrap().as_deref(), Some("hunter2"));
+        assert!(VaultStore::open(&path, "wrong").is_err());
+
+        reopened.delete("account").unwrap();
+        assert_eq!(reopened.get("account").unwrap(), None);
+    }
+}
@@ -0,0 +1,282 @@
+//! Decal's install location and its plugins, services and filters, as
+//! registered in a Wine prefix.
+//!
+//! Decal keeps a key for each component under `Software\Decal\<kind>\{CLSID}`
+//! with the component's name as the default value and an `Enabled` DWORD.
+//! Components without `Enabled` are loaded, so they count as enabled. The
+//! component's DLL comes from its COM registration.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+
+use crate::wine_paths::windows_to_unix_path;
+use crate::wine_registry::{RegValue, RegistryFile};
+
+/// Where Decal keeps its settings. 32-bit programs see `Wow6432Node` as
+/// `Software` in 64-bit prefixes.
+const DECAL_KEYS: [&str; 2] = [r"Software\Decal", r"Software\Wow6432Node\Decal"];
+
+/// Where COM classes are registered, for 32-bit classes in either kind of
+/// prefix
+const CLSID_KEYS: [&str; 3] = [
+    r"Software\Classes\CLSID",
+    r"Software\Classes\Wow6432Node\CLSID",
+    r"Software\Wow6432Node\Classes\CLSID",
+];
+
+fn system_registry(prefix: &Path) -> PathBuf {
+    prefix.join("system.reg")
+}
+
+/// Find where Decal is installed in a Wine prefix from the prefix's registry.
+/// Returns `None` if the registry doesn't say, like when the registry was
+/// reset after Decal was installed.
+pub fn install_dir(prefix: &Path) -> Option<PathBuf> {
+    let registry = RegistryFile::load(&system_registry(prefix)).ok()?;
+
+    DECAL_KEYS.iter().find_map(|root| {
+        let agent_path = registry
+            .value(&format!(r"{}\Agent", root), "AgentPath")?
+            .as_str()?;
+        windows_to_unix_path(prefix, Path::new(agent_path.trim_end_matches('\\'))).ok()
+    })
+}
+
+/// The kinds of component Decal loads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecalComponentKind {
+    Plugin,
+    Service,
+    Filter,
+    NetworkFilter,
+}
+
+impl DecalComponentKind {
+    pub const ALL: [DecalComponentKind; 4] = [
+        DecalComponentKind::Plugin,
+        DecalComponentKind::Service,
+        DecalComponentKind::Filter,
+        DecalComponentKind::NetworkFilter,
+    ];
+
+    /// The key under Decal's settings that lists components of this kind
+    fn registry_key(self) -> &'static str {
+        match self {
+            DecalComponentKind::Plugin => "Plugins",
+            DecalComponentKind::Service => "Services",
+            DecalComponentKind::Filter => "FileFilters",
+            DecalComponentKind::NetworkFilter => "NetworkFilters",
+        }
+    }
+}
+
+impl fmt::Display for DecalComponentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecalComponentKind::Plugin => write!(f, "Plugin"),
+            DecalComponentKind::Service => write!(f, "Service"),
+            DecalComponentKind::Filter => write!(f, "Filter"),
+            DecalComponentKind::NetworkFilter => write!(f, "Network Filter"),
+        }
+    }
+}
+
+/// A plugin, service or filter registered with Decal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecalComponent {
+    pub kind: DecalComponentKind,
+    /// The component's COM class ID, like `{5C2C3E4D-...}`
+    pub clsid: String,
+    pub name: String,
+    /// Windows path of the DLL that implements the component, if registered
+    pub dll_path: Option<PathBuf>,
+    pub enabled: bool,
+    /// Path of the component's key in the registry
+    key: String,
+}
+
+/// Decal's components in a Wine prefix, which can be enabled or disabled and
+/// saved back to the prefix
+pub struct DecalRegistry {
+    path: PathBuf,
+    registry: RegistryFile,
+}
+
+impl DecalRegistry {
+    pub fn load(prefix: &Path) -> anyhow::Result<Self> {
+        let path = system_registry(prefix);
+        let registry = RegistryFile::load(&path)?;
+
+        Ok(Self { path, registry })
+    }
+
+    /// Write changes back to the prefix. Wine only reads the registry when
+    /// the prefix starts, and overwrites it when it stops, so the game
+    /// should be closed first.
+    pub fn save(&self) -> anyhow::Result<()> {
+        self.registry.save(&self.path)
+    }
+
+    fn dll_path(&self, clsid: &str) -> Option<PathBuf> {
+        CLSID_KEYS.iter().find_map(|classes| {
+            let server = format!(r"{}\{}\InprocServer32", classes, clsid);
+            let path = self.registry.value(&server, "")?.as_str()?;
+            Some(PathBuf::from(path))
+        })
+    }
+
+    /// All registered components, grouped by kind
+    pub fn components(&self) -> Vec<DecalComponent> {
+        let mut components = vec![];
+
+        for kind in DecalComponentKind::ALL {
+            for root in DECAL_KEYS {
+                let list = format!(r"{}\{}", root, kind.registry_key());
+
+                for key in self.registry.subkeys(&list) {
+                    let clsid = key.name().to_string();
+                    let name = key
+                        .value("")
+                        .and_then(RegValue::as_str)
+                        .unwrap_or(&clsid)
+                        .to_string();
+                    let enabled = key
+                        .value("Enabled")
+                        .and_then(RegValue::as_dword)
+                        .is_none_or(|enabled| enabled != 0);
+
+                    components.push(DecalComponent {
+                        kind,
+                        dll_path: self.dll_path(&clsid),
+                        clsid,
+                        name,
+                        enabled,
+                        key: key.path().to_string(),
+                    });
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Find a component by index (from `components`), CLSID or name
+    pub fn find(&self, key: &str) -> anyhow::Result<DecalComponent> {
+        let components = self.components();
+        let clsid = key.trim_matches(|c| c == '{' || c == '}');
+
+        let found = key
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| components.get(index))
+            .or_else(|| {
+                components.iter().find(|c| {
+                    c.clsid
+                        .trim_matches(|c| c == '{' || c == '}')
+                        .eq_ignore_ascii_case(clsid)
+                })
+            })
+            .or_else(|| components.iter().find(|c| c.name.eq_ignore_ascii_case(key)));
+
+        match found {
+            Some(component) => Ok(component.clone()),
+            None => bail!("No Decal plugin, service or filter matches '{}'", key),
+        }
+    }
+
+    pub fn set_enabled(&mut self, component: &DecalComponent, enabled: bool) -> anyhow::Result<()> {
+        let Some(key) = self.registry.key_mut(&component.key) else {
+            bail!("{} is no longer registered", component.name);
+        };
+
+        key.set_value("Enabled", RegValue::Dword(enabled as u32));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::fs;
+
+    #[test]
+    fn lists_and_toggles_components() {
+        let prefix = TempDir::new("decal");
+        fs::write(
+            prefix.join("system.reg"),
+            r#"WINE REGISTRY Version 2
+
+[Software\\Classes\\Wow6432Node\\CLSID\\{AAAA0001-0000-0000-0000-000000000000}\\InprocServer32] 1700000000
+@="C:\\Games\\VTank\\VTank.dll"
+
+[Software\\Wow6432Node\\Decal\\Agent] 1700000000
+"AgentPath"="C:\\Program Files\\Decal 3.0\\"
+
+[Software\\Wow6432Node\\Decal\\NetworkFilters\\{AAAA0003-0000-0000-0000-000000000000}] 1700000000
+@="Echo Filter"
+
+[Software\\Wow6432Node\\Decal\\Plugins\\{AAAA0001-0000-0000-0000-000000000000}] 1700000000
+@="VTank"
+"Enabled"=dword:00000001
+
+[Software\\Wow6432Node\\Decal\\Plugins\\{AAAA0002-0000-0000-0000-000000000000}] 1700000000
+@="Old Plugin"
+"Enabled"=dword:00000000
+"#,
+        )
+        .expect("registry should be written");
+
+        assert_eq!(
+            install_dir(&prefix),
+            Some(prefix.join("drive_c/Program Files/Decal 3.0"))
+        );
+
+        let mut decal = DecalRegistry::load(&prefix).expect("registry should load");
+        let summary: Vec<_> = decal
+            .components()
+            .into_iter()
+            .map(|c| (c.kind, c.name, c.enabled))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (DecalComponentKind::Plugin, "VTank".to_string(), true),
+                (DecalComponentKind::Plugin, "Old Plugin".to_string(), false),
+                (
+                    DecalComponentKind::NetworkFilter,
+                    "Echo Filter".to_string(),
+                    true
+                ),
+            ]
+        );
+
+        let vtank = decal
+            .find("aaaa0001-0000-0000-0000-000000000000")
+            .expect("found by CLSID");
+        assert_eq!(
+            vtank.dll_path,
+            Some(PathBuf::from(r"C:\Games\VTank\VTank.dll"))
+        );
+        assert_eq!(
+            decal.find("old plugin").expect("found by name").clsid,
+            decal.components()[1].clsid
+        );
+
+        decal
+            .set_enabled(&vtank, false)
+            .expect("vtank is registered");
+        let filter = decal.find("2").expect("found by index");
+        decal
+            .set_enabled(&filter, false)
+            .expect("filter is registered");
+        decal.save().expect("registry should save");
+
+        let reloaded = DecalRegistry::load(&prefix).expect("registry should load");
+        assert!(reloaded.components().iter().all(|c| !c.enabled));
+    }
+}
@@ -0,0 +1,266 @@
+//! Checking a DLL before it's injected.
+//!
+//! cork only finds out a DLL is the wrong architecture, or doesn't export its
+//! startup function, once it's inside acclient.exe, and a DLL whose imports
+//! can't be found just fails to load there. Reading the DLL first turns each
+//! of those into a finding that says what's wrong.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    inject_config::InjectConfig,
+    pe::{Machine, PeFile},
+    settings::ClientConfigType,
+    validation::{Finding, ValidationResult},
+};
+
+/// What a DLL is built for, what it exports and what it needs
+#[derive(Debug, Clone)]
+pub struct DllInspection {
+    pub machine: Machine,
+    pub exports: Vec<String>,
+    pub imports: Vec<String>,
+    /// Imported DLLs that aren't in any of the searched directories, or
+    /// `None` if the system directory couldn't be found to search
+    pub missing_imports: Option<Vec<String>>,
+}
+
+impl DllInspection {
+    /// Read the DLL at `path` (a host path) and look for its imports in
+    /// `search_dirs`, if given
+    pub fn read(path: &Path, search_dirs: Option<&[PathBuf]>) -> Result<Self> {
+        let pe = PeFile::load(path)?;
+        let exports = pe.exports()?;
+        let imports = pe.imports()?;
+        let missing_imports = search_dirs.map(|dirs| find_missing(&imports, dirs));
+
+        Ok(Self {
+            machine: pe.machine(),
+            exports,
+            imports,
+            missing_imports,
+        })
+    }
+
+    /// Read a DLL configured for a client, looking for its imports where
+    /// Windows does when cork loads it into acclient.exe: the client's
+    /// directory, then the system and Windows directories
+    pub fn for_client(client: &ClientConfigType, dll: &InjectConfig) -> Result<Self> {
+        let path = client
+            .host_path(&dll.dll_path)
+            .with_context(|| format!("Couldn't find {} on this machine", dll.dll_path.display()))?;
+
+        let search_dirs = system_dirs(client).map(|system| {
+            let mut dirs: Vec<PathBuf> = client
+                .host_path(&client.install_path())
+                .into_iter()
+                .collect();
+            dirs.extend(system);
+            dirs
+        });
+
+        Self::read(&path, search_dirs.as_deref())
+    }
+
+    pub fn is_x86(&self) -> bool {
+        self.machine == Machine::X86
+    }
+
+    pub fn exports_function(&self, function: &str) -> bool {
+        self.exports.iter().any(|export| export == function)
+    }
+}
+
+/// The system and Windows directories a 32-bit client loads DLLs from, on the
+/// host. 64-bit Windows and win64 prefixes keep 32-bit DLLs in SysWOW64.
+fn system_dirs(client: &ClientConfigType) -> Option<Vec<PathBuf>> {
+    let windows = match client {
+        ClientConfigType::Windows(_) => PathBuf::from(std::env::var_os("SystemRoot")?),
+        ClientConfigType::Wine(c) => c.host_path(Path::new(r"C:\windows"))?,
+    };
+
+    let system = ["SysWOW64", "System32"]
+        .iter()
+        .map(|name| find_entry(&windows, name))
+        .find(|dir| dir.is_dir())?;
+
+    Some(vec![system, windows])
+}
+
+/// Find `name` in `dir`, ignoring case like Windows does
+fn find_entry(dir: &Path, name: &str) -> PathBuf {
+    fs::read_dir(dir)
+        .ok()
+        .and_then(|entries| {
+            entries
+                .flatten()
+                .find(|entry| entry.file_name().eq_ignore_ascii_case(name))
+        })
+        .map(|entry| entry.path())
+        .unwrap_or_else(|| dir.join(name))
+}
+
+/// API sets like `api-ms-win-crt-runtime-l1-1-0.dll` are resolved by the
+/// loader rather than found on disk
+fn is_api_set(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.starts_with("api-ms-") || name.starts_with("ext-ms-")
+}
+
+fn find_missing(imports: &[String], dirs: &[PathBuf]) -> Vec<String> {
+    let present: HashSet<String> = dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .map(|entry| entry.file_name().to_string_lossy().to_ascii_lowercase())
+        .collect();
+
+    imports
+        .iter()
+        .filter(|name| !is_api_set(name) && !present.contains(&name.to_ascii_lowercase()))
+        .cloned()
+        .collect()
+}
+
+/// Check a DLL is 32-bit, exports its startup function and that the DLLs it
+/// imports can be found. Missing DLLs are reported by
+/// `ClientConfigType::validate`, so they're skipped here.
+pub fn validate_dll(client: &ClientConfigType, dll: &InjectConfig) -> ValidationResult {
+    if client.host_path(&dll.dll_path).is_none_or(|p| !p.exists()) {
+        return ValidationResult::ok();
+    }
+
+    let inspection = match DllInspection::for_client(client, dll) {
+        Ok(inspection) => inspection,
+        Err(e) => {
+            return Finding::error("dll-invalid", "DLL", format!("{:#}", e))
+                .with_fix("Check the DLL path points to a Windows DLL")
+                .into();
+        }
+    };
+
+    if !inspection.is_x86() {
+        return Finding::error(
+            "dll-arch",
+            "DLL",
+            format!(
+                "{} is {}, but acclient.exe is 32-bit x86",
+                dll.dll_path.display(),
+                inspection.machine
+            ),
+        )
+        .with_fix("Use the 32-bit build of the DLL")
+        .into();
+    }
+
+    let mut result = ValidationResult::ok();
+
+    match &dll.startup_function {
+        Some(function) if inspection.exports_function(function) => result.add(Finding::info(
+            "dll-exports",
+            "DLL",
+            format!("is 32-bit x86 and exports {}", function),
+        )),
+        Some(function) => result.add(
+            Finding::error(
+                "dll-exports",
+                "DLL",
+                format!("doesn't export the startup function {}", function),
+            )
+            .with_fix(format!(
+                "Check the DLL type is right for {}, or change its startup function",
+                dll.dll_path.display()
+            )),
+        ),
+        None => result.add(Finding::info("dll-arch", "DLL", "is 32-bit x86")),
+    }
+
+    match &inspection.missing_imports {
+        Some(missing) if !missing.is_empty() => result.add(
+            Finding::warning(
+                "dll-imports",
+                "DLL",
+                format!("imports DLLs that can't be found: {}", missing.join(", ")),
+            )
+            .with_fix(
+                "Install what provides them, like the Visual C++ runtime, or copy them next to acclient.exe",
+            ),
+        ),
+        Some(_) => result.add(Finding::info(
+            "dll-imports",
+            "DLL",
+            format!("all {} imported DLLs were found", inspection.imports.len()),
+        )),
+        None => {}
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_config::{LaunchCommand, WineClientConfig};
+    use crate::inject_config::DllType;
+    use crate::pe::tests::{add_imports, build_pe};
+    use crate::test_util::TempDir;
+    use crate::validation::Severity;
+
+    #[test]
+    fn finds_missing_imports_in_the_prefix() {
+        let prefix = TempDir::new("dll-inspect");
+        fs::create_dir_all(prefix.join("drive_c/windows/syswow64")).expect("prefix should exist");
+        fs::create_dir_all(prefix.join("drive_c/AC")).expect("client dir should exist");
+        fs::write(prefix.join("drive_c/windows/syswow64/kernel32.dll"), b"")
+            .expect("system dll should be written");
+        fs::write(prefix.join("drive_c/AC/Helper.dll"), b"").expect("helper should be written");
+
+        let mut data = build_pe(0x014c, &["DecalStartup"], None);
+        add_imports(
+            &mut data,
+            &[
+                "KERNEL32.dll",
+                "helper.dll",
+                "api-ms-win-crt-runtime-l1-1-0.dll",
+                "MSVCP140.dll",
+            ],
+        );
+        fs::write(prefix.join("drive_c/AC/Inject.dll"), data).expect("dll should be written");
+
+        let client = ClientConfigType::Wine(WineClientConfig {
+            id: "client".to_string(),
+            name: "Test".to_string(),
+            client_path: PathBuf::from(r"C:\AC\acclient.exe"),
+            launch_command: LaunchCommand::new("wine")
+                .env("WINEPREFIX", prefix.display().to_string()),
+            dlls: vec![],
+            selected_dll: None,
+            fingerprint: None,
+        });
+        let dll = InjectConfig {
+            id: "decal".to_string(),
+            dll_path: PathBuf::from(r"C:\AC\Inject.dll"),
+            dll_type: DllType::Decal,
+            startup_function: Some("DecalStartup".to_string()),
+        };
+
+        let inspection = DllInspection::for_client(&client, &dll).expect("dll should be read");
+        assert!(inspection.is_x86());
+        assert!(inspection.exports_function("DecalStartup"));
+        assert_eq!(inspection.imports.len(), 4);
+        assert_eq!(
+            inspection.missing_imports,
+            Some(vec!["MSVCP140.dll".to_string()])
+        );
+
+        let result = validate_dll(&client, &dll);
+        assert_eq!(result.worst(), Some(Severity::Warning));
+        assert!(result.findings.iter().any(|f| f.code == "dll-imports"));
+    }
+}
@@ -0,0 +1,300 @@
+//! Checks of everything a launch depends on, for `alembic doctor` and the
+//! desktop's Check setup view.
+//!
+//! Each check adds findings to a `ValidationResult`, including passing ones,
+//! so the report shows what was checked as well as what's wrong.
+
+use std::{
+    net::{Ipv4Addr, TcpListener},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    client_config::WineClientConfig,
+    launcher::find_cork,
+    pe::{Machine, PeFile},
+    rpc::RPC_PORT,
+    settings::{AlembicSettings, ClientConfigType},
+    validation::{Finding, ValidationResult},
+    wine_registry::RegistryFile,
+};
+
+/// Check cork.exe is where the launchers look for it and is 32-bit, since it
+/// has to inject into 32-bit acclient.exe
+pub fn check_cork() -> ValidationResult {
+    let path = match find_cork() {
+        Ok(path) => path,
+        Err(e) => {
+            return Finding::error("cork-missing", "cork.exe", e.to_string())
+                .with_fix("Reinstall Alembic, or build cork for a 32-bit Windows target")
+                .into();
+        }
+    };
+
+    match PeFile::load(&path).map(|pe| pe.machine()) {
+        Ok(Machine::X86) => Finding::info(
+            "cork",
+            "cork.exe",
+            format!("found at {} (32-bit x86)", path.display()),
+        )
+        .into(),
+        Ok(machine) => Finding::error(
+            "cork-arch",
+            "cork.exe",
+            format!(
+                "{} is {}, but it must be 32-bit x86",
+                path.display(),
+                machine
+            ),
+        )
+        .with_fix("Build cork for i686, not x86_64")
+        .into(),
+        Err(e) => Finding::error("cork-arch", "cork.exe", format!("{:#}", e))
+            .with_fix("Reinstall Alembic, or build cork again")
+            .into(),
+    }
+}
+
+/// Find a program the way a shell would: as given if it has a directory,
+/// otherwise on `PATH`
+fn find_program(program: &Path) -> Option<PathBuf> {
+    if program.components().count() > 1 {
+        return program.is_file().then(|| program.to_path_buf());
+    }
+
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| path.is_file())
+}
+
+/// Check the program a Wine client launches with (wine itself, or a wrapper
+/// like flatpak) exists
+pub fn check_wine_program(client: &WineClientConfig) -> ValidationResult {
+    let program = &client.launch_command.program;
+
+    match find_program(program) {
+        Some(path) => Finding::info(
+            "wine",
+            "Wine program",
+            format!("found at {}", path.display()),
+        )
+        .into(),
+        None => Finding::error(
+            "wine-missing",
+            "Wine program",
+            format!("{} not found", program.display()),
+        )
+        .with_fix("Install Wine, or set the client's program to where it's installed")
+        .into(),
+    }
+}
+
+/// Check a Wine prefix exists, has been started at least once, and what
+/// architecture it is
+pub fn check_wine_prefix(prefix: &Path) -> ValidationResult {
+    if !prefix.is_dir() {
+        return Finding::error(
+            "prefix-missing",
+            "Wine prefix",
+            format!("{} doesn't exist", prefix.display()),
+        )
+        .with_fix("Set WINEPREFIX to an existing prefix, or run winecfg with it to create one")
+        .into();
+    }
+
+    let registry = match RegistryFile::load(&prefix.join("system.reg")) {
+        Ok(registry) => registry,
+        Err(e) => {
+            return Finding::warning("prefix-uninitialized", "Wine prefix", format!("{:#}", e))
+                .with_fix("Run winecfg with this WINEPREFIX once to set the prefix up")
+                .into();
+        }
+    };
+
+    match registry.arch() {
+        Some("win32") => Finding::info(
+            "prefix-arch",
+            "Wine prefix",
+            format!("{} is 32-bit (win32)", prefix.display()),
+        )
+        .into(),
+        Some("win64") => Finding::info(
+            "prefix-arch",
+            "Wine prefix",
+            format!(
+                "{} is 64-bit (win64); acclient.exe runs under WoW64",
+                prefix.display()
+            ),
+        )
+        .into(),
+        arch => Finding::warning(
+            "prefix-arch",
+            "Wine prefix",
+            format!(
+                "{} has an unknown architecture ({})",
+                prefix.display(),
+                arch.unwrap_or("no #arch line in system.reg")
+            ),
+        )
+        .into(),
+    }
+}
+
+/// Check a server and account are selected and belong together
+pub fn check_server_and_account(settings: &AlembicSettings) -> ValidationResult {
+    let mut result = ValidationResult::ok();
+
+    let server = settings.get_selected_server();
+    match server {
+        Some(server) if server.port.parse::<u16>().is_err() => result.add(
+            Finding::error(
+                "server-port",
+                "Server",
+                format!("{} has an invalid port '{}'", server.name, server.port),
+            )
+            .with_fix("Edit the server's port"),
+        ),
+        Some(server) => result.add(Finding::info(
+            "server",
+            "Server",
+            format!("{} ({}:{})", server.name, server.hostname, server.port),
+        )),
+        None => result.add(
+            Finding::error("no-server", "Server", "no server is selected")
+                .with_fix("Add or select a server"),
+        ),
+    }
+
+    match settings.get_selected_account() {
+        Some(account) => match (server, settings.account_server(account)) {
+            (Some(server), Some(owner)) if owner.id != server.id => result.add(
+                Finding::warning(
+                    "account-server",
+                    "Account",
+                    format!(
+                        "{} is for {}, not the selected server {}",
+                        account.username, owner.name, server.name
+                    ),
+                )
+                .with_fix("Select an account for the selected server"),
+            ),
+            _ => result.add(Finding::info(
+                "account",
+                "Account",
+                account.username.clone(),
+            )),
+        },
+        None => result.add(
+            Finding::error("no-account", "Account", "no account is selected")
+                .with_fix("Add or select an account"),
+        ),
+    }
+
+    result
+}
+
+/// Check the port injected DLLs report back to is free. Something else
+/// listening there, like another Alembic, gets their messages instead.
+pub fn check_rpc_port() -> ValidationResult {
+    match TcpListener::bind((Ipv4Addr::LOCALHOST, RPC_PORT)) {
+        Ok(_) => Finding::info("rpc-port", "RPC port", format!("{} is free", RPC_PORT)).into(),
+        Err(e) => Finding::warning(
+            "rpc-port",
+            "RPC port",
+            format!("{} is in use: {}", RPC_PORT, e),
+        )
+        .with_fix("Close other copies of Alembic, or whatever else is using the port")
+        .into(),
+    }
+}
+
+/// Check the selected client, DLL, server and account and what they need to
+/// launch. The RPC port isn't checked since the desktop app holds it itself;
+/// see `check_rpc_port`.
+pub fn check_setup(settings: &AlembicSettings) -> ValidationResult {
+    let mut result = check_cork();
+
+    match settings.get_selected_client() {
+        Some(client) => {
+            if let ClientConfigType::Wine(wine) = client {
+                result.merge(check_wine_program(wine));
+                result.merge(check_wine_prefix(&wine.wine_prefix()));
+            }
+
+            result.merge(client.validate(settings.get_selected_dll()));
+        }
+        None => result.add(
+            Finding::error("no-client", "Client", "no client is selected")
+                .with_fix("Scan for clients, or add and select one"),
+        ),
+    }
+
+    result.merge(check_server_and_account(settings));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_config::LaunchCommand;
+    use crate::inject_config::{DllType, InjectConfig};
+    use crate::pe::tests::build_pe;
+    use crate::test_util::TempDir;
+    use crate::validation::Severity;
+    use std::fs;
+
+    #[test]
+    fn reports_problems_with_the_selected_setup() {
+        let prefix = TempDir::new("doctor");
+        fs::create_dir_all(prefix.join("drive_c/AC")).expect("prefix should be created");
+        fs::write(
+            prefix.join("system.reg"),
+            "WINE REGISTRY Version 2\n\n#arch=win64\n",
+        )
+        .expect("registry should be written");
+        fs::write(prefix.join("drive_c/AC/acclient.exe"), b"").expect("client should be written");
+        fs::write(
+            prefix.join("drive_c/AC/Inject.dll"),
+            build_pe(0x014c, &["DllMain"], None),
+        )
+        .expect("dll should be written");
+
+        let mut settings = AlembicSettings::new();
+        settings.add_client(
+            ClientConfigType::Wine(WineClientConfig {
+                id: "client".to_string(),
+                name: "Test".to_string(),
+                client_path: PathBuf::from(r"C:\AC\acclient.exe"),
+                launch_command: LaunchCommand::new("/nonexistent/wine")
+                    .env("WINEPREFIX", prefix.display().to_string()),
+                dlls: vec![InjectConfig {
+                    id: "decal".to_string(),
+                    dll_path: PathBuf::from(r"C:\AC\Inject.dll"),
+                    dll_type: DllType::Decal,
+                    startup_function: Some("DecalStartup".to_string()),
+                }],
+                selected_dll: Some("decal".to_string()),
+                fingerprint: None,
+            }),
+            true,
+        );
+
+        let result = check_setup(&settings);
+        let severity = |code: &str| {
+            result
+                .findings
+                .iter()
+                .find(|f| f.code == code)
+                .map(|f| f.severity)
+        };
+
+        assert_eq!(severity("wine-missing"), Some(Severity::Error));
+        assert_eq!(severity("prefix-arch"), Some(Severity::Info));
+        assert_eq!(severity("client-path"), Some(Severity::Info));
+        assert_eq!(severity("dll-path"), Some(Severity::Info));
+        assert_eq!(severity("dll-exports"), Some(Severity::Error));
+        assert_eq!(severity("no-server"), Some(Severity::Error));
+        assert_eq!(severity("no-account"), Some(Severity::Error));
+        assert!(!result.is_valid());
+    }
+}
@@ -0,0 +1,189 @@
+//! Identifying which build of acclient.exe a client is.
+//!
+//! DLLs like Alembic hook functions at fixed addresses that only exist in the
+//! build they were written for, so injecting them into another build crashes
+//! the client. The fingerprint is recorded when a client is added or scanned
+//! and checked again before launching.
+
+use std::{fmt, fs, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    inject_config::InjectConfig,
+    pe::PeFile,
+    validation::{Finding, ValidationResult},
+};
+
+/// What identifies a build of acclient.exe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientFingerprint {
+    /// File version from the version resource, like `0.0.11.6096`
+    pub version: Option<String>,
+    /// Linker timestamp from the PE header
+    pub timestamp: u32,
+    /// SHA-256 of the whole file, hex encoded
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl ClientFingerprint {
+    pub fn read(path: &Path) -> Result<Self> {
+        let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let sha256 = Sha256::digest(&data)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let size = data.len() as u64;
+
+        let pe =
+            PeFile::parse(data).with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        Ok(Self {
+            version: pe.file_version(),
+            timestamp: pe.timestamp(),
+            sha256,
+            size,
+        })
+    }
+
+    /// Whether both fingerprints are of the same file
+    pub fn is_same_file(&self, other: &ClientFingerprint) -> bool {
+        self.sha256 == other.sha256
+    }
+}
+
+impl fmt::Display for ClientFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "version {}", version)?,
+            None => write!(f, "unknown version")?,
+        }
+        write!(
+            f,
+            " (built 0x{:08x}, sha256 {})",
+            self.timestamp,
+            &self.sha256[..self.sha256.len().min(12)]
+        )
+    }
+}
+
+/// Whether a DLL can be injected into a client build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildSupport {
+    Supported,
+    /// The build isn't one the DLL is known to support, so it might not be
+    Unknown,
+}
+
+/// Check the client at `client_exe` (a host path) is a build the DLL
+/// supports, and that it's still the build that was recorded for it
+pub fn validate_client_build(
+    client_exe: &Path,
+    recorded: Option<&ClientFingerprint>,
+    inject_config: Option<&InjectConfig>,
+) -> ValidationResult {
+    let fingerprint = match ClientFingerprint::read(client_exe) {
+        Ok(fingerprint) => fingerprint,
+        Err(e) => {
+            return Finding::warning("client-build", "Client build", format!("{:#}", e))
+                .with_fix("Check the client path points to acclient.exe")
+                .into();
+        }
+    };
+
+    let mut result = ValidationResult::ok();
+
+    if let Some(recorded) = recorded
+        && !recorded.is_same_file(&fingerprint)
+    {
+        result.add(
+            Finding::warning(
+                "client-changed",
+                "Client build",
+                format!(
+                    "acclient.exe changed since it was added: was {}, now {}",
+                    recorded, fingerprint
+                ),
+            )
+            .with_fix("Scan for clients again to record the new build"),
+        );
+    }
+
+    let Some(dll) = inject_config else {
+        result.add(Finding::info(
+            "client-build",
+            "Client build",
+            fingerprint.to_string(),
+        ));
+        return result;
+    };
+
+    match dll.dll_type.supports(&fingerprint) {
+        BuildSupport::Supported => result.add(Finding::info(
+            "client-build",
+            "Client build",
+            format!("{}, supported by {}", fingerprint, dll.dll_type),
+        )),
+        BuildSupport::Unknown => result.add(
+            Finding::warning(
+                "client-build",
+                "Client build",
+                format!(
+                    "couldn't tell whether {} supports this client ({})",
+                    dll.dll_type, fingerprint
+                ),
+            )
+            .with_fix(format!(
+                "{} may not work with this client; launch without a DLL if it misbehaves",
+                dll.dll_type
+            )),
+        ),
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inject_config::DllType;
+    use crate::pe::tests::build_pe;
+    use crate::test_util::TempDir;
+    use crate::validation::Severity;
+    use std::path::PathBuf;
+
+    #[test]
+    fn checks_the_client_build_against_the_dll() {
+        let dir = TempDir::new("fingerprint");
+        let supported = dir.join("supported.exe");
+        let other = dir.join("other.exe");
+        fs::write(&supported, build_pe(0x014c, &[], Some([0, 0, 11, 6096])))
+            .expect("client should be written");
+        fs::write(&other, build_pe(0x014c, &[], Some([0, 0, 1, 1])))
+            .expect("client should be written");
+
+        let fingerprint = ClientFingerprint::read(&supported).expect("client should be read");
+        assert_eq!(fingerprint.version.as_deref(), Some("0.0.11.6096"));
+        assert_eq!(fingerprint.timestamp, 0x5000_0000);
+        assert_eq!(fingerprint.sha256.len(), 64);
+
+        let dll = |dll_type| InjectConfig {
+            id: "dll".to_string(),
+            dll_path: PathBuf::from(r"C:\Alembic.dll"),
+            dll_type,
+            startup_function: None,
+        };
+        let worst = |path: &Path, dll_type| {
+            validate_client_build(path, Some(&fingerprint), Some(&dll(dll_type))).worst()
+        };
+
+        assert_eq!(worst(&supported, DllType::Alembic), Some(Severity::Info));
+        // Builds missing from the hook table are only warned about, not refused
+        assert_eq!(worst(&other, DllType::Alembic), Some(Severity::Warning));
+        // Decal finds what it hooks itself, but the build still changed
+        assert_eq!(worst(&other, DllType::Decal), Some(Severity::Warning));
+    }
+}
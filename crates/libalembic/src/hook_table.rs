@@ -0,0 +1,517 @@
+//! Where the functions Alembic hooks are in each build of acclient.exe.
+//!
+//! The addresses, calling conventions and signatures live in a JSON file,
+//! `data/hooks.json`, keyed by the client build they were found in, so
+//! supporting another build or adding a hook to a known one doesn't need any
+//! code changes. The DLL looks its hooks up here once it knows which build
+//! it was injected into, and checks they match the detours it was built with.
+//!
+//! ```json
+//! {
+//!   "format_version": 1,
+//!   "builds": [
+//!     {
+//!       "name": "End of Retail",
+//!       "version": "0.0.11.6096",
+//!       "hooks": [
+//!         {
+//!           "name": "AddTextToScroll_char_ptr",
+//!           "address": "0x004882F0",
+//!           "calling_convention": "thiscall",
+//!           "args": ["ptr", "ptr", "u32", "u8", "u32"],
+//!           "returns": "i32"
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! A build is matched by any of `version`, `timestamp` and `sha256` from its
+//! `ClientFingerprint`; every one that's given has to match. Prefer all
+//! three, read from the real acclient.exe with `ClientFingerprint::read`.
+//! The shipped End of Retail entry only has a version so far, which is why
+//! clients that match no build are warned about rather than refused.
+
+use std::{cmp::Reverse, collections::HashSet, fmt, fs, path::Path};
+
+use anyhow::{Context, Result, bail};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer};
+
+use crate::{fingerprint::ClientFingerprint, pe::PeFile};
+
+/// Version of the definition format this reader understands
+pub const HOOK_TABLE_FORMAT_VERSION: u32 = 1;
+
+/// The definitions shipped with Alembic
+static BUILTIN: Lazy<HookTable> = Lazy::new(|| {
+    HookTable::parse(include_str!("../data/hooks.json"))
+        .expect("data/hooks.json should be a valid hook table")
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallingConvention {
+    Cdecl,
+    Stdcall,
+    Thiscall,
+    Fastcall,
+}
+
+impl fmt::Display for CallingConvention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallingConvention::Cdecl => write!(f, "cdecl"),
+            CallingConvention::Stdcall => write!(f, "stdcall"),
+            CallingConvention::Thiscall => write!(f, "thiscall"),
+            CallingConvention::Fastcall => write!(f, "fastcall"),
+        }
+    }
+}
+
+/// Types of arguments and return values. acclient.exe is 32-bit, so
+/// pointers are 32 bits wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    Void,
+    Ptr,
+    U8,
+    U16,
+    U32,
+    I8,
+    I16,
+    I32,
+    F32,
+    F64,
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ValueType::Void => "void",
+            ValueType::Ptr => "ptr",
+            ValueType::U8 => "u8",
+            ValueType::U16 => "u16",
+            ValueType::U32 => "u32",
+            ValueType::I8 => "i8",
+            ValueType::I16 => "i16",
+            ValueType::I32 => "i32",
+            ValueType::F32 => "f32",
+            ValueType::F64 => "f64",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A function to hook in one client build
+#[derive(Debug, Clone, Deserialize)]
+pub struct HookDefinition {
+    pub name: String,
+    /// Virtual address of the function in acclient.exe
+    #[serde(deserialize_with = "deserialize_hex")]
+    pub address: u32,
+    pub calling_convention: CallingConvention,
+    pub args: Vec<ValueType>,
+    pub returns: ValueType,
+}
+
+impl HookDefinition {
+    /// Check the hook is what a detour expects, since calling a function
+    /// with the wrong convention or arguments corrupts the client's stack
+    pub fn check(
+        &self,
+        calling_convention: CallingConvention,
+        args: &[ValueType],
+        returns: ValueType,
+    ) -> Result<()> {
+        if self.calling_convention != calling_convention
+            || self.args != args
+            || self.returns != returns
+        {
+            bail!(
+                "{} is defined as {}, but the detour is {}",
+                self.name,
+                describe(self.calling_convention, &self.args, self.returns),
+                describe(calling_convention, args, returns)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn describe(
+    calling_convention: CallingConvention,
+    args: &[ValueType],
+    returns: ValueType,
+) -> String {
+    let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+    format!(
+        "{} fn({}) -> {}",
+        calling_convention,
+        args.join(", "),
+        returns
+    )
+}
+
+/// A number written in hex like `"0x004882F0"`, as disassemblers show
+/// addresses, or as a plain number
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HexNumber {
+    Number(u32),
+    Text(String),
+}
+
+impl HexNumber {
+    fn value<E: serde::de::Error>(self) -> Result<u32, E> {
+        match self {
+            HexNumber::Number(number) => Ok(number),
+            HexNumber::Text(text) => {
+                let hex = text
+                    .strip_prefix("0x")
+                    .or_else(|| text.strip_prefix("0X"))
+                    .ok_or_else(|| E::custom(format!("'{}' isn't 0x-prefixed hex", text)))?;
+                u32::from_str_radix(hex, 16).map_err(E::custom)
+            }
+        }
+    }
+}
+
+fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    HexNumber::deserialize(deserializer)?.value()
+}
+
+fn deserialize_optional_hex<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u32>, D::Error> {
+    Option::<HexNumber>::deserialize(deserializer)?
+        .map(HexNumber::value)
+        .transpose()
+}
+
+/// The hooks for one client build
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuildHooks {
+    /// What the build is known as, like `End of Retail`
+    pub name: String,
+    pub version: Option<String>,
+    /// Linker timestamp, in hex like the client's fingerprint shows it
+    #[serde(default, deserialize_with = "deserialize_optional_hex")]
+    pub timestamp: Option<u32>,
+    pub sha256: Option<String>,
+    pub hooks: Vec<HookDefinition>,
+}
+
+impl BuildHooks {
+    /// Whether every key given for this build matches the fingerprint
+    pub fn matches(&self, fingerprint: &ClientFingerprint) -> bool {
+        self.matches_header(fingerprint.version.as_deref(), fingerprint.timestamp)
+            && self
+                .sha256
+                .as_ref()
+                .is_none_or(|s| s.eq_ignore_ascii_case(&fingerprint.sha256))
+    }
+
+    /// Whether `version` and `timestamp` match, for those given for this build
+    fn matches_header(&self, version: Option<&str>, timestamp: u32) -> bool {
+        self.version.as_deref().is_none_or(|v| version == Some(v))
+            && self.timestamp.is_none_or(|t| timestamp == t)
+    }
+
+    /// How many of `version`, `timestamp` and `sha256` are given
+    fn key_count(&self) -> usize {
+        [
+            self.version.is_some(),
+            self.timestamp.is_some(),
+            self.sha256.is_some(),
+        ]
+        .iter()
+        .filter(|&&given| given)
+        .count()
+    }
+
+    pub fn hook(&self, name: &str) -> Option<&HookDefinition> {
+        self.hooks.iter().find(|hook| hook.name == name)
+    }
+}
+
+/// Hook definitions for every known client build
+#[derive(Debug, Clone, Deserialize)]
+pub struct HookTable {
+    pub format_version: u32,
+    pub builds: Vec<BuildHooks>,
+}
+
+impl HookTable {
+    /// The definitions shipped with Alembic, from `data/hooks.json`
+    pub fn builtin() -> &'static HookTable {
+        &BUILTIN
+    }
+
+    pub fn parse(json: &str) -> Result<Self> {
+        let table: HookTable =
+            serde_json::from_str(json).context("Failed to parse the hook table")?;
+        table.validate()?;
+        Ok(table)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&json).with_context(|| format!("Invalid hook table in {}", path.display()))
+    }
+
+    /// Check the table is one this version of Alembic can use, and that each
+    /// build can be told apart and hooked safely
+    fn validate(&self) -> Result<()> {
+        if self.format_version != HOOK_TABLE_FORMAT_VERSION {
+            bail!(
+                "Hook table format version {} isn't supported (expected {})",
+                self.format_version,
+                HOOK_TABLE_FORMAT_VERSION
+            );
+        }
+
+        let mut keys = HashSet::new();
+        for build in &self.builds {
+            if build.key_count() == 0 {
+                bail!(
+                    "Build {} needs a version, timestamp or sha256 to match clients by",
+                    build.name
+                );
+            }
+            if let Some(sha256) = &build.sha256
+                && (sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()))
+            {
+                bail!("Build {} has an invalid sha256 '{}'", build.name, sha256);
+            }
+            let key = (
+                build.version.clone(),
+                build.timestamp,
+                build.sha256.as_ref().map(|s| s.to_ascii_lowercase()),
+            );
+            if !keys.insert(key) {
+                bail!("Build {} is defined more than once", build.name);
+            }
+
+            let mut names = HashSet::new();
+            for hook in &build.hooks {
+                if !names.insert(hook.name.as_str()) {
+                    bail!("Build {} defines {} more than once", build.name, hook.name);
+                }
+                if hook.address == 0 {
+                    bail!("{} in build {} has no address", hook.name, build.name);
+                }
+                if hook.args.contains(&ValueType::Void) {
+                    bail!("{} in build {} has a void argument", hook.name, build.name);
+                }
+                if hook.calling_convention == CallingConvention::Thiscall
+                    && hook.args.first() != Some(&ValueType::Ptr)
+                {
+                    bail!(
+                        "{} in build {} is thiscall, so its first argument must be a ptr",
+                        hook.name,
+                        build.name
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The hooks for a client build, if it's a known one. When several
+    /// builds match, the one matched by the most keys wins.
+    pub fn for_build(&self, fingerprint: &ClientFingerprint) -> Option<&BuildHooks> {
+        self.builds
+            .iter()
+            .filter(|build| build.matches(fingerprint))
+            .min_by_key(|build| Reverse(build.key_count()))
+    }
+
+    /// The hooks for the client at `path`, like `for_build`. Builds are told
+    /// apart by the version and timestamp in the client's headers, and the
+    /// whole file is only hashed when a build that could match has a
+    /// `sha256` to check, since that's slow to do as the client starts.
+    pub fn for_client(&self, path: &Path) -> Result<&BuildHooks> {
+        let pe = PeFile::load(path)?;
+        let version = pe.file_version();
+        let timestamp = pe.timestamp();
+
+        let candidates = || {
+            self.builds
+                .iter()
+                .filter(|build| build.matches_header(version.as_deref(), timestamp))
+        };
+
+        let build = if candidates().any(|build| build.sha256.is_some()) {
+            self.for_build(&ClientFingerprint::read(path)?)
+        } else {
+            candidates().min_by_key(|build| Reverse(build.key_count()))
+        };
+
+        match build {
+            Some(build) => Ok(build),
+            None => bail!(
+                "No hooks are defined for this client (version {}, built 0x{:08x})",
+                version.as_deref().unwrap_or("unknown"),
+                timestamp
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pe::tests::build_pe;
+    use crate::test_util::TempDir;
+
+    fn fingerprint(version: Option<&str>) -> ClientFingerprint {
+        ClientFingerprint {
+            version: version.map(str::to_string),
+            timestamp: 0x5000_0000,
+            sha256: "ab".repeat(32),
+            size: 0x600,
+        }
+    }
+
+    #[test]
+    fn parses_and_looks_up_hooks_by_build() {
+        let table = HookTable::parse(
+            r#"{
+                "format_version": 1,
+                "builds": [
+                    {
+                        "name": "End of Retail",
+                        "version": "0.0.11.6096",
+                        "hooks": [
+                            {
+                                "name": "AddTextToScroll",
+                                "address": "0x004882F0",
+                                "calling_convention": "thiscall",
+                                "args": ["ptr", "ptr", "u32", "u8", "u32"],
+                                "returns": "i32"
+                            }
+                        ]
+                    },
+                    {
+                        "name": "Exact",
+                        "version": "0.0.11.6096",
+                        "timestamp": "0x50000000",
+                        "sha256": "ABABABABABABABABABABABABABABABABABABABABABABABABABABABABABABABAB",
+                        "hooks": [
+                            {
+                                "name": "Exact",
+                                "address": 4096,
+                                "calling_convention": "stdcall",
+                                "args": [],
+                                "returns": "void"
+                            }
+                        ]
+                    }
+                ]
+            }"#,
+        )
+        .expect("table should parse");
+
+        // The build matched by more keys wins
+        assert_eq!(
+            table
+                .for_build(&fingerprint(Some("0.0.11.6096")))
+                .map(|b| b.name.as_str()),
+            Some("Exact")
+        );
+        let mut other = fingerprint(Some("0.0.11.6096"));
+        other.sha256 = "cd".repeat(32);
+        let build = table.for_build(&other).expect("version should match");
+        assert_eq!(build.name, "End of Retail");
+        assert!(table.for_build(&fingerprint(Some("0.0.1.1"))).is_none());
+        assert!(table.for_build(&fingerprint(None)).is_none());
+
+        let hook = build.hook("AddTextToScroll").expect("hook should exist");
+        assert_eq!(hook.address, 0x004882F0);
+        use ValueType::*;
+        assert!(
+            hook.check(CallingConvention::Thiscall, &[Ptr, Ptr, U32, U8, U32], I32)
+                .is_ok()
+        );
+        assert!(
+            hook.check(CallingConvention::Stdcall, &[Ptr, Ptr, U32, U8, U32], I32)
+                .is_err()
+        );
+
+        // Unknown versions, missing keys and bad hooks are rejected
+        for invalid in [
+            r#"{"format_version": 2, "builds": []}"#,
+            r#"{"format_version": 1, "builds": [{"name": "A", "hooks": []}]}"#,
+            r#"{"format_version": 1, "builds": [{"name": "A", "version": "1", "hooks": [
+                {"name": "f", "address": "4882F0", "calling_convention": "cdecl", "args": [], "returns": "void"}]}]}"#,
+            r#"{"format_version": 1, "builds": [{"name": "A", "version": "1", "hooks": [
+                {"name": "f", "address": 1, "calling_convention": "thiscall", "args": ["u32"], "returns": "void"}]}]}"#,
+        ] {
+            assert!(HookTable::parse(invalid).is_err(), "{}", invalid);
+        }
+
+        assert!(!HookTable::builtin().builds.is_empty());
+    }
+
+    #[test]
+    fn finds_the_hooks_for_a_client_file() {
+        let dir = TempDir::new("hook-table");
+        let client = dir.join("acclient.exe");
+        fs::write(&client, build_pe(0x014c, &[], Some([0, 0, 11, 6096])))
+            .expect("client should be written");
+        let sha256 = ClientFingerprint::read(&client)
+            .expect("client should be read")
+            .sha256;
+
+        let table = |builds: &[(&str, &str)]| {
+            let builds = builds
+                .iter()
+                .map(|(name, keys)| format!(r#"{{"name": "{}", {}, "hooks": []}}"#, name, keys))
+                .collect::<Vec<_>>()
+                .join(",");
+            HookTable::parse(&format!(
+                r#"{{"format_version": 1, "builds": [{}]}}"#,
+                builds
+            ))
+            .expect("table should parse")
+        };
+        let found = |table: &HookTable| {
+            table
+                .for_client(&client)
+                .map(|build| build.name.clone())
+                .ok()
+        };
+
+        let by_version = ("Version", r#""version": "0.0.11.6096""#);
+        let by_timestamp = (
+            "Timestamp",
+            r#""version": "0.0.11.6096", "timestamp": "0x50000000""#,
+        );
+        let exact = format!(r#""version": "0.0.11.6096", "sha256": "{}""#, sha256);
+        let other = format!(
+            r#""version": "0.0.11.6096", "sha256": "{}""#,
+            "cd".repeat(32)
+        );
+
+        assert_eq!(
+            found(&table(&[by_version, by_timestamp])).as_deref(),
+            Some("Timestamp")
+        );
+        assert_eq!(
+            found(&table(&[by_version, ("Exact", &exact)])).as_deref(),
+            Some("Exact")
+        );
+        assert_eq!(
+            found(&table(&[by_version, ("Other", &other)])).as_deref(),
+            Some("Version")
+        );
+        assert_eq!(found(&table(&[("Other", &other)])), None);
+        assert_eq!(found(&table(&[("Old", r#""version": "0.0.1.1""#)])), None);
+    }
+}
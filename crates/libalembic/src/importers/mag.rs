@@ -0,0 +1,130 @@
+//! Mag-ACClientLauncher saves its servers, and the accounts for each server,
+//! to `Servers.xml` in its settings directory.
+
+use std::{fs, path::Path};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use super::{LauncherAccount, LauncherImport, password, server};
+
+const SERVERS_FILE: &str = "Servers.xml";
+
+#[derive(Deserialize)]
+struct ServerList {
+    #[serde(rename = "Server", default)]
+    servers: Vec<Server>,
+}
+
+#[derive(Deserialize)]
+struct Server {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Address", alias = "Host", alias = "Hostname")]
+    address: String,
+    #[serde(rename = "Port", default)]
+    port: Option<String>,
+    #[serde(rename = "Accounts", default)]
+    accounts: Option<AccountList>,
+}
+
+#[derive(Deserialize)]
+struct AccountList {
+    #[serde(rename = "Account", default)]
+    accounts: Vec<Account>,
+}
+
+#[derive(Deserialize)]
+struct Account {
+    #[serde(rename = "UserName", alias = "Username")]
+    username: String,
+    #[serde(rename = "Password", default)]
+    password: Option<String>,
+}
+
+pub(super) fn read(path: &Path) -> anyhow::Result<LauncherImport> {
+    let file = if path.is_dir() {
+        path.join(SERVERS_FILE)
+    } else {
+        path.to_path_buf()
+    };
+
+    let contents =
+        fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+
+    parse(&contents)
+}
+
+fn parse(contents: &str) -> anyhow::Result<LauncherImport> {
+    let list: ServerList =
+        serde_xml_rs::from_str(contents).context("Not a Mag-ACClientLauncher server list")?;
+    let mut import = LauncherImport::default();
+
+    for s in list.servers {
+        // Older versions saved the port as part of the address
+        let (hostname, port) = match (&s.port, s.address.rsplit_once(':')) {
+            (Some(port), _) if !port.trim().is_empty() => (s.address.as_str(), port.as_str()),
+            (_, Some((hostname, port))) => (hostname, port),
+            _ => (s.address.as_str(), ""),
+        };
+        let imported = server(&s.name, hostname, port);
+
+        for account in s.accounts.map(|a| a.accounts).unwrap_or_default() {
+            import.accounts.push(LauncherAccount {
+                username: account.username.trim().to_string(),
+                password: password(account.password),
+                server_id: Some(imported.id.clone()),
+            });
+        }
+
+        import.servers.push(imported);
+    }
+
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_servers_with_their_accounts() {
+        let import = parse(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<ArrayOfServer>
+  <Server>
+    <Name>Coldeve</Name>
+    <Address>coldeve.ac</Address>
+    <Port>9000</Port>
+    <Accounts>
+      <Account>
+        <UserName>alice</UserName>
+        <Password>secret</Password>
+      </Account>
+      <Account>
+        <UserName>bob</UserName>
+        <Password />
+      </Account>
+    </Accounts>
+  </Server>
+  <Server>
+    <Name>Local</Name>
+    <Address>127.0.0.1:9000</Address>
+  </Server>
+</ArrayOfServer>"#,
+        )
+        .unwrap();
+
+        assert_eq!(import.servers.len(), 2);
+        assert_eq!(import.servers[1].hostname, "127.0.0.1");
+        assert_eq!(import.servers[1].port, "9000");
+        assert_eq!(import.accounts.len(), 2);
+        assert_eq!(import.accounts[0].username, "alice");
+        assert_eq!(import.accounts[0].password.as_deref(), Some("secret"));
+        assert_eq!(import.accounts[1].password, None);
+        assert_eq!(
+            import.accounts[1].server_id.as_deref(),
+            Some(import.servers[0].id.as_str())
+        );
+    }
+}
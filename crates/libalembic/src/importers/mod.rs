@@ -0,0 +1,158 @@
+//! Importers for the servers and accounts saved by other AC launchers.
+//!
+//! Each importer reads a launcher's own files into a [`LauncherImport`], which
+//! can then be turned into a [`SettingsExport`] and merged with
+//! [`AlembicSettings::merge_import`](crate::settings::AlembicSettings::merge_import)
+//! like any other import.
+
+mod mag;
+mod thwarg;
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::bail;
+
+use crate::settings::{ExportedAccount, ServerInfo, SettingsExport, new_id};
+
+/// A launcher we can import from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Launcher {
+    Thwarg,
+    Mag,
+}
+
+/// An account read from another launcher
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LauncherAccount {
+    pub username: String,
+    pub password: Option<String>,
+    /// ID of the imported server this account belongs to, if the launcher
+    /// ties accounts to servers
+    pub server_id: Option<String>,
+}
+
+/// Everything read from another launcher's files
+#[derive(Clone, Debug, Default)]
+pub struct LauncherImport {
+    pub servers: Vec<ServerInfo>,
+    pub accounts: Vec<LauncherAccount>,
+}
+
+impl Launcher {
+    pub const ALL: [Launcher; 2] = [Launcher::Thwarg, Launcher::Mag];
+
+    /// Short name used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Launcher::Thwarg => "thwarg",
+            Launcher::Mag => "mag",
+        }
+    }
+
+    /// Where the launcher keeps its files, when installed for this user
+    pub fn default_path(&self) -> Option<PathBuf> {
+        if !cfg!(target_os = "windows") {
+            return None;
+        }
+
+        // On Windows this is %APPDATA%
+        let app_data = directories::BaseDirs::new()?.config_dir().to_path_buf();
+        let path = match self {
+            Launcher::Thwarg => app_data.join("ThwargLauncher"),
+            Launcher::Mag => app_data.join("Mag-ACClientLauncher"),
+        };
+
+        path.exists().then_some(path)
+    }
+
+    /// Read servers and accounts from `path`, either one of the launcher's
+    /// files or the directory it keeps them in
+    pub fn read(&self, path: &Path) -> anyhow::Result<LauncherImport> {
+        if !path.exists() {
+            bail!("{} does not exist", path.display());
+        }
+
+        match self {
+            Launcher::Thwarg => thwarg::read(path),
+            Launcher::Mag => mag::read(path),
+        }
+    }
+}
+
+impl fmt::Display for Launcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Launcher::Thwarg => write!(f, "ThwargLauncher"),
+            Launcher::Mag => write!(f, "Mag-ACClientLauncher"),
+        }
+    }
+}
+
+impl FromStr for Launcher {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "thwarg" | "thwarglauncher" => Ok(Launcher::Thwarg),
+            "mag" | "mag-acclientlauncher" => Ok(Launcher::Mag),
+            _ => bail!("Unknown launcher '{}'. Expected thwarg or mag.", s),
+        }
+    }
+}
+
+impl LauncherImport {
+    /// Convert into an export that can be merged into settings.
+    ///
+    /// Accounts that aren't tied to a server are added to `fallback_server_id`
+    /// or, when only one server was imported, to that server. Any others are
+    /// left out. Returns the export and how many accounts were left out.
+    pub fn into_export(self, fallback_server_id: Option<&str>) -> (SettingsExport, usize) {
+        let fallback =
+            fallback_server_id
+                .map(str::to_string)
+                .or_else(|| match self.servers.as_slice() {
+                    [server] => Some(server.id.clone()),
+                    _ => None,
+                });
+
+        let mut skipped = 0;
+        let accounts = self
+            .accounts
+            .into_iter()
+            .filter_map(|account| {
+                let Some(server_id) = account.server_id.or_else(|| fallback.clone()) else {
+                    skipped += 1;
+                    return None;
+                };
+
+                Some(ExportedAccount {
+                    id: new_id(),
+                    server_id,
+                    username: account.username,
+                    password: account.password,
+                })
+            })
+            .collect();
+
+        (SettingsExport::new(self.servers, accounts), skipped)
+    }
+}
+
+/// Build a server from fields read from another launcher
+fn server(name: &str, hostname: &str, port: &str) -> ServerInfo {
+    ServerInfo {
+        id: new_id(),
+        name: name.trim().to_string(),
+        hostname: hostname.trim().to_string(),
+        port: port.trim().to_string(),
+    }
+}
+
+/// Treat empty passwords as missing
+fn password(password: Option<String>) -> Option<String> {
+    password.filter(|p| !p.is_empty())
+}
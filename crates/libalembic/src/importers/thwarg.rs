@@ -0,0 +1,173 @@
+//! ThwargLauncher keeps its server lists as XML, in the same format as the
+//! community server list, and its accounts in a text file with one account per
+//! line, like `Name=alice,Password=secret,Alias=Main`. Accounts aren't tied to
+//! a particular server.
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, bail};
+use serde::Deserialize;
+
+use super::{LauncherAccount, LauncherImport, password, server};
+
+#[derive(Deserialize)]
+struct ServerList {
+    #[serde(rename = "ServerItem", default)]
+    servers: Vec<ServerItem>,
+}
+
+#[derive(Deserialize)]
+struct ServerItem {
+    name: String,
+    server_host: String,
+    server_port: String,
+}
+
+pub(super) fn read(path: &Path) -> anyhow::Result<LauncherImport> {
+    let files = if path.is_dir() {
+        let mut files = fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.is_file())
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut import = LauncherImport::default();
+
+    for file in files {
+        let extension = file
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let contents = fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+
+        match extension.as_str() {
+            "xml" => match parse_servers(&contents) {
+                Ok(servers) => {
+                    for s in servers {
+                        let duplicate = import.servers.iter().any(|existing| {
+                            existing.hostname.eq_ignore_ascii_case(&s.hostname)
+                                && existing.port == s.port
+                        });
+                        if !duplicate {
+                            import.servers.push(s);
+                        }
+                    }
+                }
+                // Other XML files in the directory aren't server lists
+                Err(_) if path.is_dir() => continue,
+                Err(e) => return Err(e),
+            },
+            "txt" => import.accounts.extend(parse_accounts(&contents)),
+            _ => {}
+        }
+    }
+
+    if import.servers.is_empty() && import.accounts.is_empty() {
+        bail!(
+            "No ThwargLauncher servers or accounts found in {}",
+            path.display()
+        );
+    }
+
+    Ok(import)
+}
+
+fn parse_servers(contents: &str) -> anyhow::Result<Vec<crate::settings::ServerInfo>> {
+    let list: ServerList =
+        serde_xml_rs::from_str(contents).context("Not a ThwargLauncher server list")?;
+
+    Ok(list
+        .servers
+        .iter()
+        .map(|s| server(&s.name, &s.server_host, &s.server_port))
+        .collect())
+}
+
+fn parse_accounts(contents: &str) -> Vec<LauncherAccount> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut username = None;
+            let mut pw = None;
+
+            for field in line.split(',') {
+                let Some((key, value)) = field.split_once('=') else {
+                    continue;
+                };
+
+                match key.trim().to_lowercase().as_str() {
+                    "name" => username = Some(value.trim().to_string()),
+                    "password" => pw = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+
+            Some(LauncherAccount {
+                username: username.filter(|u| !u.is_empty())?,
+                password: password(pw),
+                server_id: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_servers_and_accounts() {
+        let servers = parse_servers(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<ArrayOfServerItem>
+  <ServerItem>
+    <id>1</id>
+    <name>Coldeve</name>
+    <emu>ACE</emu>
+    <server_host>coldeve.ac</server_host>
+    <server_port>9000</server_port>
+  </ServerItem>
+  <ServerItem>
+    <id>2</id>
+    <name>Levistras</name>
+    <emu>ACE</emu>
+    <server_host>levistras.ac</server_host>
+    <server_port>9050</server_port>
+  </ServerItem>
+</ArrayOfServerItem>"#,
+        )
+        .unwrap();
+
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[1].name, "Levistras");
+        assert_eq!(servers[1].hostname, "levistras.ac");
+        assert_eq!(servers[1].port, "9050");
+
+        let accounts = parse_accounts(
+            "Name=alice,Password=secret,Alias=Main\n\nName=bob,Password=\nPassword=orphan\n",
+        );
+
+        assert_eq!(
+            accounts,
+            vec![
+                LauncherAccount {
+                    username: "alice".to_string(),
+                    password: Some("secret".to_string()),
+                    server_id: None,
+                },
+                LauncherAccount {
+                    username: "bob".to_string(),
+                    password: None,
+                    server_id: None,
+                },
+            ]
+        );
+    }
+}
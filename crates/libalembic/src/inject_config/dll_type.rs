@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::fingerprint::{BuildSupport, ClientFingerprint};
+use crate::hook_table::HookTable;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DllType {
     Alembic,
@@ -15,3 +18,21 @@ impl fmt::Display for DllType {
         }
     }
 }
+
+impl DllType {
+    /// Whether the DLL's hooks are known for this client build. Decal finds
+    /// what it hooks itself, so it works with any build; Alembic's hooks are
+    /// looked up in the hook table. Builds that aren't in the table are
+    /// unknown rather than unsupported, since the table's End of Retail
+    /// entry is only keyed by version and hasn't been checked against a
+    /// real client's timestamp and sha256 yet.
+    pub fn supports(self, fingerprint: &ClientFingerprint) -> BuildSupport {
+        match self {
+            DllType::Decal => BuildSupport::Supported,
+            DllType::Alembic => match HookTable::builtin().for_build(fingerprint) {
+                Some(_) => BuildSupport::Supported,
+                None => BuildSupport::Unknown,
+            },
+        }
+    }
+}
@@ -7,8 +7,10 @@ use std::fmt;
 use std::path::PathBuf;
 
 /// Configuration for DLL injection
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InjectConfig {
+    /// Stable identifier used to reference this DLL from its client
+    pub id: String,
     /// Path to the DLL (e.g., C:\Program Files\Alembic\Alembic.dll)
     pub dll_path: PathBuf,
     /// Type of DLL (Alembic or Decal)
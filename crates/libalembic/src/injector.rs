@@ -14,8 +14,8 @@ use windows::Win32::System::Memory::{
     MEM_COMMIT, MEM_RELEASE, MEM_RESERVE, PAGE_READWRITE, VirtualAllocEx, VirtualFreeEx,
 };
 use windows::Win32::System::Threading::{
-    CREATE_SUSPENDED, CreateProcessA, CreateRemoteThread, GetExitCodeThread, INFINITE,
-    PROCESS_INFORMATION, ResumeThread, STARTUPINFOA, WaitForSingleObject,
+    CREATE_SUSPENDED, CreateProcessA, CreateRemoteThread, GetExitCodeProcess, GetExitCodeThread,
+    INFINITE, PROCESS_INFORMATION, ResumeThread, STARTUPINFOA, WaitForSingleObject,
 };
 use windows::core::{PCSTR, PSTR};
 
@@ -58,6 +58,33 @@ impl Drop for LibraryGuard {
     }
 }
 
+/// A process started by one of the launch functions, so its exit can be
+/// waited for. The process handle is closed on Drop.
+pub struct ClientProcess {
+    handle: HandleGuard,
+    pid: u32,
+}
+
+impl ClientProcess {
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Wait for the process to exit and return its exit code, which is an
+    /// NTSTATUS like 0xC0000005 if it crashed
+    pub fn wait(&self) -> Result<u32> {
+        let mut exit_code = 0u32;
+        unsafe {
+            if WaitForSingleObject(self.handle.0, INFINITE) != WAIT_OBJECT_0 {
+                anyhow::bail!("WaitForSingleObject failed while waiting for the client");
+            }
+            GetExitCodeProcess(self.handle.0, &mut exit_code)
+                .context("GetExitCodeProcess failed")?;
+        }
+        Ok(exit_code)
+    }
+}
+
 /// Launch a process in suspended state, inject a DLL, optionally call a function, and resume the process
 ///
 /// # Arguments
@@ -67,14 +94,14 @@ impl Drop for LibraryGuard {
 /// * `dll_function` - Optional name of a function to execute in the DLL after injection
 ///
 /// # Returns
-/// * `Ok(())` if successful
+/// * `Ok(ClientProcess)` if successful
 /// * `Err` with details if any step fails
 pub fn launch_suspended_inject_and_resume(
     executable_path: &str,
     executable_args: &str,
     dll_path: &str,
     dll_function: Option<&str>,
-) -> Result<()> {
+) -> Result<ClientProcess> {
     // Combine filename and executable_args into command line
     let command_line = format!("{} {}", executable_path, executable_args);
     let command_line_cstring =
@@ -117,18 +144,24 @@ pub fn launch_suspended_inject_and_resume(
     // Inject the DLL (and optionally execute function)
     let result = inject_into_process(process_info.hProcess, dll_path, dll_function);
 
-    // Always resume the thread and close handles
+    // Always resume the thread, and keep the process handle to wait on
+    let process = ClientProcess {
+        handle: HandleGuard(process_info.hProcess),
+        pid: process_info.dwProcessId,
+    };
     unsafe {
         ResumeThread(process_info.hThread);
         CloseHandle(process_info.hThread).ok();
-        CloseHandle(process_info.hProcess).ok();
     }
 
-    result
+    result.map(|_| process)
 }
 
 /// Launch a process without DLL injection (just create and resume)
-pub fn launch_without_injection(executable_path: &str, executable_args: &str) -> Result<()> {
+pub fn launch_without_injection(
+    executable_path: &str,
+    executable_args: &str,
+) -> Result<ClientProcess> {
     // Combine filename and executable_args into command line
     let command_line = format!("{} {}", executable_path, executable_args);
     let command_line_cstring =
@@ -167,13 +200,15 @@ pub fn launch_without_injection(executable_path: &str, executable_args: &str) ->
         return Err(anyhow::anyhow!("CreateProcessA failed"));
     }
 
-    // Close handles (no need to resume since we didn't suspend)
+    // Close the thread handle (no need to resume since we didn't suspend)
     unsafe {
         CloseHandle(process_info.hThread).ok();
-        CloseHandle(process_info.hProcess).ok();
     }
 
-    Ok(())
+    Ok(ClientProcess {
+        handle: HandleGuard(process_info.hProcess),
+        pid: process_info.dwProcessId,
+    })
 }
 
 /// Inject a DLL into an existing process
@@ -1,3 +1,6 @@
+use std::{io::Write, path::PathBuf, process::Child};
+
+pub mod session;
 pub mod traits;
 
 #[cfg(all(target_os = "windows", target_env = "msvc"))]
@@ -11,3 +14,71 @@ pub type Launcher = windows::WindowsLauncherImpl;
 
 #[cfg(not(all(target_os = "windows", target_env = "msvc")))]
 pub type Launcher = wine::WineLauncherImpl;
+
+/// The target cork is built for. Cork must be 32-bit to inject into 32-bit
+/// acclient.exe.
+#[cfg(all(target_os = "windows", target_env = "msvc"))]
+const CORK_TARGET: &str = "i686-pc-windows-msvc";
+
+#[cfg(not(all(target_os = "windows", target_env = "msvc")))]
+const CORK_TARGET: &str = "i686-pc-windows-gnu";
+
+/// Find cork.exe next to the running executable (release/installed) or, in
+/// development, in cargo's target directory for `CORK_TARGET`, preferring the
+/// same build type (debug/release) as the running executable.
+pub fn find_cork() -> std::io::Result<PathBuf> {
+    let not_found = || {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "cork.exe not found. Expected in same directory as executable or target/{}/[debug|release]/",
+                CORK_TARGET
+            ),
+        )
+    };
+
+    let exe_path = std::env::current_exe()?;
+    let parent = exe_path.parent().ok_or_else(not_found)?;
+
+    let same_dir = parent.join("cork.exe");
+    if same_dir.exists() {
+        return Ok(same_dir);
+    }
+
+    // e.g., if exe is at target/debug/desktop, look for
+    // target/i686-pc-windows-gnu/debug/cork.exe
+    if let Some(target_dir) = parent.parent()
+        && let Some(build_type) = parent.file_name()
+    {
+        let other_type = if build_type == "debug" {
+            "release"
+        } else {
+            "debug"
+        };
+
+        for build_type in [build_type, other_type.as_ref()] {
+            let path = target_dir
+                .join(CORK_TARGET)
+                .join(build_type)
+                .join("cork.exe");
+            if path.exists() {
+                return Ok(path);
+            }
+        }
+    }
+
+    Err(not_found())
+}
+
+/// Hand the account password to cork over its stdin so it never shows up in
+/// cork's command line. Cork reads a single line when run with
+/// `--password-stdin`.
+pub(crate) fn send_password(child: &mut Child, password: &str) -> std::io::Result<()> {
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| std::io::Error::other("cork's stdin isn't piped"))?;
+
+    // Dropping stdin closes the pipe so cork sees EOF after the password
+    writeln!(stdin, "{}", password)
+}
@@ -0,0 +1,363 @@
+//! Following a launched client until it exits.
+//!
+//! Launchers run cork with `--wait`, so it stays running for as long as
+//! acclient.exe does and reports the client's PID and exit code on its
+//! stdout. A session reads those reports while passing the rest of cork's
+//! output through, so it knows whether the client is still running and how
+//! it exited, even under Wine, where cork's own exit code gets truncated.
+
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader},
+    process::{Child, ExitStatus},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::launcher::{Launcher, traits::ClientLauncher};
+
+/// Start of the line cork prints with the client's PID once it's started
+pub const CLIENT_PID_PREFIX: &str = "cork: client pid ";
+
+/// Start of the line cork prints with the client's exit code, in hex, once
+/// it exits
+pub const CLIENT_EXIT_PREFIX: &str = "cork: client exit ";
+
+/// How long to wait for cork's last output once it has exited
+const OUTPUT_GRACE: Duration = Duration::from_secs(1);
+
+/// Something cork reported about the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CorkReport {
+    ClientPid(u32),
+    ClientExit(u32),
+}
+
+fn parse_report(line: &str) -> Option<CorkReport> {
+    if let Some(pid) = line.strip_prefix(CLIENT_PID_PREFIX) {
+        return pid.trim().parse().ok().map(CorkReport::ClientPid);
+    }
+
+    let code = line.strip_prefix(CLIENT_EXIT_PREFIX)?.trim();
+    let code = code.strip_prefix("0x").unwrap_or(code);
+    u32::from_str_radix(code, 16)
+        .ok()
+        .map(CorkReport::ClientExit)
+}
+
+/// What cork has reported so far
+#[derive(Debug, Default)]
+struct CorkReports {
+    client_pid: Option<u32>,
+    client_code: Option<u32>,
+    /// Whether cork's stdout has closed, so there's nothing more to read
+    closed: bool,
+}
+
+/// What the NTSTATUS codes a crashed client exits with mean
+fn crash_name(code: u32) -> Option<&'static str> {
+    Some(match code {
+        0x8000_0003 => "breakpoint",
+        0xC000_0005 => "access violation",
+        0xC000_001D => "illegal instruction",
+        0xC000_0094 => "integer divide by zero",
+        0xC000_00FD => "stack overflow",
+        0xC000_0135 => "a DLL it needs wasn't found",
+        0xC000_0142 => "a DLL failed to initialize",
+        0xC000_0374 => "heap corruption",
+        0xC000_0409 => "stack buffer overrun",
+        _ => return None,
+    })
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+/// How a session ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExitReport {
+    /// acclient.exe's PID, if cork started it
+    pub client_pid: Option<u32>,
+    /// acclient.exe's exit code, if cork saw it exit
+    pub client_code: Option<u32>,
+    /// How cork (or Wine) exited, like `exit status: 0`
+    pub launcher_status: String,
+    pub ran_for: Duration,
+}
+
+impl ExitReport {
+    /// What went wrong, if the client's exit code says it crashed
+    pub fn crash(&self) -> Option<&'static str> {
+        let code = self.client_code?;
+        crash_name(code)
+            .or_else(|| (code & 0xC000_0000 == 0xC000_0000).then_some("unhandled exception"))
+    }
+
+    /// Whether the client started and exited normally
+    pub fn is_clean(&self) -> bool {
+        self.client_code == Some(0)
+    }
+}
+
+impl fmt::Display for ExitReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ran_for = format_duration(self.ran_for);
+
+        match (self.client_code, self.crash()) {
+            (Some(0), _) => write!(f, "acclient.exe exited normally after {}", ran_for),
+            (Some(code), Some(crash)) => write!(
+                f,
+                "acclient.exe crashed after {}: {} (0x{:08X})",
+                ran_for, crash, code
+            ),
+            (Some(code), None) => {
+                write!(
+                    f,
+                    "acclient.exe exited with code {} after {}",
+                    code, ran_for
+                )
+            }
+            (None, _) if self.client_pid.is_some() => write!(
+                f,
+                "Lost track of acclient.exe after {}: the launcher exited ({})",
+                ran_for, self.launcher_status
+            ),
+            (None, _) => write!(
+                f,
+                "acclient.exe didn't start: the launcher exited ({})",
+                self.launcher_status
+            ),
+        }
+    }
+}
+
+/// Where a session is at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionState {
+    /// cork is starting the client
+    Starting,
+    Running {
+        client_pid: u32,
+    },
+    Exited(ExitReport),
+}
+
+impl fmt::Display for SessionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionState::Starting => write!(f, "Starting acclient.exe..."),
+            SessionState::Running { client_pid } => {
+                write!(f, "acclient.exe is running (pid {})", client_pid)
+            }
+            SessionState::Exited(report) => write!(f, "{}", report),
+        }
+    }
+}
+
+/// A launched client, from cork starting it until it exits. Cleanup, like
+/// ejecting the DLL, runs once the client exits or the session is dropped.
+pub struct LaunchSession {
+    child: Child,
+    started: Instant,
+    reports: Arc<Mutex<CorkReports>>,
+    exit: Option<ExitReport>,
+    cleanup: Option<Box<dyn FnOnce() -> anyhow::Result<()> + Send>>,
+}
+
+impl LaunchSession {
+    /// Launch a client and follow it, ejecting through the launcher once
+    /// the session is over
+    pub fn launch(mut launcher: Launcher) -> io::Result<Self> {
+        let child = launcher.launch()?;
+        Ok(Self::new(child).with_cleanup(move || launcher.eject()))
+    }
+
+    /// Follow a cork process started with `--wait`. Its stdout should be
+    /// piped so the session can read cork's reports.
+    pub fn new(mut child: Child) -> Self {
+        let reports = Arc::new(Mutex::new(CorkReports::default()));
+
+        match child.stdout.take() {
+            Some(stdout) => {
+                let reports = reports.clone();
+                thread::spawn(move || {
+                    for line in BufReader::new(stdout).split(b'\n').map_while(Result::ok) {
+                        let line = String::from_utf8_lossy(&line);
+                        let line = line.trim_end_matches('\r');
+
+                        match parse_report(line) {
+                            Some(CorkReport::ClientPid(pid)) => {
+                                reports.lock().unwrap().client_pid = Some(pid)
+                            }
+                            Some(CorkReport::ClientExit(code)) => {
+                                reports.lock().unwrap().client_code = Some(code)
+                            }
+                            None => println!("{}", line),
+                        }
+                    }
+                    reports.lock().unwrap().closed = true;
+                });
+            }
+            None => reports.lock().unwrap().closed = true,
+        }
+
+        Self {
+            child,
+            started: Instant::now(),
+            reports,
+            exit: None,
+            cleanup: None,
+        }
+    }
+
+    /// Run `cleanup` once the session is over
+    pub fn with_cleanup(
+        mut self,
+        cleanup: impl FnOnce() -> anyhow::Result<()> + Send + 'static,
+    ) -> Self {
+        self.cleanup = Some(Box::new(cleanup));
+        self
+    }
+
+    /// PID of the process the launcher started: cork, or Wine running it
+    pub fn launcher_pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// PID of acclient.exe once cork has started it. Under Wine, this is its
+    /// Windows PID.
+    pub fn client_pid(&self) -> Option<u32> {
+        self.reports.lock().unwrap().client_pid
+    }
+
+    /// Check on the session without waiting
+    pub fn state(&mut self) -> SessionState {
+        if self.exit.is_none()
+            && let Ok(Some(status)) = self.child.try_wait()
+        {
+            self.finish(status);
+        }
+
+        match (&self.exit, self.client_pid()) {
+            (Some(report), _) => SessionState::Exited(report.clone()),
+            (None, Some(client_pid)) => SessionState::Running { client_pid },
+            (None, None) => SessionState::Starting,
+        }
+    }
+
+    /// Wait for the client to exit
+    pub fn wait(&mut self) -> io::Result<ExitReport> {
+        if self.exit.is_none() {
+            let status = self.child.wait()?;
+            self.finish(status);
+        }
+
+        Ok(self.exit.clone().expect("finish records the exit"))
+    }
+
+    fn finish(&mut self, status: ExitStatus) {
+        // cork's last reports can still be in the pipe when it exits
+        let deadline = Instant::now() + OUTPUT_GRACE;
+        while !self.reports.lock().unwrap().closed && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let report = {
+            let reports = self.reports.lock().unwrap();
+            ExitReport {
+                client_pid: reports.client_pid,
+                client_code: reports.client_code,
+                launcher_status: status.to_string(),
+                ran_for: self.started.elapsed(),
+            }
+        };
+        self.exit = Some(report);
+        self.clean_up();
+    }
+
+    fn clean_up(&mut self) {
+        if let Some(cleanup) = self.cleanup.take()
+            && let Err(e) = cleanup()
+        {
+            eprintln!("Failed to clean up after the client: {:#}", e);
+        }
+    }
+}
+
+impl Drop for LaunchSession {
+    fn drop(&mut self) {
+        self.clean_up();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        process::{Command, Stdio},
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    #[test]
+    fn reads_cork_reports_and_classifies_exits() {
+        assert_eq!(
+            parse_report("cork: client pid 1234"),
+            Some(CorkReport::ClientPid(1234))
+        );
+        assert_eq!(
+            parse_report("cork: client exit 0xC0000005"),
+            Some(CorkReport::ClientExit(0xC000_0005))
+        );
+        assert_eq!(parse_report("Client launched successfully!"), None);
+
+        let report = |client_code| ExitReport {
+            client_pid: Some(1234),
+            client_code,
+            launcher_status: "exit status: 5".to_string(),
+            ran_for: Duration::from_secs(125),
+        };
+        assert!(report(Some(0)).is_clean());
+        assert_eq!(report(Some(1)).crash(), None);
+        assert_eq!(report(Some(0xC000_0005)).crash(), Some("access violation"));
+        assert_eq!(
+            report(Some(0xC000_0417)).crash(),
+            Some("unhandled exception")
+        );
+        assert_eq!(
+            report(Some(0xC000_0005)).to_string(),
+            "acclient.exe crashed after 2m 5s: access violation (0xC0000005)"
+        );
+        assert!(report(None).to_string().starts_with("Lost track"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follows_a_launcher_until_it_exits() {
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg("echo 'cork: client pid 1234'; echo 'cork: client exit 0xC0000005'; exit 5")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("sh should start");
+
+        let cleaned_up = Arc::new(AtomicBool::new(false));
+        let flag = cleaned_up.clone();
+        let mut session = LaunchSession::new(child).with_cleanup(move || {
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        });
+
+        let report = session.wait().expect("session should end");
+        assert_eq!(report.client_pid, Some(1234));
+        assert_eq!(report.client_code, Some(0xC000_0005));
+        assert!(cleaned_up.load(Ordering::SeqCst));
+        assert_eq!(session.state(), SessionState::Exited(report));
+    }
+}
@@ -1,4 +1,4 @@
-use std::num::NonZero;
+use std::process::Child;
 
 use crate::{
     inject_config::InjectConfig,
@@ -15,11 +15,13 @@ pub trait ClientLauncher: std::any::Any {
         account_info: Account,
     ) -> Self;
 
-    /// Launch a new client process (with automatic injection if configured)
-    fn launch(&mut self) -> Result<NonZero<u32>, std::io::Error>;
+    /// Launch a new client process (with automatic injection if configured).
+    /// Returns cork, which runs until the client exits; follow it with a
+    /// `LaunchSession`.
+    fn launch(&mut self) -> Result<Child, std::io::Error>;
 
     /// Find or launch the client process (tries to find existing first, with automatic injection if configured)
-    fn find_or_launch(&mut self) -> Result<NonZero<u32>, std::io::Error>;
+    fn find_or_launch(&mut self) -> Result<Child, std::io::Error>;
 
     /// Inject a DLL into the running client
     fn inject(&mut self) -> Result<(), anyhow::Error>;
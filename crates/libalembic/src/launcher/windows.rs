@@ -1,14 +1,12 @@
 #![cfg(all(target_os = "windows", target_env = "msvc"))]
 
-use std::{
-    num::NonZero,
-    process::{Child, Command, Stdio},
-};
+use std::process::{Child, Command, Stdio};
 
 use crate::{
     client_config::WindowsClientConfig,
+    credentials,
     inject_config::InjectConfig,
-    launcher::traits::ClientLauncher,
+    launcher::{find_cork, send_password, traits::ClientLauncher},
     settings::{Account, ClientConfigType, ServerInfo},
 };
 
@@ -17,60 +15,6 @@ pub struct WindowsLauncherImpl {
     inject_config: Option<InjectConfig>,
     server_info: ServerInfo,
     account_info: Account,
-    child: Option<Child>,
-}
-
-/// Find cork.exe for native Windows.
-/// Looks for the i686-pc-windows-msvc build since cork must be 32-bit
-/// to inject into 32-bit acclient.exe.
-fn find_cork() -> Result<std::path::PathBuf, std::io::Error> {
-    let exe_path = std::env::current_exe()?;
-    let parent = exe_path.parent().ok_or_else(|| {
-        std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "Cannot determine exe directory",
-        )
-    })?;
-
-    // Strategy 1: Same directory as executable (release/installed)
-    let same_dir = parent.join("cork.exe");
-    if same_dir.exists() {
-        return Ok(same_dir);
-    }
-
-    // Strategy 2: Development mode - look in cargo target directory
-    // e.g., if exe is at target/debug/desktop.exe, look for target/i686-pc-windows-msvc/debug/cork.exe
-    if let Some(target_dir) = parent.parent()
-        && let Some(build_type) = parent.file_name()
-    {
-        // Try same build type first (debug/release)
-        let same_type_path = target_dir
-            .join("i686-pc-windows-msvc")
-            .join(build_type)
-            .join("cork.exe");
-        if same_type_path.exists() {
-            return Ok(same_type_path);
-        }
-
-        // Fall back to opposite build type
-        let other_type = if build_type == "debug" {
-            "release"
-        } else {
-            "debug"
-        };
-        let other_type_path = target_dir
-            .join("i686-pc-windows-msvc")
-            .join(other_type)
-            .join("cork.exe");
-        if other_type_path.exists() {
-            return Ok(other_type_path);
-        }
-    }
-
-    Err(std::io::Error::new(
-        std::io::ErrorKind::NotFound,
-        "cork.exe not found. Expected in same directory as executable or target/i686-pc-windows-msvc/[debug|release]/",
-    ))
 }
 
 impl ClientLauncher for WindowsLauncherImpl {
@@ -90,12 +34,14 @@ impl ClientLauncher for WindowsLauncherImpl {
             inject_config,
             server_info,
             account_info,
-            child: None,
         }
     }
 
-    fn launch(&mut self) -> Result<NonZero<u32>, std::io::Error> {
+    fn launch(&mut self) -> Result<Child, std::io::Error> {
         let cork_path = find_cork()?;
+        // Look up the password now so it's only held for as long as the launch
+        let password = credentials::get_password(&self.account_info)
+            .map_err(|e| std::io::Error::other(format!("{:#}", e)))?;
 
         println!("Using cork: {}", cork_path.display());
 
@@ -110,8 +56,8 @@ impl ClientLauncher for WindowsLauncherImpl {
             .arg(&self.server_info.port)
             .arg("--account")
             .arg(&self.account_info.username)
-            .arg("--password")
-            .arg(&self.account_info.password);
+            .arg("--password-stdin")
+            .arg("--wait");
 
         // Add DLL injection parameters if configured
         if let Some(inject_config) = &self.inject_config {
@@ -123,7 +69,9 @@ impl ClientLauncher for WindowsLauncherImpl {
             }
         }
 
-        cmd.stdout(Stdio::inherit());
+        cmd.stdin(Stdio::piped());
+        // cork reports on the client over stdout, which LaunchSession reads
+        cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::inherit());
 
         // Print launch info
@@ -146,14 +94,13 @@ impl ClientLauncher for WindowsLauncherImpl {
             }
         }
 
-        let child = cmd.spawn()?;
-        let pid = child.id();
-        self.child = Some(child);
+        let mut child = cmd.spawn()?;
+        send_password(&mut child, &password)?;
 
-        Ok(NonZero::new(pid).unwrap())
+        Ok(child)
     }
 
-    fn find_or_launch(&mut self) -> Result<NonZero<u32>, std::io::Error> {
+    fn find_or_launch(&mut self) -> Result<Child, std::io::Error> {
         // Cork handles process creation, so just launch
         self.launch()
     }
@@ -1,16 +1,14 @@
 #![cfg(not(all(target_os = "windows", target_env = "msvc")))]
 
-use std::{
-    num::NonZero,
-    process::{Child, Command, Stdio},
-};
+use std::process::{Child, Command, Stdio};
 
 use crate::{
     client_config::{ClientConfig, WineClientConfig},
+    credentials,
     inject_config::InjectConfig,
-    launcher::traits::ClientLauncher,
-    scanner::windows_to_unix_path,
+    launcher::{find_cork, send_password, traits::ClientLauncher},
     settings::{Account, ClientConfigType, ServerInfo},
+    wine_paths::DosDevices,
 };
 
 pub struct WineLauncherImpl {
@@ -18,8 +16,6 @@ pub struct WineLauncherImpl {
     inject_config: Option<InjectConfig>,
     server_info: ServerInfo,
     account_info: Account,
-    child_pid: Option<u32>,
-    child: Option<Child>,
 }
 
 impl ClientLauncher for WineLauncherImpl {
@@ -39,69 +35,26 @@ impl ClientLauncher for WineLauncherImpl {
             inject_config,
             server_info,
             account_info,
-            child_pid: None,
-            child: None,
         }
     }
 
-    fn launch(&mut self) -> Result<NonZero<u32>, std::io::Error> {
+    fn launch(&mut self) -> Result<Child, std::io::Error> {
         println!("Launching new client via Wine...");
 
-        // Find cork.exe - try multiple locations for dev vs release
-        let cork_path = std::env::current_exe()
-            .ok()
-            .and_then(|exe_path| {
-                let parent = exe_path.parent()?;
-
-                // Strategy 1: Same directory as executable (release/installed)
-                let same_dir = parent.join("cork.exe");
-                if same_dir.exists() {
-                    return Some(same_dir);
-                }
-
-                // Strategy 2: Development mode - look in cargo target directory
-                // e.g., if exe is at target/debug/desktop, look for target/i686-pc-windows-gnu/debug/cork.exe
-                // Try matching build type first (debug/release), then try the other
-                if let Some(target_dir) = parent.parent() {
-                    let build_type = parent.file_name()?; // "debug" or "release"
-
-                    // Try same build type first
-                    let same_type_path = target_dir
-                        .join("i686-pc-windows-gnu")
-                        .join(build_type)
-                        .join("cork.exe");
-                    if same_type_path.exists() {
-                        return Some(same_type_path);
-                    }
-
-                    // Fall back to opposite build type
-                    let other_type = if build_type == "debug" { "release" } else { "debug" };
-                    let other_type_path = target_dir
-                        .join("i686-pc-windows-gnu")
-                        .join(other_type)
-                        .join("cork.exe");
-                    if other_type_path.exists() {
-                        return Some(other_type_path);
-                    }
-                }
+        // Look up the password now so it's only held for as long as the launch
+        let password = credentials::get_password(&self.account_info)
+            .map_err(|e| std::io::Error::other(format!("{:#}", e)))?;
 
-                None
-            })
-            .ok_or_else(|| {
-                std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    "cork.exe not found. Expected in same directory as executable or target/i686-pc-windows-gnu/[debug|release]/",
-                )
-            })?;
+        let cork_path = find_cork()?;
 
         let client_exe = self.config.client_path().display().to_string();
         let launch_cmd = &self.config.launch_command;
 
         // Verify paths exist on the host filesystem before launching
         if let Some(prefix_str) = launch_cmd.env.get("WINEPREFIX") {
-            let prefix = std::path::Path::new(prefix_str);
+            let devices = DosDevices::load(std::path::Path::new(prefix_str));
 
-            if let Ok(unix_client) = windows_to_unix_path(prefix, self.config.client_path())
+            if let Ok(unix_client) = devices.to_unix(self.config.client_path())
                 && !unix_client.exists()
             {
                 return Err(std::io::Error::new(
@@ -114,7 +67,7 @@ impl ClientLauncher for WineLauncherImpl {
             }
 
             if let Some(inject_config) = &self.inject_config
-                && let Ok(unix_dll) = windows_to_unix_path(prefix, &inject_config.dll_path)
+                && let Ok(unix_dll) = devices.to_unix(&inject_config.dll_path)
                 && !unix_dll.exists()
             {
                 return Err(std::io::Error::new(
@@ -157,8 +110,8 @@ impl ClientLauncher for WineLauncherImpl {
             .arg(&self.server_info.port)
             .arg("--account")
             .arg(&self.account_info.username)
-            .arg("--password")
-            .arg(&self.account_info.password);
+            .arg("--password-stdin")
+            .arg("--wait");
 
         // Add DLL injection parameters if configured
         if let Some(inject_config) = &self.inject_config {
@@ -170,8 +123,12 @@ impl ClientLauncher for WineLauncherImpl {
             }
         }
 
-        // For debugging: inherit stdout/stderr so we can see cork's output
-        cmd.stdout(Stdio::inherit());
+        // The password is written to cork's stdin once it has started
+        cmd.stdin(Stdio::piped());
+
+        // cork reports on the client over stdout, which LaunchSession reads
+        // and passes through. Inherit stderr so we can see Wine's output.
+        cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::inherit());
 
         // Print launch info
@@ -235,7 +192,8 @@ impl ClientLauncher for WineLauncherImpl {
         print!("--hostname {} ", self.server_info.hostname);
         print!("--port {} ", self.server_info.port);
         print!("--account {} ", self.account_info.username);
-        print!("--password <hidden> ");
+        print!("--password-stdin ");
+        print!("--wait ");
 
         if let Some(inject_config) = &self.inject_config {
             print!("--dll \"{}\" ", inject_config.dll_path.display());
@@ -247,16 +205,13 @@ impl ClientLauncher for WineLauncherImpl {
         println!("===========================");
         println!();
 
-        let child = cmd.spawn()?;
-        let unix_pid = child.id();
-
-        self.child_pid = Some(unix_pid);
-        self.child = Some(child);
+        let mut child = cmd.spawn()?;
+        send_password(&mut child, &password)?;
 
-        Ok(NonZero::new(unix_pid).unwrap())
+        Ok(child)
     }
 
-    fn find_or_launch(&mut self) -> Result<NonZero<u32>, std::io::Error> {
+    fn find_or_launch(&mut self) -> Result<Child, std::io::Error> {
         println!("find_or_launch not implemented in Wine mode, just calling launch() instead");
         self.launch()
     }
@@ -1,16 +1,29 @@
 pub mod acclient;
 pub mod async_runtime;
 pub mod client_config;
+pub mod credentials;
+pub mod decal;
+pub mod dll_inspect;
+pub mod doctor;
+pub mod fingerprint;
+pub mod hook_table;
+pub mod importers;
 pub mod inject_config;
 pub mod injector;
 pub mod launcher;
 pub mod msg;
+pub mod pe;
 pub mod rpc;
 pub mod scanner;
 pub mod settings;
+pub mod sigscan;
+#[cfg(test)]
+mod test_util;
 pub mod util;
 pub mod validation;
 pub mod win;
+pub mod wine_paths;
+pub mod wine_registry;
 
 /// Defines how the game client should be launched
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
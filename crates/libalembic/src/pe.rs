@@ -0,0 +1,467 @@
+//! A small reader for Windows PE files (EXEs and DLLs).
+//!
+//! This only reads what Alembic needs to check files before handing them to
+//! Wine or cork, like the CPU architecture and exported function names, so it
+//! works on any host without Windows APIs.
+
+use std::{fmt, fs, path::Path};
+
+use anyhow::{Context, Result, bail};
+
+/// Index of the export table in the optional header's data directories
+const EXPORT_DIRECTORY: usize = 0;
+
+/// Index of the import table in the optional header's data directories
+const IMPORT_DIRECTORY: usize = 1;
+
+/// Size of an import descriptor, one per imported DLL
+const IMPORT_DESCRIPTOR_SIZE: usize = 20;
+
+/// Index of the resource table in the optional header's data directories
+const RESOURCE_DIRECTORY: usize = 2;
+
+/// Resource type of version information
+const RT_VERSION: u32 = 16;
+
+/// Signature at the start of `VS_FIXEDFILEINFO`
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xfeef04bd;
+
+/// The CPU architecture a PE file was built for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Machine {
+    X86,
+    X64,
+    Arm64,
+    Other(u16),
+}
+
+impl Machine {
+    fn from_raw(machine: u16) -> Self {
+        match machine {
+            0x014c => Machine::X86,
+            0x8664 => Machine::X64,
+            0xaa64 => Machine::Arm64,
+            other => Machine::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Machine::X86 => write!(f, "32-bit x86"),
+            Machine::X64 => write!(f, "64-bit x64"),
+            Machine::Arm64 => write!(f, "ARM64"),
+            Machine::Other(machine) => write!(f, "unknown machine 0x{:04x}", machine),
+        }
+    }
+}
+
+/// Section flag for sections that hold code
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+
+/// A section of the image, like `.text`
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub name: String,
+    pub virtual_address: u32,
+    pub virtual_size: u32,
+    pub raw_offset: u32,
+    pub raw_size: u32,
+    pub characteristics: u32,
+}
+
+impl Section {
+    pub fn is_executable(&self) -> bool {
+        self.characteristics & IMAGE_SCN_MEM_EXECUTE != 0
+    }
+}
+
+/// A parsed PE file
+#[derive(Debug, Clone)]
+pub struct PeFile {
+    data: Vec<u8>,
+    machine: Machine,
+    /// Address the image is meant to be loaded at
+    image_base: u64,
+    /// When the linker built the file, in seconds since the Unix epoch
+    timestamp: u32,
+    sections: Vec<Section>,
+    /// RVA and size of each data directory
+    data_directories: Vec<(u32, u32)>,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .with_context(|| format!("Unexpected end of file at 0x{:x}", offset))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .with_context(|| format!("Unexpected end of file at 0x{:x}", offset))
+}
+
+impl PeFile {
+    pub fn parse(data: Vec<u8>) -> Result<Self> {
+        if data.get(..2) != Some(b"MZ") {
+            bail!("Not a Windows executable (no MZ header)");
+        }
+
+        let pe_offset = read_u32(&data, 0x3c)? as usize;
+        if data.get(pe_offset..pe_offset + 4) != Some(b"PE\0\0") {
+            bail!("Not a Windows executable (no PE header)");
+        }
+
+        let coff = pe_offset + 4;
+        let machine = Machine::from_raw(read_u16(&data, coff)?);
+        let section_count = read_u16(&data, coff + 2)? as usize;
+        let timestamp = read_u32(&data, coff + 4)?;
+        let optional_header_size = read_u16(&data, coff + 16)? as usize;
+
+        let optional = coff + 20;
+        let (directories_offset, image_base) = match read_u16(&data, optional)? {
+            0x10b => (optional + 96, read_u32(&data, optional + 28)? as u64),
+            0x20b => (
+                optional + 112,
+                read_u32(&data, optional + 24)? as u64
+                    | (read_u32(&data, optional + 28)? as u64) << 32,
+            ),
+            magic => bail!("Unknown optional header type 0x{:x}", magic),
+        };
+        let directory_count = read_u32(&data, directories_offset - 4)? as usize;
+
+        let data_directories = (0..directory_count.min(16))
+            .map(|i| {
+                let entry = directories_offset + i * 8;
+                Ok((read_u32(&data, entry)?, read_u32(&data, entry + 4)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let section_table = optional + optional_header_size;
+        let sections = (0..section_count)
+            .map(|i| {
+                let header = section_table + i * 40;
+                let name = data
+                    .get(header..header + 8)
+                    .context("Unexpected end of file in the section table")?;
+                let name = String::from_utf8_lossy(name)
+                    .trim_end_matches('\0')
+                    .to_string();
+
+                Ok(Section {
+                    name,
+                    virtual_size: read_u32(&data, header + 8)?,
+                    virtual_address: read_u32(&data, header + 12)?,
+                    raw_size: read_u32(&data, header + 16)?,
+                    raw_offset: read_u32(&data, header + 20)?,
+                    characteristics: read_u32(&data, header + 36)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            data,
+            machine,
+            image_base,
+            timestamp,
+            sections,
+            data_directories,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(data).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn machine(&self) -> Machine {
+        self.machine
+    }
+
+    pub fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    pub fn image_base(&self) -> u64 {
+        self.image_base
+    }
+
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// The bytes of a section as stored in the file
+    pub fn section_data(&self, section: &Section) -> &[u8] {
+        let start = (section.raw_offset as usize).min(self.data.len());
+        let end = start
+            .saturating_add(section.raw_size as usize)
+            .min(self.data.len());
+        &self.data[start..end]
+    }
+
+    /// The file version from the version resource, like `1.2.3.4`, if the
+    /// file has one
+    pub fn file_version(&self) -> Option<String> {
+        let &(rva, _) = self.data_directories.get(RESOURCE_DIRECTORY)?;
+        let root = self.rva_to_offset(rva)?;
+
+        // The resource tree is type, then name, then language. Any name and
+        // language will do since executables only have one version resource.
+        let mut directory = root;
+        let mut wanted = Some(RT_VERSION);
+        let mut leaf = None;
+        for _ in 0..3 {
+            let named = read_u16(&self.data, directory + 12).ok()? as usize;
+            let ids = read_u16(&self.data, directory + 14).ok()? as usize;
+
+            let entry = (0..named + ids)
+                .map(|i| directory + 16 + i * 8)
+                .find(|&entry| {
+                    wanted.is_none_or(|id| read_u32(&self.data, entry).ok() == Some(id))
+                })?;
+            let offset = read_u32(&self.data, entry + 4).ok()?;
+            wanted = None;
+
+            // The high bit marks another level of the tree
+            if offset & 0x8000_0000 == 0 {
+                leaf = Some(root + offset as usize);
+                break;
+            }
+            directory = root + (offset & 0x7fff_ffff) as usize;
+        }
+        let leaf = leaf?;
+
+        let start = self.rva_to_offset(read_u32(&self.data, leaf).ok()?)?;
+        let size = read_u32(&self.data, leaf + 4).ok()? as usize;
+        let info = self.data.get(start..start.checked_add(size)?)?;
+
+        let fixed = (0..info.len().saturating_sub(16))
+            .step_by(4)
+            .find(|&i| read_u32(info, i).ok() == Some(FIXED_FILE_INFO_SIGNATURE))?;
+        let most = read_u32(info, fixed + 8).ok()?;
+        let least = read_u32(info, fixed + 12).ok()?;
+
+        Some(format!(
+            "{}.{}.{}.{}",
+            most >> 16,
+            most & 0xffff,
+            least >> 16,
+            least & 0xffff
+        ))
+    }
+
+    /// Find where an RVA is stored in the file
+    fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        self.sections.iter().find_map(|section| {
+            let size = section.virtual_size.max(section.raw_size);
+            (rva >= section.virtual_address && rva < section.virtual_address + size)
+                .then(|| (rva - section.virtual_address + section.raw_offset) as usize)
+        })
+    }
+
+    /// Read the NUL-terminated string at an RVA
+    fn string_at(&self, rva: u32) -> Result<String> {
+        let offset = self
+            .rva_to_offset(rva)
+            .with_context(|| format!("RVA 0x{:x} is outside the file", rva))?;
+        let bytes = &self.data[offset.min(self.data.len())..];
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        Ok(String::from_utf8_lossy(&bytes[..end]).into_owned())
+    }
+
+    /// Names of the functions the file exports. Exports by ordinal only
+    /// have no name and aren't listed.
+    pub fn exports(&self) -> Result<Vec<String>> {
+        let Some(&(rva, size)) = self.data_directories.get(EXPORT_DIRECTORY) else {
+            return Ok(vec![]);
+        };
+        if rva == 0 || size == 0 {
+            return Ok(vec![]);
+        }
+
+        let directory = self
+            .rva_to_offset(rva)
+            .context("The export table is outside the file")?;
+        let name_count = read_u32(&self.data, directory + 24)? as usize;
+        let names_rva = read_u32(&self.data, directory + 32)?;
+        let names = self
+            .rva_to_offset(names_rva)
+            .context("The export name table is outside the file")?;
+
+        (0..name_count)
+            .map(|i| self.string_at(read_u32(&self.data, names + i * 4)?))
+            .collect()
+    }
+
+    /// Names of the DLLs the file imports from, as written in the import
+    /// table, like `KERNEL32.dll`
+    pub fn imports(&self) -> Result<Vec<String>> {
+        let Some(&(rva, size)) = self.data_directories.get(IMPORT_DIRECTORY) else {
+            return Ok(vec![]);
+        };
+        if rva == 0 || size == 0 {
+            return Ok(vec![]);
+        }
+
+        let table = self
+            .rva_to_offset(rva)
+            .context("The import table is outside the file")?;
+
+        // The table ends with an all-zero descriptor
+        let mut imports = vec![];
+        for descriptor in (table..self.data.len()).step_by(IMPORT_DESCRIPTOR_SIZE) {
+            let name_rva = read_u32(&self.data, descriptor + 12)?;
+            if name_rva == 0 {
+                break;
+            }
+            imports.push(self.string_at(name_rva)?);
+        }
+
+        Ok(imports)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Build a minimal PE file with one section holding an export table for
+    /// `exports` and, if given, a version resource
+    pub(crate) fn build_pe(machine: u16, exports: &[&str], version: Option<[u16; 4]>) -> Vec<u8> {
+        let put = |data: &mut Vec<u8>, offset: usize, value: u32| {
+            data[offset..offset + 4].copy_from_slice(&value.to_le_bytes())
+        };
+
+        let mut data = vec![0u8; 0x600];
+        data[..2].copy_from_slice(b"MZ");
+        put(&mut data, 0x3c, 0x40);
+        data[0x40..0x44].copy_from_slice(b"PE\0\0");
+
+        // COFF header: machine, one section, timestamp, PE32 optional header
+        data[0x44..0x46].copy_from_slice(&machine.to_le_bytes());
+        data[0x46..0x48].copy_from_slice(&1u16.to_le_bytes());
+        put(&mut data, 0x48, 0x5000_0000);
+        data[0x54..0x56].copy_from_slice(&224u16.to_le_bytes());
+
+        // Optional header with the usual image base and 16 data
+        // directories: exports and resources
+        let optional = 0x58;
+        data[optional..optional + 2].copy_from_slice(&0x10bu16.to_le_bytes());
+        put(&mut data, optional + 28, 0x0040_0000);
+        put(&mut data, optional + 92, 16);
+        put(&mut data, optional + 96, 0x1000);
+        put(&mut data, optional + 100, 0x200);
+        if version.is_some() {
+            put(&mut data, optional + 112, 0x1200);
+            put(&mut data, optional + 116, 0xb8);
+        }
+
+        // One section mapping RVA 0x1000 to file offset 0x200, marked as
+        // code so it can be scanned too
+        let section = optional + 224;
+        data[section..section + 5].copy_from_slice(b".text");
+        put(&mut data, section + 8, 0x400);
+        put(&mut data, section + 12, 0x1000);
+        put(&mut data, section + 16, 0x400);
+        put(&mut data, section + 20, 0x200);
+        put(&mut data, section + 36, 0x6000_0020);
+
+        // Export directory, then the name pointer table, then the names
+        let names_table = 0x228;
+        put(&mut data, 0x218, exports.len() as u32);
+        put(&mut data, 0x220, 0x1000 + names_table as u32 - 0x200);
+
+        let mut name_offset = names_table + exports.len() * 4;
+        for (i, name) in exports.iter().enumerate() {
+            put(
+                &mut data,
+                names_table + i * 4,
+                0x1000 + name_offset as u32 - 0x200,
+            );
+            data[name_offset..name_offset + name.len()].copy_from_slice(name.as_bytes());
+            name_offset += name.len() + 1;
+        }
+
+        // Resource tree at 0x400: RT_VERSION -> name 1 -> language 0x409,
+        // pointing at a VS_FIXEDFILEINFO
+        if let Some([major, minor, build, revision]) = version {
+            for (directory, id, offset) in [
+                (0x400, RT_VERSION, 0x8000_0018),
+                (0x418, 1, 0x8000_0030),
+                (0x430, 0x409, 0x48),
+            ] {
+                data[directory + 14..directory + 16].copy_from_slice(&1u16.to_le_bytes());
+                put(&mut data, directory + 16, id);
+                put(&mut data, directory + 20, offset);
+            }
+            put(&mut data, 0x448, 0x1258);
+            put(&mut data, 0x44c, 0x60);
+            put(&mut data, 0x480, FIXED_FILE_INFO_SIGNATURE);
+            put(&mut data, 0x488, (major as u32) << 16 | minor as u32);
+            put(&mut data, 0x48c, (build as u32) << 16 | revision as u32);
+        }
+
+        data
+    }
+
+    /// Add an import table for `imports` to a file from `build_pe`
+    pub(crate) fn add_imports(data: &mut [u8], imports: &[&str]) {
+        let put = |data: &mut [u8], offset: usize, value: u32| {
+            data[offset..offset + 4].copy_from_slice(&value.to_le_bytes())
+        };
+
+        // Descriptors at 0x500 and their names after the terminating one
+        let table = 0x500;
+        put(data, 0x58 + 104, 0x1000 + table as u32 - 0x200);
+        put(data, 0x58 + 108, ((imports.len() + 1) * 20) as u32);
+
+        let mut name_offset = table + (imports.len() + 1) * 20;
+        for (i, name) in imports.iter().enumerate() {
+            put(
+                data,
+                table + i * 20 + 12,
+                0x1000 + name_offset as u32 - 0x200,
+            );
+            data[name_offset..name_offset + name.len()].copy_from_slice(name.as_bytes());
+            name_offset += name.len() + 1;
+        }
+    }
+
+    #[test]
+    fn reads_machine_exports_imports_and_version() {
+        let pe = PeFile::parse(build_pe(0x014c, &["DecalStartup", "DllMain"], None))
+            .expect("dll should parse");
+        assert_eq!(pe.machine(), Machine::X86);
+        assert_eq!(pe.timestamp(), 0x5000_0000);
+        assert_eq!(pe.image_base(), 0x0040_0000);
+        assert_eq!(pe.sections()[0].name, ".text");
+        assert!(pe.sections()[0].is_executable());
+        assert_eq!(pe.file_version(), None);
+        assert_eq!(
+            pe.exports().expect("exports should be readable"),
+            vec!["DecalStartup".to_string(), "DllMain".to_string()]
+        );
+
+        let pe = PeFile::parse(build_pe(0x8664, &[], Some([1, 2, 3, 4]))).expect("pe should parse");
+        assert_eq!(pe.machine(), Machine::X64);
+        assert!(pe.exports().expect("no exports").is_empty());
+        assert!(pe.imports().expect("no imports").is_empty());
+        assert_eq!(pe.file_version().as_deref(), Some("1.2.3.4"));
+
+        let mut data = build_pe(0x014c, &[], None);
+        add_imports(&mut data, &["KERNEL32.dll", "MSVCP140.dll"]);
+        let pe = PeFile::parse(data).expect("dll should parse");
+        assert_eq!(
+            pe.imports().expect("imports should be readable"),
+            vec!["KERNEL32.dll".to_string(), "MSVCP140.dll".to_string()]
+        );
+
+        assert!(PeFile::parse(b"#!/bin/sh\n".to_vec()).is_err());
+    }
+}
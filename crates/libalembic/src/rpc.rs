@@ -4,6 +4,9 @@ use tokio::sync::{Mutex, mpsc::Sender};
 
 use crate::msg::{client_server::ClientServerMessage, server_gui::ServerGuiMessage};
 
+/// Port on localhost the launcher listens on for RPC from injected DLLs
+pub const RPC_PORT: u16 = 5000;
+
 #[tarpc::service]
 pub trait World {
     async fn append_log(value: String) -> String;
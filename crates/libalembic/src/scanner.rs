@@ -1,10 +1,16 @@
 use crate::client_config::{LaunchCommand, WindowsClientConfig, WineClientConfig};
+use crate::fingerprint::ClientFingerprint;
 use crate::inject_config::{DllType, InjectConfig};
-use crate::settings::ClientConfigType;
+use crate::settings::{ClientConfigType, new_id};
+use crate::wine_paths::DosDevices;
 use anyhow::Result;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
 
 /// Extract WINEPREFIX from a WineClientConfig's launch command.
 #[cfg(not(target_os = "windows"))]
@@ -12,68 +18,349 @@ fn get_wineprefix(wine_config: &WineClientConfig) -> Option<String> {
     wine_config.launch_command.env.get("WINEPREFIX").cloned()
 }
 
-/// Trait for client installation scanners
-pub trait ClientScanner {
+/// How deep to look for Wine prefixes under a scan root
+const PREFIX_SEARCH_DEPTH: usize = 3;
+
+/// How deep to look for acclient.exe under a prefix's drive_c (or a scan root
+/// on Windows) when it isn't in one of the usual places
+const CLIENT_SEARCH_DEPTH: usize = 4;
+
+/// User-configurable places to look, saved in settings
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// Extra directories to scan. On macOS and Linux, each may be a Wine
+    /// prefix or contain prefixes. On Windows, each is searched for
+    /// acclient.exe.
+    #[serde(default)]
+    pub roots: Vec<PathBuf>,
+    /// Extra glob patterns for acclient.exe, like `Games/*/acclient.exe`.
+    /// Relative patterns are matched under each prefix's drive_c.
+    #[serde(default)]
+    pub client_globs: Vec<String>,
+    /// Extra glob patterns for DLLs to inject, matched like `client_globs`.
+    /// Inject.dll is taken to be Decal and anything else Alembic.
+    #[serde(default)]
+    pub dll_globs: Vec<String>,
+}
+
+impl ScanOptions {
+    /// Check that `client_globs` and `dll_globs` are valid glob patterns
+    pub fn check_globs(&self) -> Result<()> {
+        for pattern in self.client_globs.iter().chain(&self.dll_globs) {
+            glob::Pattern::new(pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid scan glob '{}': {}", pattern, e))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Something that happened during a scan, for showing progress
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanEvent {
+    /// A scanner started looking for clients
+    ScannerStarted { scanner: String },
+    /// A scanner is checking a Wine prefix or directory
+    Checking { scanner: String, path: PathBuf },
+    /// A scanner found a client
+    Found {
+        scanner: String,
+        name: String,
+        client_path: PathBuf,
+    },
+    /// A scanner finished, finding this many clients
+    ScannerFinished { scanner: String, found: usize },
+    /// A scanner failed. The other scanners carry on.
+    ScannerFailed { scanner: String, error: String },
+}
+
+impl std::fmt::Display for ScanEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanEvent::ScannerStarted { scanner } => write!(f, "[{}] Scanning...", scanner),
+            ScanEvent::Checking { scanner, path } => {
+                write!(f, "[{}] Checking {}", scanner, path.display())
+            }
+            ScanEvent::Found {
+                scanner,
+                name,
+                client_path,
+            } => write!(
+                f,
+                "[{}] Found {} ({})",
+                scanner,
+                name,
+                client_path.display()
+            ),
+            ScanEvent::ScannerFinished { scanner, found } => {
+                write!(f, "[{}] Done, found {} client(s)", scanner, found)
+            }
+            ScanEvent::ScannerFailed { scanner, error } => {
+                write!(f, "[{}] Failed: {}", scanner, error)
+            }
+        }
+    }
+}
+
+/// Cancels a running scan. Clones share the same flag, so one can be handed
+/// to another thread or a Ctrl-C handler.
+#[derive(Clone, Debug, Default)]
+pub struct ScanCancel(Arc<AtomicBool>);
+
+impl ScanCancel {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Passed to scanners to report progress and check for cancellation
+#[derive(Clone, Debug, Default)]
+pub struct ScanProgress {
+    events: Option<Sender<ScanEvent>>,
+    cancel: ScanCancel,
+    /// Name of the scanner reporting, set by `scan_all_with_progress`
+    scanner: String,
+}
+
+impl ScanProgress {
+    pub fn new(events: Sender<ScanEvent>, cancel: ScanCancel) -> Self {
+        Self {
+            events: Some(events),
+            cancel,
+            scanner: String::new(),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    fn for_scanner(&self, scanner: &str) -> Self {
+        Self {
+            scanner: scanner.to_string(),
+            ..self.clone()
+        }
+    }
+
+    fn emit(&self, event: ScanEvent) {
+        // Nobody may be listening anymore, which is fine
+        if let Some(events) = &self.events {
+            let _ = events.send(event);
+        }
+    }
+
+    fn checking(&self, path: &Path) {
+        self.emit(ScanEvent::Checking {
+            scanner: self.scanner.clone(),
+            path: path.to_path_buf(),
+        });
+    }
+
+    fn found(&self, client: &ClientConfigType) {
+        self.emit(ScanEvent::Found {
+            scanner: self.scanner.clone(),
+            name: client.name().to_string(),
+            client_path: client.client_path().to_path_buf(),
+        });
+    }
+}
+
+/// Most prefixes a scanner checks at once
+const MAX_SCAN_THREADS: usize = 8;
+
+/// Run `scan` on each item across a few threads, skipping whatever is left
+/// once the scan is cancelled. Results keep the order of `items`.
+fn scan_each<T: Sync>(
+    items: &[T],
+    progress: &ScanProgress,
+    scan: impl Fn(&T) -> Vec<ClientConfigType> + Sync,
+) -> Vec<ClientConfigType> {
+    let threads = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_SCAN_THREADS)
+        .min(items.len());
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= items.len() || progress.is_cancelled() {
+                        break;
+                    }
+
+                    let configs = scan(&items[index]);
+                    results.lock().unwrap().push((index, configs));
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results
+        .into_iter()
+        .flat_map(|(_, configs)| configs)
+        .collect()
+}
+
+/// Trait for client installation scanners. Scanners run on their own threads.
+pub trait ClientScanner: Send + Sync {
     /// Returns the name of this scanner (e.g., "Wine", "Whisky", "Windows Registry")
     fn name(&self) -> &str;
 
-    /// Scan for client installations and return discovered configs
-    fn scan(&self) -> Result<Vec<ClientConfigType>>;
+    /// Scan for client installations and return discovered configs, reporting
+    /// to `progress` and stopping early when it's cancelled
+    fn scan(&self, options: &ScanOptions, progress: &ScanProgress)
+    -> Result<Vec<ClientConfigType>>;
 
     /// Check if this scanner is available on the current platform
     fn is_available(&self) -> bool;
 }
 
-/// Convert a Unix path within a Wine prefix to a Windows-style path
-fn unix_to_windows_path(unix_path: &Path) -> Result<PathBuf> {
-    let path_str = unix_path.display().to_string();
+/// Match glob `patterns` under `base`. Absolute patterns are matched as-is.
+/// Invalid patterns match nothing.
+fn glob_under(base: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut matches = vec![];
+
+    for pattern in patterns {
+        let full_pattern = if Path::new(pattern).is_absolute() {
+            pattern.clone()
+        } else {
+            format!(
+                "{}/{}",
+                glob::Pattern::escape(&base.to_string_lossy()),
+                pattern
+            )
+        };
 
-    if let Some(idx) = path_str.find("/drive_c/") {
-        let relative = &path_str[idx + 9..]; // Skip "/drive_c/"
-        let windows = format!("C:\\{}", relative.replace("/", "\\"));
-        Ok(PathBuf::from(windows))
-    } else {
-        anyhow::bail!("Path does not contain /drive_c/: {}", path_str)
+        // Invalid patterns were already rejected by ScanOptions::check_globs
+        if let Ok(paths) = glob::glob(&full_pattern) {
+            matches.extend(paths.flatten().filter(|path| path.is_file()));
+        }
     }
+
+    matches
 }
 
-/// Convert a Windows-style path back to a Unix path within a Wine prefix
-pub fn windows_to_unix_path(prefix: &Path, windows_path: &Path) -> Result<PathBuf> {
-    let path_str = windows_path.to_string_lossy();
+/// Search `dir` for acclient.exe, at most `depth` directories down. Doesn't
+/// follow symlinks, since prefixes link back to themselves through
+/// dosdevices, and skips the Windows directory.
+fn find_acclient(dir: &Path, depth: usize) -> Option<PathBuf> {
+    let mut subdirs = vec![];
 
-    // Strip the drive letter (e.g., "C:\") and convert backslashes
-    if let Some(rest) = path_str
-        .strip_prefix("C:\\")
-        .or_else(|| path_str.strip_prefix("c:\\"))
-    {
-        let unix_relative = rest.replace('\\', "/");
-        Ok(prefix.join("drive_c").join(unix_relative))
-    } else {
-        anyhow::bail!(
-            "Path does not start with a drive letter: {}",
-            windows_path.display()
-        )
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let file_name = entry.file_name();
+
+        if file_type.is_file() && file_name.eq_ignore_ascii_case("acclient.exe") {
+            return Some(entry.path());
+        }
+        if file_type.is_dir() && !file_name.eq_ignore_ascii_case("windows") {
+            subdirs.push(entry.path());
+        }
     }
+
+    if depth == 0 {
+        return None;
+    }
+    subdirs.sort();
+    subdirs
+        .iter()
+        .find_map(|subdir| find_acclient(subdir, depth - 1))
 }
 
-/// Check if acclient.exe exists anywhere in a Wine prefix
-fn prefix_has_acclient(prefix: &Path) -> bool {
+/// Find acclient.exe in a Wine prefix: in the usual install directories,
+/// then anything matching the configured globs, then anywhere in drive_c
+fn find_acclient_in_prefix(prefix: &Path, options: &ScanOptions) -> Option<PathBuf> {
     let drive_c = prefix.join("drive_c");
+    if !drive_c.is_dir() {
+        return None;
+    }
+
     AC_SEARCH_PATHS
         .iter()
-        .any(|search_path| drive_c.join(search_path).join("acclient.exe").exists())
+        .map(|search_path| drive_c.join(search_path).join("acclient.exe"))
+        .find(|exe_path| exe_path.exists())
+        .or_else(|| {
+            glob_under(&drive_c, &options.client_globs)
+                .into_iter()
+                .next()
+        })
+        .or_else(|| find_acclient(&drive_c, CLIENT_SEARCH_DEPTH))
+}
+
+/// Find Wine prefixes at or under `root`, at most `depth` directories down.
+/// Prefixes aren't searched for further prefixes.
+pub fn find_wine_prefixes(root: &Path, depth: usize) -> Vec<PathBuf> {
+    if root.join("drive_c").is_dir() {
+        return vec![root.to_path_buf()];
+    }
+    if depth == 0 {
+        return vec![];
+    }
+
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return vec![];
+    };
+    let mut subdirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .collect();
+    subdirs.sort();
+
+    subdirs
+        .iter()
+        .flat_map(|subdir| find_wine_prefixes(subdir, depth - 1))
+        .collect()
 }
 
+/// Guess what kind of DLL a file matched by `dll_globs` is from its name
+fn inject_config_for(dll_path: PathBuf) -> InjectConfig {
+    let is_decal = dll_path
+        .file_name()
+        .is_some_and(|name| name.eq_ignore_ascii_case("Inject.dll"));
+
+    if is_decal {
+        InjectConfig {
+            id: new_id(),
+            dll_type: DllType::Decal,
+            dll_path,
+            startup_function: Some("DecalStartup".to_string()),
+        }
+    } else {
+        InjectConfig {
+            id: new_id(),
+            dll_type: DllType::Alembic,
+            dll_path,
+            startup_function: None,
+        }
+    }
+}
+
+/// Where Decal's installer puts it by default, relative to drive_c
+const DECAL_DEFAULT_DIRS: [&str; 2] = ["Program Files/Decal 3.0", "Program Files (x86)/Decal 3.0"];
+
 /// Discover DLL installations in a Wine prefix.
 /// Only returns results if acclient.exe also exists in the prefix,
 /// since DLLs are useless without a client to inject into.
-pub fn discover_dlls_in_wine_prefix(prefix: &Path) -> Vec<InjectConfig> {
+pub fn discover_dlls_in_wine_prefix(prefix: &Path, options: &ScanOptions) -> Vec<InjectConfig> {
     let mut inject_configs = vec![];
 
     let drive_c = prefix.join("drive_c");
+    let devices = DosDevices::load(prefix);
 
     // Don't report any DLLs if there's no AC client in this prefix
-    if !prefix_has_acclient(prefix) {
+    if find_acclient_in_prefix(prefix, options).is_none() {
         return inject_configs;
     }
 
@@ -82,9 +369,10 @@ pub fn discover_dlls_in_wine_prefix(prefix: &Path) -> Vec<InjectConfig> {
     for search_path in alembic_search_paths {
         let path = drive_c.join(search_path);
         if path.exists()
-            && let Ok(windows_path) = unix_to_windows_path(&path)
+            && let Ok(windows_path) = devices.to_windows(&path)
         {
             inject_configs.push(InjectConfig {
+                id: new_id(),
                 dll_type: DllType::Alembic,
                 dll_path: windows_path,
                 startup_function: None,
@@ -92,17 +380,33 @@ pub fn discover_dlls_in_wine_prefix(prefix: &Path) -> Vec<InjectConfig> {
         }
     }
 
-    let decal_search_paths = ["Program Files/Decal 3.0", "Program Files (x86)/Decal 3.0"];
-    for search_path in decal_search_paths {
-        let inject_dll_path = drive_c.join(search_path).join("Inject.dll");
-        if inject_dll_path.exists()
-            && let Ok(dll_path) = unix_to_windows_path(&inject_dll_path)
+    // Fall back to the installer's default locations for prefixes whose
+    // registry doesn't say where Decal is
+    let inject_dll_path = crate::decal::install_dir(prefix)
+        .into_iter()
+        .chain(DECAL_DEFAULT_DIRS.iter().map(|dir| drive_c.join(dir)))
+        .map(|dir| dir.join("Inject.dll"))
+        .find(|path| path.exists());
+    if let Some(inject_dll_path) = inject_dll_path
+        && let Ok(dll_path) = devices.to_windows(&inject_dll_path)
+    {
+        inject_configs.push(InjectConfig {
+            id: new_id(),
+            dll_type: DllType::Decal,
+            dll_path,
+            startup_function: Some("DecalStartup".to_string()),
+        });
+    }
+
+    for dll_path in glob_under(&drive_c, &options.dll_globs) {
+        let Ok(windows_path) = devices.to_windows(&dll_path) else {
+            continue;
+        };
+        if !inject_configs
+            .iter()
+            .any(|dll| dll.dll_path == windows_path)
         {
-            inject_configs.push(InjectConfig {
-                dll_type: DllType::Decal,
-                dll_path,
-                startup_function: Some("DecalStartup".to_string()),
-            });
+            inject_configs.push(inject_config_for(windows_path));
         }
     }
 
@@ -122,142 +426,131 @@ fn scan_prefix_for_ac(
     wine_prefix_path: &Path,
     name: &str,
     launch_command: LaunchCommand,
+    options: &ScanOptions,
+    progress: &ScanProgress,
 ) -> Result<Vec<ClientConfigType>> {
-    let drive_c = wine_prefix_path.join("drive_c");
-    if !drive_c.exists() || !drive_c.is_dir() {
-        return Ok(vec![]);
-    }
-
-    for search_path in AC_SEARCH_PATHS {
-        let exe_path = drive_c.join(search_path).join("acclient.exe");
+    progress.checking(wine_prefix_path);
 
-        if exe_path.exists() {
-            let windows_exe_path = unix_to_windows_path(&exe_path)?;
-            let dlls = discover_dlls_in_wine_prefix(wine_prefix_path);
-            let selected_dll = if !dlls.is_empty() { Some(0) } else { None };
-
-            return Ok(vec![ClientConfigType::Wine(WineClientConfig {
-                name: name.to_string(),
-                client_path: windows_exe_path,
-                launch_command,
-                dlls,
-                selected_dll,
-            })]);
-        }
-    }
+    let Some(exe_path) = find_acclient_in_prefix(wine_prefix_path, options) else {
+        return Ok(vec![]);
+    };
 
-    Ok(vec![])
+    let windows_exe_path = DosDevices::load(wine_prefix_path).to_windows(&exe_path)?;
+    let dlls = discover_dlls_in_wine_prefix(wine_prefix_path, options);
+    let selected_dll = dlls.first().map(|dll| dll.id.clone());
+
+    let client = ClientConfigType::Wine(WineClientConfig {
+        id: new_id(),
+        name: name.to_string(),
+        client_path: windows_exe_path,
+        launch_command,
+        dlls,
+        selected_dll,
+        fingerprint: ClientFingerprint::read(&exe_path).ok(),
+    });
+    progress.found(&client);
+
+    Ok(vec![client])
 }
 
 pub struct WineScanner {
     wine_executable_path: PathBuf,
+    /// Only scan the configured roots, not the usual prefix locations
+    roots_only: bool,
 }
 
 impl WineScanner {
     pub fn new(wine_executable_path: PathBuf) -> Self {
         Self {
             wine_executable_path,
+            roots_only: false,
         }
     }
 
-    fn scan_prefix(&self, wine_prefix_path: &Path) -> Result<Vec<ClientConfigType>> {
+    /// A scanner for prefixes under the configured roots only, for when
+    /// there's no wine install of our own to go with the usual locations
+    pub fn roots_only(wine_executable_path: PathBuf) -> Self {
+        Self {
+            wine_executable_path,
+            roots_only: true,
+        }
+    }
+
+    fn scan_prefix(
+        &self,
+        wine_prefix_path: &Path,
+        options: &ScanOptions,
+        progress: &ScanProgress,
+    ) -> Result<Vec<ClientConfigType>> {
         let launch_command = LaunchCommand::new(&self.wine_executable_path)
             .env("WINEPREFIX", wine_prefix_path.display().to_string());
         let name = format!("Wine: {}", wine_prefix_path.display());
-        scan_prefix_for_ac(wine_prefix_path, &name, launch_command)
-    }
-}
-
-impl ClientScanner for WineScanner {
-    fn name(&self) -> &str {
-        "Wine"
+        scan_prefix_for_ac(wine_prefix_path, &name, launch_command, options, progress)
     }
 
-    fn scan(&self) -> Result<Vec<ClientConfigType>> {
-        let mut all_configs = vec![];
-        let mut scanned_prefixes: Vec<PathBuf> = vec![];
-
-        let home = std::env::var("HOME")?;
-
-        // Known wine prefix locations (these are prefixes themselves)
-        let known_prefixes = vec![PathBuf::from(&home).join(".wine")];
+    /// Get the list of prefixes that would be scanned: ~/.wine, prefixes in
+    /// ~/.local/share/wineprefixes, and prefixes under the configured roots
+    pub fn get_scannable_prefixes(options: &ScanOptions) -> Vec<PathBuf> {
+        let mut prefixes = vec![];
 
-        // Directories that may contain wine prefixes as subdirectories
-        let prefix_containers = vec![PathBuf::from(&home).join(".local/share/wineprefixes")];
+        if let Ok(home) = std::env::var("HOME") {
+            let home = PathBuf::from(home);
+            prefixes.extend(find_wine_prefixes(&home.join(".wine"), 0));
+            prefixes.extend(find_wine_prefixes(
+                &home.join(".local/share/wineprefixes"),
+                1,
+            ));
+        }
 
-        // Scan known prefixes directly
-        for prefix in known_prefixes {
-            if prefix.exists() && prefix.join("drive_c").is_dir() {
-                scanned_prefixes.push(prefix.clone());
-                if let Ok(mut configs) = self.scan_prefix(&prefix) {
-                    all_configs.append(&mut configs);
-                }
+        for prefix in Self::root_prefixes(options) {
+            if !prefixes.contains(&prefix) {
+                prefixes.push(prefix);
             }
         }
 
-        // Scan containers for subdirectories that look like wine prefixes
-        for container in prefix_containers {
-            if !container.exists() {
-                continue;
-            }
-            if let Ok(entries) = std::fs::read_dir(&container) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_dir() && path.join("drive_c").is_dir() {
-                        scanned_prefixes.push(path.clone());
-                        if let Ok(mut configs) = self.scan_prefix(&path) {
-                            all_configs.append(&mut configs);
-                        }
-                    }
+        prefixes
+    }
+
+    /// Prefixes under the configured roots
+    fn root_prefixes(options: &ScanOptions) -> Vec<PathBuf> {
+        let mut prefixes = vec![];
+
+        for root in &options.roots {
+            for prefix in find_wine_prefixes(root, PREFIX_SEARCH_DEPTH) {
+                if !prefixes.contains(&prefix) {
+                    prefixes.push(prefix);
                 }
             }
         }
 
-        Ok(all_configs)
-    }
-
-    fn is_available(&self) -> bool {
-        cfg!(any(target_os = "macos", target_os = "linux"))
+        prefixes
     }
 }
 
-impl WineScanner {
-    /// Get the list of prefixes that would be scanned (for reporting)
-    pub fn get_scannable_prefixes() -> Vec<PathBuf> {
-        let mut prefixes = vec![];
+impl ClientScanner for WineScanner {
+    fn name(&self) -> &str {
+        "Wine"
+    }
 
-        let home = match std::env::var("HOME") {
-            Ok(h) => h,
-            Err(_) => return prefixes,
+    fn scan(
+        &self,
+        options: &ScanOptions,
+        progress: &ScanProgress,
+    ) -> Result<Vec<ClientConfigType>> {
+        let prefixes = if self.roots_only {
+            Self::root_prefixes(options)
+        } else {
+            Self::get_scannable_prefixes(options)
         };
 
-        // Known wine prefix locations
-        let known_prefixes = vec![PathBuf::from(&home).join(".wine")];
-
-        // Directories that may contain wine prefixes
-        let prefix_containers = vec![PathBuf::from(&home).join(".local/share/wineprefixes")];
-
-        for prefix in known_prefixes {
-            if prefix.exists() && prefix.join("drive_c").is_dir() {
-                prefixes.push(prefix);
-            }
-        }
-
-        for container in prefix_containers {
-            if !container.exists() {
-                continue;
-            }
-            if let Ok(entries) = std::fs::read_dir(&container) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_dir() && path.join("drive_c").is_dir() {
-                        prefixes.push(path);
-                    }
-                }
-            }
-        }
+        Ok(scan_each(&prefixes, progress, |prefix| {
+            self.scan_prefix(prefix, options, progress)
+                .unwrap_or_default()
+        }))
+    }
 
-        prefixes
+    fn is_available(&self) -> bool {
+        cfg!(any(target_os = "macos", target_os = "linux"))
     }
 }
 
@@ -328,11 +621,13 @@ impl WhiskyScanner {
         wine_prefix_path: &Path,
         wine_exe: &Path,
         bottle_name: &str,
+        options: &ScanOptions,
+        progress: &ScanProgress,
     ) -> Result<Vec<ClientConfigType>> {
         let launch_command =
             LaunchCommand::new(wine_exe).env("WINEPREFIX", wine_prefix_path.display().to_string());
         let name = format!("Whisky: {}", bottle_name);
-        scan_prefix_for_ac(wine_prefix_path, &name, launch_command)
+        scan_prefix_for_ac(wine_prefix_path, &name, launch_command, options, progress)
     }
 }
 
@@ -341,9 +636,11 @@ impl ClientScanner for WhiskyScanner {
         "Whisky"
     }
 
-    fn scan(&self) -> Result<Vec<ClientConfigType>> {
-        let mut all_configs = vec![];
-
+    fn scan(
+        &self,
+        options: &ScanOptions,
+        progress: &ScanProgress,
+    ) -> Result<Vec<ClientConfigType>> {
         // Get list of bottles
         let output = Command::new("whisky").arg("list").output()?;
 
@@ -368,21 +665,19 @@ impl ClientScanner for WhiskyScanner {
             }
         }
 
-        // Scan each bottle
-        for bottle in bottles {
-            match self.get_bottle_info(&bottle) {
-                Ok((wine_exe, prefix)) => {
-                    if let Ok(mut configs) = self.scan_prefix(&prefix, &wine_exe, &bottle) {
-                        all_configs.append(&mut configs);
-                    }
-                }
+        // Scan each bottle. Getting a bottle's info runs whisky again, so
+        // this is worth doing in parallel.
+        Ok(scan_each(&bottles, progress, |bottle| {
+            match self.get_bottle_info(bottle) {
+                Ok((wine_exe, prefix)) => self
+                    .scan_prefix(&prefix, &wine_exe, bottle, options, progress)
+                    .unwrap_or_default(),
                 Err(e) => {
                     eprintln!("Warning: Failed to get info for bottle '{}': {}", bottle, e);
+                    vec![]
                 }
             }
-        }
-
-        Ok(all_configs)
+        }))
     }
 
     fn is_available(&self) -> bool {
@@ -471,6 +766,8 @@ impl LutrisFlatpakScanner {
         &self,
         wine_prefix_path: &Path,
         game_name: &str,
+        options: &ScanOptions,
+        progress: &ScanProgress,
     ) -> Result<Vec<ClientConfigType>> {
         let launch_command = LaunchCommand::new("flatpak")
             .arg("run")
@@ -478,7 +775,7 @@ impl LutrisFlatpakScanner {
             .arg("net.lutris.Lutris")
             .env("WINEPREFIX", wine_prefix_path.display().to_string());
         let name = format!("Lutris: {}", game_name);
-        scan_prefix_for_ac(wine_prefix_path, &name, launch_command)
+        scan_prefix_for_ac(wine_prefix_path, &name, launch_command, options, progress)
     }
 }
 
@@ -487,40 +784,764 @@ impl ClientScanner for LutrisFlatpakScanner {
         "Lutris (Flatpak)"
     }
 
-    fn scan(&self) -> Result<Vec<ClientConfigType>> {
-        let mut all_configs = vec![];
-
+    fn scan(
+        &self,
+        options: &ScanOptions,
+        progress: &ScanProgress,
+    ) -> Result<Vec<ClientConfigType>> {
         let games_dir = match Self::get_games_dir() {
             Some(dir) if dir.exists() => dir,
-            _ => return Ok(all_configs),
+            _ => return Ok(vec![]),
         };
 
         let entries = match std::fs::read_dir(&games_dir) {
             Ok(e) => e,
-            Err(_) => return Ok(all_configs),
+            Err(_) => return Ok(vec![]),
         };
 
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().is_some_and(|ext| ext == "yml") {
-                match Self::parse_game_config(&path) {
-                    Ok((prefix, _wine_path, name)) => {
-                        if let Ok(mut configs) = self.scan_prefix(&prefix, &name) {
-                            all_configs.append(&mut configs);
-                        }
-                    }
-                    Err(_) => {
-                        // Skip configs we can't parse
-                    }
+        let games: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "yml"))
+            .collect();
+
+        Ok(scan_each(&games, progress, |path| {
+            match Self::parse_game_config(path) {
+                Ok((prefix, _wine_path, name)) => self
+                    .scan_prefix(&prefix, &name, options, progress)
+                    .unwrap_or_default(),
+                // Skip configs we can't parse
+                Err(_) => vec![],
+            }
+        }))
+    }
+
+    fn is_available(&self) -> bool {
+        cfg!(target_os = "linux")
+    }
+}
+
+/// The parts of a native Lutris config we need to run a game the way Lutris
+/// would. Game configs, `runners/wine.yml` and `system.yml` all share this
+/// layout, with later ones overriding earlier ones.
+#[derive(Debug, Default, Deserialize)]
+struct LutrisLayeredConfig {
+    game: Option<LutrisGameOptions>,
+    wine: Option<LutrisWineOptions>,
+    system: Option<LutrisSystemOptions>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LutrisGameOptions {
+    prefix: Option<String>,
+    arch: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LutrisWineOptions {
+    version: Option<String>,
+    esync: Option<bool>,
+    fsync: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LutrisSystemOptions {
+    /// Values can be any YAML scalar, e.g. `DXVK_ASYNC: 1`
+    env: Option<HashMap<String, serde_yaml::Value>>,
+}
+
+/// A Wine game installed with Lutris
+#[derive(Debug, PartialEq, Eq)]
+struct LutrisGame {
+    name: String,
+    config_path: PathBuf,
+}
+
+/// Scans games installed with a native (non-Flatpak) Lutris
+pub struct LutrisScanner {
+    /// `~/.local/share/lutris`, which has the game database, runners and,
+    /// since Lutris 0.5.13, game configs
+    data_dir: PathBuf,
+    /// `~/.config/lutris`, where older versions of Lutris keep configs
+    config_dir: PathBuf,
+}
+
+impl LutrisScanner {
+    pub fn new() -> Option<Self> {
+        let home = PathBuf::from(std::env::var("HOME").ok()?);
+
+        Some(Self {
+            data_dir: home.join(".local/share/lutris"),
+            config_dir: home.join(".config/lutris"),
+        })
+    }
+
+    /// Find a config file in the data directory, or else the config directory
+    fn find_config(&self, relative: &str) -> Option<PathBuf> {
+        [&self.data_dir, &self.config_dir]
+            .iter()
+            .map(|dir| dir.join(relative))
+            .find(|path| path.exists())
+    }
+
+    fn read_config(path: &Path) -> Result<LutrisLayeredConfig> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_yaml::from_str::<Option<LutrisLayeredConfig>>(&content)?.unwrap_or_default())
+    }
+
+    /// Installed Wine games, from the game database when there is one and
+    /// otherwise from the game configs themselves
+    fn games(&self) -> Vec<LutrisGame> {
+        let db_path = self.data_dir.join("pga.db");
+        if db_path.exists() {
+            match read_lutris_game_list(&db_path) {
+                Ok(games) => {
+                    return games
+                        .into_iter()
+                        .filter_map(|(name, config_name)| {
+                            let config_path =
+                                self.find_config(&format!("games/{}.yml", config_name))?;
+                            Some(LutrisGame { name, config_path })
+                        })
+                        .collect();
+                }
+                Err(e) => {
+                    eprintln!("Warning: Failed to read {}: {}", db_path.display(), e);
+                }
+            }
+        }
+
+        let mut games = vec![];
+        for dir in [&self.data_dir, &self.config_dir] {
+            let Ok(entries) = std::fs::read_dir(dir.join("games")) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "yml") {
+                    games.push(LutrisGame {
+                        name: path
+                            .file_stem()
+                            .map(|stem| stem.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                        config_path: path,
+                    });
                 }
             }
         }
 
-        Ok(all_configs)
+        games
+    }
+
+    /// Find the Wine binary for a runner version the way Lutris does: `system`
+    /// is the Wine on PATH, a named version lives under `runners/wine/`, and no
+    /// version means the newest one installed there
+    fn resolve_wine(&self, version: Option<&str>) -> PathBuf {
+        let runners_dir = self.data_dir.join("runners/wine");
+
+        let version_dir = match version {
+            Some("system") => return PathBuf::from("wine"),
+            Some(version) => runners_dir.join(version),
+            None => {
+                let mut installed: Vec<PathBuf> = std::fs::read_dir(&runners_dir)
+                    .map(|entries| {
+                        entries
+                            .flatten()
+                            .map(|entry| entry.path())
+                            .filter(|path| path.is_dir())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                installed.sort();
+
+                match installed.pop() {
+                    Some(dir) => dir,
+                    None => return PathBuf::from("wine"),
+                }
+            }
+        };
+
+        // Proton-based builds keep Wine under files/
+        ["bin/wine", "files/bin/wine"]
+            .iter()
+            .map(|exe| version_dir.join(exe))
+            .find(|exe| exe.exists())
+            .unwrap_or_else(|| version_dir.join("bin/wine"))
+    }
+
+    /// Build the prefix and launch command for a game, layering the game's
+    /// config over the Wine runner's config over the global system config
+    fn launch_command(&self, game_config: &Path) -> Result<(PathBuf, LaunchCommand)> {
+        let mut layers = vec![];
+        for relative in ["system.yml", "runners/wine.yml"] {
+            if let Some(path) = self.find_config(relative) {
+                layers.push(Self::read_config(&path)?);
+            }
+        }
+        layers.push(Self::read_config(game_config)?);
+
+        let mut prefix = None;
+        let mut arch = None;
+        let mut version = None;
+        let mut esync = true;
+        let mut fsync = true;
+        let mut env = HashMap::new();
+
+        for layer in layers {
+            if let Some(game) = layer.game {
+                prefix = game.prefix.or(prefix);
+                arch = game.arch.or(arch);
+            }
+            if let Some(wine) = layer.wine {
+                version = wine.version.or(version);
+                esync = wine.esync.unwrap_or(esync);
+                fsync = wine.fsync.unwrap_or(fsync);
+            }
+            for (key, value) in layer.system.and_then(|s| s.env).unwrap_or_default() {
+                let value = match value {
+                    serde_yaml::Value::String(s) => s,
+                    serde_yaml::Value::Bool(b) => (if b { "1" } else { "0" }).to_string(),
+                    serde_yaml::Value::Number(n) => n.to_string(),
+                    _ => continue,
+                };
+                env.insert(key, value);
+            }
+        }
+
+        let Some(prefix) = prefix else {
+            anyhow::bail!("No prefix found in {}", game_config.display());
+        };
+        let prefix = PathBuf::from(prefix);
+
+        let mut launch_command = LaunchCommand::new(self.resolve_wine(version.as_deref()))
+            .env("WINEDEBUG", "-all")
+            .env("WINEPREFIX", prefix.display().to_string());
+        if let Some(arch) = arch {
+            launch_command = launch_command.env("WINEARCH", arch);
+        }
+        if esync {
+            launch_command = launch_command.env("WINEESYNC", "1");
+        }
+        if fsync {
+            launch_command = launch_command.env("WINEFSYNC", "1");
+        }
+        // The game's own environment variables win over Lutris' defaults
+        launch_command.env.extend(env);
+
+        Ok((prefix, launch_command))
+    }
+}
+
+/// Names and config file names (without `.yml`) of installed Wine games in
+/// Lutris' game database
+#[cfg(target_os = "linux")]
+fn read_lutris_game_list(db_path: &Path) -> Result<Vec<(String, String)>> {
+    use rusqlite::{Connection, OpenFlags};
+
+    let connection = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut statement = connection.prepare(
+        "SELECT name, configpath FROM games \
+         WHERE runner = 'wine' AND installed = 1 AND configpath IS NOT NULL",
+    )?;
+    let games = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(games)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_lutris_game_list(_db_path: &Path) -> Result<Vec<(String, String)>> {
+    anyhow::bail!("Lutris' game database is only read on Linux")
+}
+
+impl ClientScanner for LutrisScanner {
+    fn name(&self) -> &str {
+        "Lutris"
+    }
+
+    fn scan(
+        &self,
+        options: &ScanOptions,
+        progress: &ScanProgress,
+    ) -> Result<Vec<ClientConfigType>> {
+        let games = self.games();
+
+        Ok(scan_each(&games, progress, |game| {
+            match self.launch_command(&game.config_path) {
+                Ok((prefix, launch_command)) => {
+                    let name = format!("Lutris: {}", game.name);
+                    scan_prefix_for_ac(&prefix, &name, launch_command, options, progress)
+                        .unwrap_or_default()
+                }
+                // Skip configs we can't parse
+                Err(_) => vec![],
+            }
+        }))
     }
 
     fn is_available(&self) -> bool {
-        cfg!(target_os = "linux")
+        cfg!(target_os = "linux") && (self.data_dir.exists() || self.config_dir.exists())
+    }
+}
+
+// ============================================================================
+// BOTTLES SCANNER
+// ============================================================================
+
+/// Flatpak application ID of Bottles
+const BOTTLES_FLATPAK_ID: &str = "com.usebottles.bottles";
+
+/// The parts of a Bottles `bottle.yml` we need
+#[derive(Debug, Deserialize)]
+struct BottleConfig {
+    #[serde(rename = "Name")]
+    name: Option<String>,
+    /// Absolute path to the bottle when `Custom_Path` is set. Otherwise the
+    /// bottle is the directory `bottle.yml` is in.
+    #[serde(rename = "Path")]
+    path: Option<String>,
+    #[serde(rename = "Custom_Path", default)]
+    custom_path: bool,
+    /// Directory under `runners/`, or `sys-wine-*` for the system Wine
+    #[serde(rename = "Runner")]
+    runner: Option<String>,
+    #[serde(rename = "Environment_Variables")]
+    environment_variables: Option<HashMap<String, String>>,
+}
+
+/// Scans bottles managed by Bottles, either installed natively or from Flatpak
+pub struct BottlesScanner {
+    flatpak: bool,
+}
+
+impl BottlesScanner {
+    pub fn native() -> Self {
+        Self { flatpak: false }
+    }
+
+    pub fn flatpak() -> Self {
+        Self { flatpak: true }
+    }
+
+    /// Returns Bottles' data directory, which contains `bottles/` and `runners/`
+    fn get_data_dir(&self) -> Option<PathBuf> {
+        let home = PathBuf::from(std::env::var("HOME").ok()?);
+
+        if self.flatpak {
+            Some(
+                home.join(".var/app")
+                    .join(BOTTLES_FLATPAK_ID)
+                    .join("data/bottles"),
+            )
+        } else {
+            Some(home.join(".local/share/bottles"))
+        }
+    }
+
+    fn parse_bottle_config(path: &Path) -> Result<BottleConfig> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_yaml::from_str(&content)?)
+    }
+
+    fn bottle_prefix(bottle_dir: &Path, config: &BottleConfig) -> PathBuf {
+        match &config.path {
+            Some(path) if config.custom_path && Path::new(path).is_absolute() => {
+                PathBuf::from(path)
+            }
+            _ => bottle_dir.to_path_buf(),
+        }
+    }
+
+    /// Build a command that runs Wine from the bottle's runner, in the bottle,
+    /// with the bottle's environment variables
+    fn launch_command(
+        &self,
+        data_dir: &Path,
+        config: &BottleConfig,
+        prefix: &Path,
+    ) -> LaunchCommand {
+        let wine = match config.runner.as_deref() {
+            Some(runner) if !runner.starts_with("sys-") => {
+                let bin = data_dir.join("runners").join(runner).join("bin");
+                ["wine", "wine64"]
+                    .iter()
+                    .map(|exe| bin.join(exe))
+                    .find(|exe| exe.exists())
+                    .unwrap_or_else(|| bin.join("wine"))
+            }
+            // System Wine, from PATH (inside the sandbox, for Flatpak)
+            _ => PathBuf::from("wine"),
+        };
+
+        let mut launch_command = if self.flatpak {
+            LaunchCommand::new("flatpak")
+                .arg("run")
+                .arg(format!("--command={}", wine.display()))
+                .arg(BOTTLES_FLATPAK_ID)
+        } else {
+            LaunchCommand::new(wine)
+        };
+
+        for (key, value) in config.environment_variables.iter().flatten() {
+            launch_command = launch_command.env(key, value);
+        }
+
+        launch_command.env("WINEPREFIX", prefix.display().to_string())
+    }
+}
+
+impl ClientScanner for BottlesScanner {
+    fn name(&self) -> &str {
+        if self.flatpak {
+            "Bottles (Flatpak)"
+        } else {
+            "Bottles"
+        }
+    }
+
+    fn scan(
+        &self,
+        options: &ScanOptions,
+        progress: &ScanProgress,
+    ) -> Result<Vec<ClientConfigType>> {
+        let data_dir = match self.get_data_dir() {
+            Some(dir) if dir.exists() => dir,
+            _ => return Ok(vec![]),
+        };
+
+        let entries = match std::fs::read_dir(data_dir.join("bottles")) {
+            Ok(e) => e,
+            Err(_) => return Ok(vec![]),
+        };
+
+        let bottle_dirs: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+
+        Ok(scan_each(&bottle_dirs, progress, |bottle_dir| {
+            let config = match Self::parse_bottle_config(&bottle_dir.join("bottle.yml")) {
+                Ok(config) => config,
+                // Not a bottle, or one we can't parse
+                Err(_) => return vec![],
+            };
+
+            let prefix = Self::bottle_prefix(bottle_dir, &config);
+            let bottle_name = config.name.clone().unwrap_or_else(|| {
+                bottle_dir
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string()
+            });
+            let launch_command = self.launch_command(&data_dir, &config, &prefix);
+            let name = format!("Bottles: {}", bottle_name);
+
+            scan_prefix_for_ac(&prefix, &name, launch_command, options, progress)
+                .unwrap_or_default()
+        }))
+    }
+
+    fn is_available(&self) -> bool {
+        cfg!(target_os = "linux") && self.get_data_dir().is_some_and(|dir| dir.exists())
+    }
+}
+
+// ============================================================================
+// PROTON SCANNER
+// ============================================================================
+
+/// Read every value for `key` from a Steam text VDF/ACF file
+fn vdf_values(contents: &str, key: &str) -> Vec<String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.trim().split('"').collect();
+            // A line like `"key"		"value"` splits into ["", key, "\t\t", value, ""]
+            (parts.len() >= 4 && parts[1] == key).then(|| parts[3].replace("\\\\", "\\"))
+        })
+        .collect()
+}
+
+/// Wine inside a Proton install. Older Proton versions used dist/ instead of files/.
+fn proton_wine(proton_dir: &Path) -> Option<PathBuf> {
+    ["files/bin/wine", "dist/bin/wine"]
+        .iter()
+        .map(|exe| proton_dir.join(exe))
+        .find(|exe| exe.exists())
+}
+
+/// Scans the Proton prefixes Steam creates under `steamapps/compatdata`,
+/// including those of non-Steam games
+pub struct ProtonScanner {
+    steam_roots: Vec<PathBuf>,
+}
+
+impl ProtonScanner {
+    pub fn new() -> Option<Self> {
+        let home = PathBuf::from(std::env::var("HOME").ok()?);
+        let candidates = [
+            home.join(".steam/steam"),
+            home.join(".local/share/Steam"),
+            home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+        ];
+
+        // ~/.steam/steam is usually a symlink to ~/.local/share/Steam
+        let mut steam_roots: Vec<PathBuf> = vec![];
+        for candidate in candidates {
+            if let Ok(root) = candidate.canonicalize()
+                && !steam_roots.contains(&root)
+            {
+                steam_roots.push(root);
+            }
+        }
+
+        Some(Self { steam_roots })
+    }
+
+    /// Every Steam library, from each Steam install's libraryfolders.vdf
+    fn libraries(&self) -> Vec<PathBuf> {
+        let mut libraries: Vec<PathBuf> = vec![];
+
+        for root in &self.steam_roots {
+            let mut found = vec![root.clone()];
+            for vdf in ["steamapps/libraryfolders.vdf", "config/libraryfolders.vdf"] {
+                if let Ok(contents) = std::fs::read_to_string(root.join(vdf)) {
+                    found.extend(vdf_values(&contents, "path").into_iter().map(PathBuf::from));
+                }
+            }
+
+            for library in found {
+                let library = library.canonicalize().unwrap_or(library);
+                if library.join("steamapps").is_dir() && !libraries.contains(&library) {
+                    libraries.push(library);
+                }
+            }
+        }
+
+        libraries
+    }
+
+    /// Installed Proton versions: Valve's in steamapps/common and custom
+    /// builds like GE-Proton in compatibilitytools.d
+    fn proton_installs(&self, libraries: &[PathBuf]) -> Vec<PathBuf> {
+        let mut dirs = vec![];
+        for library in libraries {
+            dirs.push(library.join("steamapps/common"));
+        }
+        for root in &self.steam_roots {
+            dirs.push(root.join("compatibilitytools.d"));
+        }
+
+        let mut installs = vec![];
+        for dir in dirs {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if proton_wine(&path).is_some() {
+                    installs.push(path);
+                }
+            }
+        }
+        installs.sort();
+
+        installs
+    }
+
+    /// The Proton install a prefix was created with. Steam records it in the
+    /// prefix's config_info, which lists paths inside the Proton install.
+    /// Falls back to the newest Proton found.
+    fn prefix_proton(compat_dir: &Path, installs: &[PathBuf]) -> Option<PathBuf> {
+        if let Ok(config_info) = std::fs::read_to_string(compat_dir.join("config_info")) {
+            let recorded = config_info.lines().find_map(|line| {
+                ["/files/", "/dist/"]
+                    .iter()
+                    .find_map(|marker| line.find(marker).map(|idx| PathBuf::from(&line[..idx])))
+            });
+
+            if let Some(proton_dir) = recorded
+                && proton_wine(&proton_dir).is_some()
+            {
+                return Some(proton_dir);
+            }
+        }
+
+        installs.last().cloned()
+    }
+
+    /// Run Proton's Wine directly, with the environment Proton would set up
+    fn launch_command(proton_dir: &Path, prefix: &Path) -> Option<LaunchCommand> {
+        let wine = proton_wine(proton_dir)?;
+        let files = wine.parent()?.parent()?.to_path_buf();
+
+        Some(
+            LaunchCommand::new(wine)
+                .env("WINEPREFIX", prefix.display().to_string())
+                .env(
+                    "WINEDLLPATH",
+                    format!(
+                        "{}:{}",
+                        files.join("lib64/wine").display(),
+                        files.join("lib/wine").display()
+                    ),
+                )
+                .env(
+                    "LD_LIBRARY_PATH",
+                    format!(
+                        "{}:{}",
+                        files.join("lib64").display(),
+                        files.join("lib").display()
+                    ),
+                )
+                .env("WINEESYNC", "1")
+                .env("WINEFSYNC", "1")
+                .env("WINEDEBUG", "-all"),
+        )
+    }
+
+    /// Name of a Steam game from its app manifest. Non-Steam games don't have one.
+    fn app_name(library: &Path, app_id: &str) -> Option<String> {
+        let manifest = library.join(format!("steamapps/appmanifest_{}.acf", app_id));
+        let contents = std::fs::read_to_string(manifest).ok()?;
+        vdf_values(&contents, "name").into_iter().next()
+    }
+}
+
+impl ClientScanner for ProtonScanner {
+    fn name(&self) -> &str {
+        "Proton"
+    }
+
+    fn scan(
+        &self,
+        options: &ScanOptions,
+        progress: &ScanProgress,
+    ) -> Result<Vec<ClientConfigType>> {
+        let libraries = self.libraries();
+        let installs = self.proton_installs(&libraries);
+
+        // Each library's compatdata holds one prefix per game
+        let mut compat_dirs = vec![];
+        for library in &libraries {
+            let Ok(entries) = std::fs::read_dir(library.join("steamapps/compatdata")) else {
+                continue;
+            };
+
+            compat_dirs.extend(entries.flatten().map(|entry| (library, entry.path())));
+        }
+
+        Ok(scan_each(
+            &compat_dirs,
+            progress,
+            |(library, compat_dir)| {
+                let prefix = compat_dir.join("pfx");
+                if !prefix.join("drive_c").is_dir() {
+                    return vec![];
+                }
+
+                let Some(launch_command) = Self::prefix_proton(compat_dir, &installs)
+                    .and_then(|proton_dir| Self::launch_command(&proton_dir, &prefix))
+                else {
+                    return vec![];
+                };
+
+                let app_id = compat_dir
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let name = match Self::app_name(library, &app_id) {
+                    Some(app_name) => format!("Proton: {}", app_name),
+                    None => format!("Proton: {}", app_id),
+                };
+
+                scan_prefix_for_ac(&prefix, &name, launch_command, options, progress)
+                    .unwrap_or_default()
+            },
+        ))
+    }
+
+    fn is_available(&self) -> bool {
+        cfg!(target_os = "linux") && !self.steam_roots.is_empty()
+    }
+}
+
+// ============================================================================
+// CROSSOVER SCANNER
+// ============================================================================
+
+/// Scans CrossOver bottles on macOS
+pub struct CrossOverScanner {
+    /// The `wine` script bundled with CrossOver, which selects a bottle with `--bottle`
+    wine: PathBuf,
+    bottles_dir: PathBuf,
+}
+
+impl CrossOverScanner {
+    pub fn new() -> Option<Self> {
+        let home = PathBuf::from(std::env::var("HOME").ok()?);
+
+        let wine = [
+            PathBuf::from("/Applications/CrossOver.app"),
+            home.join("Applications/CrossOver.app"),
+        ]
+        .iter()
+        .map(|app| app.join("Contents/SharedSupport/CrossOver/bin/wine"))
+        .find(|wine| wine.exists())?;
+
+        // CrossOver lets users move bottles with CX_BOTTLE_PATH
+        let bottles_dir = std::env::var("CX_BOTTLE_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| home.join("Library/Application Support/CrossOver/Bottles"));
+
+        Some(Self { wine, bottles_dir })
+    }
+
+    fn launch_command(&self, bottle_name: &str, prefix: &Path) -> LaunchCommand {
+        LaunchCommand::new(&self.wine)
+            .arg("--bottle")
+            .arg(bottle_name)
+            .env("CX_BOTTLE", bottle_name)
+            .env("WINEPREFIX", prefix.display().to_string())
+    }
+}
+
+impl ClientScanner for CrossOverScanner {
+    fn name(&self) -> &str {
+        "CrossOver"
+    }
+
+    fn scan(
+        &self,
+        options: &ScanOptions,
+        progress: &ScanProgress,
+    ) -> Result<Vec<ClientConfigType>> {
+        let Ok(entries) = std::fs::read_dir(&self.bottles_dir) else {
+            return Ok(vec![]);
+        };
+
+        let prefixes: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|prefix| prefix.join("drive_c").is_dir())
+            .collect();
+
+        Ok(scan_each(&prefixes, progress, |prefix| {
+            let bottle_name = prefix
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let launch_command = self.launch_command(&bottle_name, prefix);
+            let name = format!("CrossOver: {}", bottle_name);
+
+            scan_prefix_for_ac(prefix, &name, launch_command, options, progress).unwrap_or_default()
+        }))
+    }
+
+    fn is_available(&self) -> bool {
+        cfg!(target_os = "macos") && self.bottles_dir.exists()
     }
 }
 
@@ -548,7 +1569,11 @@ impl ClientScanner for WindowsScanner {
         "Windows File System"
     }
 
-    fn scan(&self) -> Result<Vec<ClientConfigType>> {
+    fn scan(
+        &self,
+        options: &ScanOptions,
+        progress: &ScanProgress,
+    ) -> Result<Vec<ClientConfigType>> {
         let mut configs = vec![];
 
         // Common AC installation paths on Windows
@@ -560,19 +1585,45 @@ impl ClientScanner for WindowsScanner {
             r"C:\Games\AC",
         ];
 
-        for search_path in search_paths {
-            let path = PathBuf::from(search_path);
-            let client_exe = path.join("acclient.exe");
-
-            if client_exe.exists() {
-                let name = format!("Asheron's Call - {}", search_path);
-                configs.push(ClientConfigType::Windows(WindowsClientConfig {
-                    name,
-                    client_path: client_exe,
-                    dlls: vec![],
-                    selected_dll: None,
-                }));
+        let mut found: Vec<PathBuf> = search_paths
+            .iter()
+            .map(|search_path| PathBuf::from(search_path).join("acclient.exe"))
+            .filter(|client_exe| client_exe.exists())
+            .collect();
+
+        // Then the configured globs and roots
+        found.extend(glob_under(Path::new(r"C:\"), &options.client_globs));
+        for root in &options.roots {
+            if progress.is_cancelled() {
+                break;
             }
+
+            progress.checking(root);
+            found.extend(find_acclient(root, CLIENT_SEARCH_DEPTH));
+        }
+
+        for client_exe in found {
+            if configs
+                .iter()
+                .any(|c: &ClientConfigType| c.client_path() == client_exe)
+            {
+                continue;
+            }
+
+            let name = format!(
+                "Asheron's Call - {}",
+                client_exe.parent().unwrap_or(&client_exe).display()
+            );
+            let client = ClientConfigType::Windows(WindowsClientConfig {
+                id: new_id(),
+                name,
+                fingerprint: ClientFingerprint::read(&client_exe).ok(),
+                client_path: client_exe,
+                dlls: vec![],
+                selected_dll: None,
+            });
+            progress.found(&client);
+            configs.push(client);
         }
 
         Ok(configs)
@@ -598,9 +1649,11 @@ pub fn get_available_scanners() -> Vec<Box<dyn ClientScanner>> {
 
     #[cfg(any(target_os = "macos", target_os = "linux"))]
     {
-        // Try to find wine
-        if let Ok(wine_path) = find_wine_executable() {
-            scanners.push(Box::new(WineScanner::new(wine_path)));
+        match find_wine_executable() {
+            Ok(wine_path) => scanners.push(Box::new(WineScanner::new(wine_path))),
+            // Prefixes under the scan roots are still worth finding. Their
+            // clients run with whichever wine is on PATH.
+            Err(_) => scanners.push(Box::new(WineScanner::roots_only(PathBuf::from("wine")))),
         }
     }
 
@@ -610,41 +1663,113 @@ pub fn get_available_scanners() -> Vec<Box<dyn ClientScanner>> {
         if whisky_scanner.is_available() {
             scanners.push(Box::new(whisky_scanner));
         }
+
+        if let Some(crossover_scanner) = CrossOverScanner::new()
+            && crossover_scanner.is_available()
+        {
+            scanners.push(Box::new(crossover_scanner));
+        }
     }
 
     #[cfg(target_os = "linux")]
     {
-        let lutris_scanner = LutrisFlatpakScanner;
-        if lutris_scanner.is_available() {
+        if let Some(lutris_scanner) = LutrisScanner::new()
+            && lutris_scanner.is_available()
+        {
             scanners.push(Box::new(lutris_scanner));
         }
+
+        let lutris_flatpak_scanner = LutrisFlatpakScanner;
+        if lutris_flatpak_scanner.is_available() {
+            scanners.push(Box::new(lutris_flatpak_scanner));
+        }
+
+        for bottles_scanner in [BottlesScanner::native(), BottlesScanner::flatpak()] {
+            if bottles_scanner.is_available() {
+                scanners.push(Box::new(bottles_scanner));
+            }
+        }
+
+        if let Some(proton_scanner) = ProtonScanner::new()
+            && proton_scanner.is_available()
+        {
+            scanners.push(Box::new(proton_scanner));
+        }
     }
 
     scanners
 }
 
 /// Scan using all available scanners and aggregate results
-pub fn scan_all() -> Result<Vec<ClientConfigType>> {
+pub fn scan_all(options: &ScanOptions) -> Result<Vec<ClientConfigType>> {
+    scan_all_with_progress(options, &ScanProgress::default())
+}
+
+/// Run all available scanners at once, reporting to `progress`. Results are
+/// in scanner order. Fails if the scan is cancelled.
+pub fn scan_all_with_progress(
+    options: &ScanOptions,
+    progress: &ScanProgress,
+) -> Result<Vec<ClientConfigType>> {
+    options.check_globs()?;
     let scanners = get_available_scanners();
-    let mut all_configs = vec![];
 
-    for scanner in scanners {
-        match scanner.scan() {
-            Ok(mut configs) => {
-                all_configs.append(&mut configs);
-            }
-            Err(_e) => {
-                // Silently skip failed scanners
-            }
-        }
+    let results: Vec<Vec<ClientConfigType>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = scanners
+            .iter()
+            .map(|scanner| {
+                let progress = progress.for_scanner(scanner.name());
+                scope.spawn(move || {
+                    progress.emit(ScanEvent::ScannerStarted {
+                        scanner: progress.scanner.clone(),
+                    });
+
+                    match scanner.scan(options, &progress) {
+                        Ok(configs) => {
+                            progress.emit(ScanEvent::ScannerFinished {
+                                scanner: progress.scanner.clone(),
+                                found: configs.len(),
+                            });
+                            configs
+                        }
+                        Err(e) => {
+                            // Failed scanners don't fail the whole scan
+                            progress.emit(ScanEvent::ScannerFailed {
+                                scanner: progress.scanner.clone(),
+                                error: e.to_string(),
+                            });
+                            vec![]
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .zip(&scanners)
+            .map(|(handle, scanner)| {
+                handle.join().unwrap_or_else(|_| {
+                    progress.emit(ScanEvent::ScannerFailed {
+                        scanner: scanner.name().to_string(),
+                        error: "scanner panicked".to_string(),
+                    });
+                    vec![]
+                })
+            })
+            .collect()
+    });
+
+    if progress.is_cancelled() {
+        anyhow::bail!("Scan was cancelled");
     }
 
-    Ok(all_configs)
+    Ok(results.into_iter().flatten().collect())
 }
 
 /// Discover DLL installations on Windows
 #[cfg(target_os = "windows")]
-pub fn discover_dlls_on_windows() -> Vec<InjectConfig> {
+pub fn discover_dlls_on_windows(options: &ScanOptions) -> Vec<InjectConfig> {
     let mut inject_configs = vec![];
 
     // Search for Alembic.dll in AC installation directories
@@ -660,6 +1785,7 @@ pub fn discover_dlls_on_windows() -> Vec<InjectConfig> {
         let alembic_path = PathBuf::from(search_path).join("Alembic.dll");
         if alembic_path.exists() {
             inject_configs.push(InjectConfig {
+                id: new_id(),
                 dll_path: alembic_path,
                 dll_type: DllType::Alembic,
                 startup_function: None,
@@ -681,6 +1807,7 @@ pub fn discover_dlls_on_windows() -> Vec<InjectConfig> {
         let decal_path = PathBuf::from(search_path).join("Inject.dll");
         if decal_path.exists() {
             inject_configs.push(InjectConfig {
+                id: new_id(),
                 dll_path: decal_path,
                 dll_type: DllType::Decal,
                 startup_function: Some("DecalStartup".to_string()),
@@ -688,23 +1815,33 @@ pub fn discover_dlls_on_windows() -> Vec<InjectConfig> {
         }
     }
 
+    for dll_path in glob_under(Path::new(r"C:\"), &options.dll_globs) {
+        if !inject_configs.iter().any(|dll| dll.dll_path == dll_path) {
+            inject_configs.push(inject_config_for(dll_path));
+        }
+    }
+
     inject_configs
 }
 
 /// Stub for non-Windows platforms
 #[cfg(not(target_os = "windows"))]
-pub fn discover_dlls_on_windows() -> Vec<InjectConfig> {
+pub fn discover_dlls_on_windows(_options: &ScanOptions) -> Vec<InjectConfig> {
     vec![]
 }
 
 /// Scan specifically for Decal DLL installations in the given client configs.
-pub fn scan_for_decal_dlls(clients: &[ClientConfigType]) -> Result<Vec<InjectConfig>> {
+pub fn scan_for_decal_dlls(
+    clients: &[ClientConfigType],
+    options: &ScanOptions,
+) -> Result<Vec<InjectConfig>> {
+    options.check_globs()?;
     let mut all_dlls = vec![];
 
     #[cfg(target_os = "windows")]
     {
         let _ = clients; // suppress unused warning on windows
-        all_dlls.append(&mut discover_dlls_on_windows());
+        all_dlls.append(&mut discover_dlls_on_windows(options));
     }
 
     #[cfg(not(target_os = "windows"))]
@@ -715,7 +1852,7 @@ pub fn scan_for_decal_dlls(clients: &[ClientConfigType]) -> Result<Vec<InjectCon
             {
                 let prefix = PathBuf::from(prefix_str);
                 if prefix.exists() {
-                    all_dlls.extend(discover_dlls_in_wine_prefix(&prefix));
+                    all_dlls.extend(discover_dlls_in_wine_prefix(&prefix, options));
                 }
             }
         }
@@ -777,24 +1914,19 @@ fn find_wine_executable() -> Result<PathBuf> {
 
 #[cfg(test)]
 mod tests {
-    use super::LutrisFlatpakScanner;
-    use std::{
-        fs,
-        path::PathBuf,
-        time::{SystemTime, UNIX_EPOCH},
+    use super::{
+        BottlesScanner, ClientScanner, LutrisFlatpakScanner, LutrisScanner, ProtonScanner,
+        ScanCancel, ScanEvent, ScanOptions, ScanProgress, WineScanner,
+        discover_dlls_in_wine_prefix, find_acclient_in_prefix, find_wine_prefixes,
     };
+    use crate::inject_config::DllType;
+    use crate::test_util::TempDir;
+    use std::{fs, path::PathBuf, sync::mpsc};
 
     #[test]
     fn parse_lutris_game_config_with_yaml() {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("system clock should be after unix epoch")
-            .as_nanos();
-        let path = std::env::temp_dir().join(format!(
-            "alembic-lutris-config-{}-{}.yml",
-            std::process::id(),
-            timestamp
-        ));
+        let dir = TempDir::new("lutris-config");
+        let path = dir.join("game.yml");
 
         fs::write(
             &path,
@@ -816,7 +1948,282 @@ script:
         assert_eq!(prefix, PathBuf::from("/tmp/prefix"));
         assert_eq!(wine_path, PathBuf::from("/usr/bin/wine"));
         assert_eq!(name, "Test Game");
+    }
+
+    #[test]
+    fn bottles_launch_command_uses_bottle_runner_and_environment() {
+        let data_dir = TempDir::new("bottles");
+        let bottle_dir = data_dir.join("bottles/AC");
+        let runner_bin = data_dir.join("runners/soda-9.0-1/bin");
+        fs::create_dir_all(&bottle_dir).expect("bottle dir should be created");
+        fs::create_dir_all(&runner_bin).expect("runner dir should be created");
+        fs::write(runner_bin.join("wine"), "").expect("runner should be written");
+        fs::write(
+            bottle_dir.join("bottle.yml"),
+            r#"
+Name: Asheron's Call
+Path: AC
+Custom_Path: false
+Runner: soda-9.0-1
+Environment_Variables:
+  DXVK_HUD: fps
+"#,
+        )
+        .expect("bottle config should be written");
+
+        let config = BottlesScanner::parse_bottle_config(&bottle_dir.join("bottle.yml"))
+            .expect("yaml should parse");
+        let prefix = BottlesScanner::bottle_prefix(&bottle_dir, &config);
+        assert_eq!(prefix, bottle_dir);
+
+        let native = BottlesScanner::native().launch_command(&data_dir, &config, &prefix);
+        assert_eq!(native.program, runner_bin.join("wine"));
+        assert_eq!(native.env["DXVK_HUD"], "fps");
+        assert_eq!(native.env["WINEPREFIX"], bottle_dir.display().to_string());
+
+        let flatpak = BottlesScanner::flatpak().launch_command(&data_dir, &config, &prefix);
+        assert_eq!(flatpak.program, PathBuf::from("flatpak"));
+        assert_eq!(
+            flatpak.args,
+            vec![
+                "run".to_string(),
+                format!("--command={}", runner_bin.join("wine").display()),
+                "com.usebottles.bottles".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn native_lutris_uses_game_database_and_runner() {
+        let root = TempDir::new("lutris-native");
+        let scanner = LutrisScanner {
+            data_dir: root.join("data"),
+            config_dir: root.join("config"),
+        };
+        let runner_bin = scanner
+            .data_dir
+            .join("runners/wine/wine-ge-8-26-x86_64/bin");
+        fs::create_dir_all(&runner_bin).expect("runner dir should be created");
+        fs::create_dir_all(scanner.data_dir.join("games")).expect("games dir should be created");
+        fs::create_dir_all(scanner.config_dir.join("runners"))
+            .expect("config dir should be created");
+        fs::write(runner_bin.join("wine"), "").expect("runner should be written");
+
+        fs::write(
+            scanner.config_dir.join("runners/wine.yml"),
+            "wine:\n  version: wine-ge-8-26-x86_64\n  fsync: false\n",
+        )
+        .expect("runner config should be written");
+        fs::write(
+            scanner.data_dir.join("games/asherons-call-1.yml"),
+            r#"
+game:
+  prefix: /tmp/ac-prefix
+  arch: win64
+system:
+  env:
+    DXVK_ASYNC: 1
+"#,
+        )
+        .expect("game config should be written");
+
+        #[cfg(target_os = "linux")]
+        {
+            let db = rusqlite::Connection::open(scanner.data_dir.join("pga.db"))
+                .expect("database should open");
+            db.execute_batch(
+                "CREATE TABLE games (name TEXT, runner TEXT, installed INTEGER, configpath TEXT);
+                 INSERT INTO games VALUES ('Asheron''s Call', 'wine', 1, 'asherons-call-1');
+                 INSERT INTO games VALUES ('Removed', 'wine', 0, 'removed-2');",
+            )
+            .expect("database should be written");
+
+            let games = scanner.games();
+            assert_eq!(games.len(), 1);
+            assert_eq!(games[0].name, "Asheron's Call");
+        }
+
+        let (prefix, launch_command) = scanner
+            .launch_command(&scanner.data_dir.join("games/asherons-call-1.yml"))
+            .expect("configs should parse");
+
+        assert_eq!(prefix, PathBuf::from("/tmp/ac-prefix"));
+        assert_eq!(launch_command.program, runner_bin.join("wine"));
+        assert_eq!(launch_command.env["WINEPREFIX"], "/tmp/ac-prefix");
+        assert_eq!(launch_command.env["WINEARCH"], "win64");
+        assert_eq!(launch_command.env["WINEESYNC"], "1");
+        assert!(!launch_command.env.contains_key("WINEFSYNC"));
+        assert_eq!(launch_command.env["DXVK_ASYNC"], "1");
+    }
+
+    #[test]
+    fn proton_prefix_uses_recorded_proton_version() {
+        let steam = TempDir::new("proton");
+        let compat_dir = steam.join("steamapps/compatdata/2881234567");
+        let ac_dir = compat_dir.join("pfx/drive_c/Turbine/Asheron's Call");
+        fs::create_dir_all(&ac_dir).expect("prefix should be created");
+        fs::write(ac_dir.join("acclient.exe"), "").expect("client should be written");
+
+        for proton in ["Proton 8.0", "Proton 9.0"] {
+            let bin = steam
+                .join("steamapps/common")
+                .join(proton)
+                .join("files/bin");
+            fs::create_dir_all(&bin).expect("proton should be created");
+            fs::write(bin.join("wine"), "").expect("wine should be written");
+        }
+        let proton_8 = steam.join("steamapps/common/Proton 8.0");
+        fs::write(
+            compat_dir.join("config_info"),
+            format!("8.0-104\n{}/files/share/fonts/\n", proton_8.display()),
+        )
+        .expect("config_info should be written");
+
+        let scanner = ProtonScanner {
+            steam_roots: vec![steam.to_path_buf()],
+        };
+        let configs =
+            super::ClientScanner::scan(&scanner, &Default::default(), &Default::default())
+                .expect("scan should succeed");
+
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].name(), "Proton: 2881234567");
+        let launch_command = configs[0].launch_command().expect("wine client");
+        assert_eq!(launch_command.program, proton_8.join("files/bin/wine"));
+        assert_eq!(
+            launch_command.env["WINEPREFIX"],
+            compat_dir.join("pfx").display().to_string()
+        );
+    }
+
+    #[test]
+    fn scan_roots_find_prefixes_and_clients_in_custom_folders() {
+        let root = TempDir::new("scan-roots");
+        let prefix = root.join("games/wine/ac");
+        let ac_dir = prefix.join("drive_c/Games/Asheron");
+        fs::create_dir_all(&ac_dir).expect("prefix should be created");
+        fs::create_dir_all(root.join("games/not-a-prefix")).expect("dir should be created");
+        fs::write(ac_dir.join("acclient.exe"), "").expect("client should be written");
+        fs::write(ac_dir.join("Helper.dll"), "").expect("dll should be written");
+
+        // Decal is found where the registry says it is, not in Program Files
+        let decal_dir = prefix.join("drive_c/Games/Decal");
+        fs::create_dir_all(&decal_dir).expect("decal dir should be created");
+        fs::write(decal_dir.join("Inject.dll"), "").expect("decal should be written");
+        fs::write(
+            prefix.join("system.reg"),
+            r#"WINE REGISTRY Version 2
+
+[Software\\Wow6432Node\\Decal\\Agent] 1700000000
+"AgentPath"="C:\\Games\\Decal\\"
+"#,
+        )
+        .expect("registry should be written");
+
+        assert_eq!(find_wine_prefixes(&root, 3), vec![prefix.clone()]);
+        assert!(find_wine_prefixes(&root, 2).is_empty());
+
+        let options = ScanOptions {
+            roots: vec![root.to_path_buf()],
+            client_globs: vec![],
+            dll_globs: vec!["Games/*/*.dll".to_string()],
+        };
+        assert_eq!(
+            find_acclient_in_prefix(&prefix, &options),
+            Some(ac_dir.join("acclient.exe"))
+        );
+
+        let dlls = discover_dlls_in_wine_prefix(&prefix, &options);
+        assert_eq!(dlls.len(), 2);
+        assert_eq!(dlls[0].dll_type, DllType::Decal);
+        assert_eq!(
+            dlls[0].dll_path,
+            PathBuf::from(r"C:\Games\Decal\Inject.dll")
+        );
+        assert_eq!(dlls[1].dll_type, DllType::Alembic);
+        assert_eq!(
+            dlls[1].dll_path,
+            PathBuf::from(r"C:\Games\Asheron\Helper.dll")
+        );
+
+        // Without a registry entry, Decal's default location is still checked
+        fs::remove_file(prefix.join("system.reg")).expect("registry should be removed");
+        fs::remove_dir_all(&decal_dir).expect("decal dir should be removed");
+        let default_decal_dir = prefix.join("drive_c/Program Files/Decal 3.0");
+        fs::create_dir_all(&default_decal_dir).expect("decal dir should be created");
+        fs::write(default_decal_dir.join("Inject.dll"), "").expect("decal should be written");
+
+        let dlls = discover_dlls_in_wine_prefix(&prefix, &options);
+        assert_eq!(dlls.len(), 2);
+        assert_eq!(
+            dlls[0].dll_path,
+            PathBuf::from(r"C:\Program Files\Decal 3.0\Inject.dll")
+        );
+
+        // Without a wine install, only the roots are scanned and the client
+        // runs with whichever wine is on PATH
+        let scanner = WineScanner::roots_only(PathBuf::from("wine"));
+        let found = scanner
+            .scan(&options, &ScanProgress::default())
+            .expect("scan should succeed");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].wine_prefix(), Some(&*prefix.to_string_lossy()));
+        assert_eq!(
+            found[0].launch_command().map(|c| c.program.clone()),
+            Some(PathBuf::from("wine"))
+        );
+    }
+
+    #[test]
+    fn scanning_reports_progress_and_stops_when_cancelled() {
+        let root = TempDir::new("scan-progress");
+        let prefixes = [root.join("one"), root.join("two")];
+        for prefix in &prefixes {
+            fs::create_dir_all(prefix.join("drive_c/AC")).expect("prefix should be created");
+            fs::write(prefix.join("drive_c/AC/acclient.exe"), "")
+                .expect("client should be written");
+        }
+
+        let options = ScanOptions {
+            roots: vec![root.to_path_buf()],
+            ..ScanOptions::default()
+        };
+        let scanner = WineScanner::new(PathBuf::from("/usr/bin/wine"));
+        let cancel = ScanCancel::default();
+
+        // Only look at what was found under the root, not in a real ~/.wine
+        let (sender, receiver) = mpsc::channel();
+        let progress = ScanProgress::new(sender, cancel.clone()).for_scanner("Wine");
+        let found = scanner
+            .scan(&options, &progress)
+            .expect("scan should succeed")
+            .into_iter()
+            .filter(|c| {
+                c.wine_prefix()
+                    .is_some_and(|p| p.starts_with(&*root.to_string_lossy()))
+            })
+            .count();
+        assert_eq!(found, 2);
+
+        drop(progress);
+        let events: Vec<_> = receiver.iter().collect();
+        for prefix in &prefixes {
+            assert!(events.contains(&ScanEvent::Checking {
+                scanner: "Wine".to_string(),
+                path: prefix.clone(),
+            }));
+            assert!(events.contains(&ScanEvent::Found {
+                scanner: "Wine".to_string(),
+                name: format!("Wine: {}", prefix.display()),
+                client_path: PathBuf::from(r"C:\AC\acclient.exe"),
+            }));
+        }
 
-        fs::remove_file(path).ok();
+        cancel.cancel();
+        let progress = ScanProgress::new(mpsc::channel().0, cancel);
+        let found = scanner
+            .scan(&options, &progress)
+            .expect("cancelled scans still succeed");
+        assert!(found.is_empty());
     }
 }
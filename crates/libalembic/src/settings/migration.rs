@@ -0,0 +1,389 @@
+//! Versioned migrations for config.json.
+//!
+//! Settings files are upgraded as raw JSON, one version at a time, before they
+//! are deserialized into `AlembicSettings`. Each migration only needs to know
+//! about the layout immediately before and after it.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::bail;
+use serde_json::{Map, Value, json};
+
+use super::{SETTINGS_VERSION, new_id, persist};
+
+/// Version assumed for settings files that don't have a `version` field
+const UNVERSIONED: u32 = 1;
+
+/// Version that moved account passwords out of config.json and into
+/// credential stores
+pub const CREDENTIAL_STORE_VERSION: u32 = 4;
+
+type Migration = fn(&mut Map<String, Value>) -> anyhow::Result<()>;
+
+/// Migrations in order. The migration at index `i` upgrades a file from
+/// version `i + 1` to version `i + 2`.
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4];
+
+/// Read the version of a raw settings document
+pub fn settings_version(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(UNVERSIONED)
+}
+
+/// Upgrade a raw settings document to `SETTINGS_VERSION`.
+///
+/// Returns the version the document was at before migrating, or `None` if it
+/// was already current.
+pub fn migrate(value: &mut Value) -> anyhow::Result<Option<u32>> {
+    let from_version = settings_version(value);
+
+    if from_version < UNVERSIONED {
+        bail!(
+            "Settings file is version {}, but the oldest version Alembic knows about is {}. The file may have been edited by hand or be corrupt.",
+            from_version,
+            UNVERSIONED
+        );
+    }
+
+    if from_version > SETTINGS_VERSION {
+        bail!(
+            "Settings file is version {} but this version of Alembic only supports up to version {}. Please upgrade Alembic.",
+            from_version,
+            SETTINGS_VERSION
+        );
+    }
+
+    if from_version == SETTINGS_VERSION {
+        return Ok(None);
+    }
+
+    let Some(object) = value.as_object_mut() else {
+        bail!("Settings file is not a JSON object");
+    };
+
+    for version in from_version..SETTINGS_VERSION {
+        let step = MIGRATIONS[(version - UNVERSIONED) as usize];
+        step(object)?;
+        object.insert("version".to_string(), Value::from(version + 1));
+    }
+
+    Ok(Some(from_version))
+}
+
+/// Copy the settings file aside before it gets rewritten by a migration.
+///
+/// Backups are named after the version they were written by, e.g.,
+/// `config.json.v1-1700000000.bak`, so repeated migrations never overwrite an
+/// earlier backup.
+pub fn backup_settings_file(path: &Path, from_version: u32) -> anyhow::Result<PathBuf> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let mut file_name = path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(format!(".v{}-{}.bak", from_version, timestamp));
+
+    let backup_path = path.with_file_name(file_name);
+    persist::write_private(&backup_path, &fs::read_to_string(path)?)?;
+
+    Ok(backup_path)
+}
+
+/// Blank out the passwords of the accounts at `indexes` in a backup of a
+/// settings file from before `CREDENTIAL_STORE_VERSION`, once they're safely
+/// in a store. Migrations keep accounts in order, so the indexes are the same
+/// in the backup.
+pub fn redact_backup_passwords(backup_path: &Path, indexes: &[usize]) -> anyhow::Result<()> {
+    let mut value: Value = serde_json::from_str(&fs::read_to_string(backup_path)?)?;
+
+    if let Some(Value::Array(accounts)) = value.get_mut("accounts") {
+        for &index in indexes {
+            if let Some(account) = accounts.get_mut(index).and_then(Value::as_object_mut)
+                && account.contains_key("password")
+            {
+                account.insert("password".to_string(), Value::from(""));
+            }
+        }
+    }
+
+    persist::write_private(backup_path, &serde_json::to_string_pretty(&value)?)
+}
+
+/// Version 2 moved from a single client (with top-level DLLs) to a list of
+/// clients that each own their DLLs.
+fn migrate_v1_to_v2(settings: &mut Map<String, Value>) -> anyhow::Result<()> {
+    let legacy_dlls = settings.remove("dlls");
+    let legacy_selected_dll = settings.remove("selected_dll");
+
+    let Some(mut client) = settings.remove("client") else {
+        return Ok(());
+    };
+
+    if client.is_null() {
+        return Ok(());
+    }
+
+    if let Some(client_object) = client.as_object_mut() {
+        if let Some(dlls) = legacy_dlls
+            && !client_object.contains_key("dlls")
+        {
+            client_object.insert("dlls".to_string(), dlls);
+            client_object.insert(
+                "selected_dll".to_string(),
+                legacy_selected_dll.unwrap_or(Value::Null),
+            );
+        }
+    } else {
+        bail!("Expected 'client' to be an object in version 1 settings");
+    }
+
+    let clients = settings
+        .entry("clients")
+        .or_insert_with(|| Value::Array(vec![]));
+
+    let Some(clients) = clients.as_array_mut() else {
+        bail!("Expected 'clients' to be an array in version 1 settings");
+    };
+
+    clients.insert(0, client);
+
+    // The legacy client is now first in the list, so shift any existing selection
+    let selected_client = match settings.get("selected_client").and_then(Value::as_u64) {
+        Some(selected) => selected + 1,
+        None => 0,
+    };
+    settings.insert("selected_client".to_string(), Value::from(selected_client));
+
+    Ok(())
+}
+
+/// Version 3 gave servers, accounts, clients and DLLs stable IDs and replaced
+/// every index-based reference to them with an ID.
+fn migrate_v2_to_v3(settings: &mut Map<String, Value>) -> anyhow::Result<()> {
+    let server_ids = assign_ids(settings.get_mut("servers"));
+    let account_ids = assign_ids(settings.get_mut("accounts"));
+    let client_ids = assign_ids(settings.get_mut("clients"));
+
+    if let Some(Value::Array(accounts)) = settings.get_mut("accounts") {
+        for account in accounts.iter_mut().filter_map(Value::as_object_mut) {
+            // Accounts on a server that no longer exists keep an empty ID
+            // rather than being dropped
+            let server_id = account
+                .remove("server_index")
+                .as_ref()
+                .and_then(|index| id_at(&server_ids, index))
+                .unwrap_or_default();
+            account.insert("server_id".to_string(), Value::from(server_id));
+        }
+    }
+
+    if let Some(Value::Array(clients)) = settings.get_mut("clients") {
+        for client in clients.iter_mut().filter_map(Value::as_object_mut) {
+            let dll_ids = assign_ids(client.get_mut("dlls"));
+            convert_selection(client, "selected_dll", &dll_ids);
+        }
+    }
+
+    convert_selection(settings, "selected_server", &server_ids);
+    convert_selection(settings, "selected_account", &account_ids);
+    convert_selection(settings, "selected_client", &client_ids);
+
+    Ok(())
+}
+
+/// Version 4 moved account passwords behind a credential reference. Existing
+/// passwords become plaintext credentials here, and are then moved to the
+/// default store once the settings are loaded. See `read_settings_file`.
+fn migrate_v3_to_v4(settings: &mut Map<String, Value>) -> anyhow::Result<()> {
+    if let Some(Value::Array(accounts)) = settings.get_mut("accounts") {
+        for account in accounts.iter_mut().filter_map(Value::as_object_mut) {
+            let password = account
+                .remove("password")
+                .unwrap_or_else(|| Value::from(""));
+            account.insert(
+                "credential".to_string(),
+                json!({ "store": "plaintext", "password": password }),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Give every object in an array a fresh `id`, returning the IDs in order
+fn assign_ids(items: Option<&mut Value>) -> Vec<String> {
+    let Some(Value::Array(items)) = items else {
+        return vec![];
+    };
+
+    items
+        .iter_mut()
+        .filter_map(Value::as_object_mut)
+        .map(|item| {
+            let id = new_id();
+            item.insert("id".to_string(), Value::from(id.clone()));
+            id
+        })
+        .collect()
+}
+
+fn id_at(ids: &[String], index: &Value) -> Option<String> {
+    index
+        .as_u64()
+        .and_then(|index| ids.get(index as usize))
+        .cloned()
+}
+
+/// Replace an index-based selection with the ID it pointed at, clearing it if
+/// the index was out of range
+fn convert_selection(object: &mut Map<String, Value>, key: &str, ids: &[String]) {
+    let id = object.get(key).and_then(|index| id_at(ids, index));
+    object.insert(key.to_string(), id.map(Value::from).unwrap_or(Value::Null));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn migrate_moves_single_client_into_clients() {
+        let mut value = json!({
+            "version": 1,
+            "is_configured": true,
+            "client": {
+                "type": "wine",
+                "name": "Wine: ~/.wine",
+                "client_path": "C:\\Turbine\\Asheron's Call\\acclient.exe",
+                "launch_command": { "program": "/usr/bin/wine" }
+            },
+            "dlls": [
+                { "dll_path": "C:\\Alembic.dll", "dll_type": "Alembic", "startup_function": null }
+            ],
+            "selected_dll": 0,
+            "selected_server": null,
+            "selected_account": null,
+            "accounts": [],
+            "servers": []
+        });
+
+        let from_version = migrate(&mut value).expect("migration should succeed");
+
+        assert_eq!(from_version, Some(1));
+        assert_eq!(value["version"], json!(SETTINGS_VERSION));
+        assert!(value.get("client").is_none());
+        assert!(value.get("dlls").is_none());
+        assert_eq!(value["clients"][0]["name"], json!("Wine: ~/.wine"));
+        assert_eq!(value["clients"][0]["dlls"][0]["dll_type"], json!("Alembic"));
+        assert_eq!(
+            value["clients"][0]["selected_dll"],
+            value["clients"][0]["dlls"][0]["id"]
+        );
+        assert_eq!(value["selected_client"], value["clients"][0]["id"]);
+    }
+
+    #[test]
+    fn migrate_replaces_indexes_with_ids() {
+        let mut value = json!({
+            "version": 2,
+            "is_configured": true,
+            "clients": [
+                {
+                    "type": "windows",
+                    "name": "Windows",
+                    "client_path": "C:\\Turbine\\Asheron's Call\\acclient.exe",
+                    "dlls": [
+                        { "dll_path": "C:\\Alembic.dll", "dll_type": "Alembic", "startup_function": null },
+                        { "dll_path": "C:\\Decal\\Inject.dll", "dll_type": "Decal", "startup_function": "DecalStartup" }
+                    ],
+                    "selected_dll": 1
+                }
+            ],
+            "selected_client": 0,
+            "selected_server": 1,
+            "selected_account": 5,
+            "servers": [
+                { "name": "Coldeve", "hostname": "coldeve.ac", "port": "9050" },
+                { "name": "Levistras", "hostname": "levistras.ac", "port": "9000" }
+            ],
+            "accounts": [
+                { "server_index": 1, "username": "alice", "password": "secret" },
+                { "server_index": 7, "username": "bob", "password": "secret" }
+            ]
+        });
+
+        migrate(&mut value).expect("migration should succeed");
+
+        let client = &value["clients"][0];
+        assert_eq!(client["selected_dll"], client["dlls"][1]["id"]);
+        assert_eq!(value["selected_client"], client["id"]);
+        assert_eq!(value["selected_server"], value["servers"][1]["id"]);
+        assert_eq!(value["selected_account"], Value::Null);
+        assert_eq!(value["accounts"][0]["server_id"], value["servers"][1]["id"]);
+        assert_eq!(value["accounts"][1]["server_id"], json!(""));
+        assert!(value["accounts"][0].get("server_index").is_none());
+        assert_ne!(value["servers"][0]["id"], value["servers"][1]["id"]);
+        assert_eq!(
+            value["accounts"][0]["credential"],
+            json!({ "store": "plaintext", "password": "secret" })
+        );
+        assert!(value["accounts"][0].get("password").is_none());
+
+        serde_json::from_value::<crate::settings::AlembicSettings>(value)
+            .expect("migrated settings should deserialize");
+    }
+
+    #[test]
+    fn migrate_leaves_current_version_alone() {
+        let mut value = json!({ "version": SETTINGS_VERSION, "clients": [] });
+        let before = value.clone();
+
+        assert_eq!(migrate(&mut value).unwrap(), None);
+        assert_eq!(value, before);
+    }
+
+    #[test]
+    fn migrate_rejects_newer_versions() {
+        let mut value = json!({ "version": SETTINGS_VERSION + 1 });
+
+        assert!(migrate(&mut value).is_err());
+    }
+
+    #[test]
+    fn redact_backup_passwords_only_blanks_moved_accounts() {
+        let dir = TempDir::new("redact-backup");
+        let path = dir.join("config.json.v3-0.bak");
+        let backup = json!({
+            "version": 3,
+            "accounts": [
+                { "username": "alice", "password": "secret" },
+                { "username": "bob", "password": "hunter2" }
+            ]
+        });
+        fs::write(&path, backup.to_string()).unwrap();
+
+        redact_backup_passwords(&path, &[0]).unwrap();
+
+        let redacted: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(redacted["accounts"][0]["password"], json!(""));
+        assert_eq!(redacted["accounts"][1]["password"], json!("hunter2"));
+    }
+
+    #[test]
+    fn migrate_rejects_versions_before_the_first() {
+        let mut value = json!({ "version": 0 });
+
+        assert!(migrate(&mut value).is_err());
+    }
+}
@@ -0,0 +1,896 @@
+mod migration;
+mod overrides;
+pub mod persist;
+mod profile;
+mod reconcile;
+mod transfer;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use anyhow::{Context, bail};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::client_config::{ClientConfig, LaunchCommand, WindowsClientConfig, WineClientConfig};
+use crate::credentials::{self, CredentialRef, CredentialStoreKind};
+use crate::dll_inspect::validate_dll;
+use crate::fingerprint::{ClientFingerprint, validate_client_build};
+use crate::inject_config::InjectConfig;
+use crate::scanner::ScanOptions;
+use crate::validation::ValidationResult;
+
+pub use overrides::set_settings_file_path;
+pub use profile::{LaunchProfile, LaunchTarget};
+pub use reconcile::{ScanChange, ScanEntry, ScanMergeSummary, ScanStatus};
+pub use transfer::{
+    ExportOptions, ExportedAccount, ImportSummary, SettingsExport, export_settings,
+    save_imported_passwords,
+};
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ClientConfigType {
+    Windows(WindowsClientConfig),
+    Wine(WineClientConfig),
+}
+
+impl ClientConfigType {
+    pub fn id(&self) -> &str {
+        match self {
+            ClientConfigType::Windows(c) => &c.id,
+            ClientConfigType::Wine(c) => &c.id,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ClientConfigType::Windows(c) => &c.name,
+            ClientConfigType::Wine(c) => &c.name,
+        }
+    }
+
+    pub fn name_mut(&mut self) -> &mut String {
+        match self {
+            ClientConfigType::Windows(c) => &mut c.name,
+            ClientConfigType::Wine(c) => &mut c.name,
+        }
+    }
+
+    pub fn client_path(&self) -> &std::path::Path {
+        match self {
+            ClientConfigType::Windows(c) => &c.client_path,
+            ClientConfigType::Wine(c) => &c.client_path,
+        }
+    }
+
+    pub fn client_path_mut(&mut self) -> &mut std::path::PathBuf {
+        match self {
+            ClientConfigType::Windows(c) => &mut c.client_path,
+            ClientConfigType::Wine(c) => &mut c.client_path,
+        }
+    }
+
+    pub fn launch_command(&self) -> Option<&LaunchCommand> {
+        match self {
+            ClientConfigType::Windows(_) => None,
+            ClientConfigType::Wine(c) => Some(&c.launch_command),
+        }
+    }
+
+    pub fn launch_command_mut(&mut self) -> Option<&mut LaunchCommand> {
+        match self {
+            ClientConfigType::Windows(_) => None,
+            ClientConfigType::Wine(c) => Some(&mut c.launch_command),
+        }
+    }
+
+    pub fn install_path(&self) -> std::path::PathBuf {
+        crate::client_config::windows_path_parent(self.client_path())
+    }
+
+    pub fn is_wine(&self) -> bool {
+        matches!(self, ClientConfigType::Wine(_))
+    }
+
+    pub fn is_windows(&self) -> bool {
+        matches!(self, ClientConfigType::Windows(_))
+    }
+
+    /// The WINEPREFIX this client runs in, if it sets one
+    pub fn wine_prefix(&self) -> Option<&str> {
+        self.launch_command()
+            .and_then(|cmd| cmd.env.get("WINEPREFIX"))
+            .map(String::as_str)
+    }
+
+    /// Whether both configs are the same installation: the same kind of
+    /// client at the same path and, for Wine, in the same prefix
+    pub fn is_same_install(&self, other: &ClientConfigType) -> bool {
+        self.is_wine() == other.is_wine()
+            && self.client_path() == other.client_path()
+            && self.wine_prefix() == other.wine_prefix()
+    }
+
+    pub fn dlls(&self) -> &Vec<InjectConfig> {
+        match self {
+            ClientConfigType::Windows(c) => &c.dlls,
+            ClientConfigType::Wine(c) => &c.dlls,
+        }
+    }
+
+    pub fn dlls_mut(&mut self) -> &mut Vec<InjectConfig> {
+        match self {
+            ClientConfigType::Windows(c) => &mut c.dlls,
+            ClientConfigType::Wine(c) => &mut c.dlls,
+        }
+    }
+
+    /// ID of the selected DLL, if any
+    pub fn selected_dll_id(&self) -> Option<&str> {
+        match self {
+            ClientConfigType::Windows(c) => c.selected_dll.as_deref(),
+            ClientConfigType::Wine(c) => c.selected_dll.as_deref(),
+        }
+    }
+
+    pub fn selected_dll_id_mut(&mut self) -> &mut Option<String> {
+        match self {
+            ClientConfigType::Windows(c) => &mut c.selected_dll,
+            ClientConfigType::Wine(c) => &mut c.selected_dll,
+        }
+    }
+
+    /// Position of the selected DLL in `dlls()`, if any
+    pub fn selected_dll(&self) -> Option<usize> {
+        self.dll_index(self.selected_dll_id()?)
+    }
+
+    /// Position of the DLL with this exact ID in `dlls()`
+    pub fn dll_index(&self, id: &str) -> Option<usize> {
+        self.dlls().iter().position(|dll| dll.id == id)
+    }
+
+    /// Find a DLL by index, ID or path. See `resolve_index`.
+    pub fn find_dll(&self, key: &str) -> Option<usize> {
+        resolve_index(
+            self.dlls(),
+            key,
+            |dll| &dll.id,
+            |dll| dll.dll_path.to_str().unwrap_or_default(),
+        )
+    }
+
+    /// Where a client or DLL path of this client is on the host
+    pub fn host_path(&self, path: &Path) -> Option<PathBuf> {
+        match self {
+            ClientConfigType::Windows(_) => Some(path.to_path_buf()),
+            ClientConfigType::Wine(c) => c.host_path(path),
+        }
+    }
+
+    pub fn fingerprint(&self) -> Option<&ClientFingerprint> {
+        match self {
+            ClientConfigType::Windows(c) => c.fingerprint.as_ref(),
+            ClientConfigType::Wine(c) => c.fingerprint.as_ref(),
+        }
+    }
+
+    pub fn fingerprint_mut(&mut self) -> &mut Option<ClientFingerprint> {
+        match self {
+            ClientConfigType::Windows(c) => &mut c.fingerprint,
+            ClientConfigType::Wine(c) => &mut c.fingerprint,
+        }
+    }
+
+    /// Record the build of the client executable as it is now
+    pub fn refresh_fingerprint(&mut self) -> anyhow::Result<&ClientFingerprint> {
+        let Some(path) = self.host_path(self.client_path()) else {
+            bail!(
+                "Couldn't find {} on this machine",
+                self.client_path().display()
+            );
+        };
+
+        Ok(self
+            .fingerprint_mut()
+            .insert(ClientFingerprint::read(&path)?))
+    }
+
+    /// Validate the client and DLL paths exist, that the DLL can be injected
+    /// and, when the client exists, that the DLL supports its build
+    pub fn validate(&self, inject_config: Option<&InjectConfig>) -> ValidationResult {
+        let mut result = match self {
+            ClientConfigType::Windows(c) => c.validate(inject_config),
+            ClientConfigType::Wine(c) => c.validate(inject_config),
+        };
+
+        if let Some(path) = self.host_path(self.client_path())
+            && path.exists()
+        {
+            result.merge(validate_client_build(
+                &path,
+                self.fingerprint(),
+                inject_config,
+            ));
+        }
+        if let Some(dll) = inject_config {
+            result.merge(validate_dll(self, dll));
+        }
+
+        result
+    }
+}
+
+impl std::fmt::Display for ClientConfigType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use crate::client_config::ClientConfig;
+
+        match self {
+            ClientConfigType::Windows(c) => ClientConfig::fmt_display(c, f),
+            ClientConfigType::Wine(c) => ClientConfig::fmt_display(c, f),
+        }
+    }
+}
+
+const SETTINGS_VERSION: u32 = 4;
+const SETTINGS_DIR_NAME: &str = "Alembic";
+const SETTINGS_FILE_NAME: &str = "config.json";
+/// Number of leading characters of an ID shown in listings
+const SHORT_ID_LEN: usize = 8;
+const ENV_PREFIX: &str = "ALEMBIC";
+
+#[allow(dead_code)]
+pub struct SettingsManager {
+    pub settings: Arc<RwLock<AlembicSettings>>,
+}
+
+static SETTINGS: Lazy<SettingsManager> =
+    Lazy::new(|| SettingsManager::new().expect("Failed to initialize settings"));
+
+impl SettingsManager {
+    pub fn new() -> anyhow::Result<Self> {
+        let loaded_settings = ensure_settings()?;
+
+        Ok(Self {
+            settings: Arc::new(RwLock::new(loaded_settings)),
+        })
+    }
+
+    pub fn save() -> anyhow::Result<()> {
+        Lazy::force(&SETTINGS);
+
+        let settings_path = ensure_settings_file()?;
+        let _lock = persist::lock_settings_file(&settings_path)?;
+
+        let settings = SETTINGS.settings.read().unwrap();
+        persist::write_private(&settings_path, &settings.to_saved_json()?)
+    }
+
+    pub fn get<T, F>(f: F) -> T
+    where
+        F: Fn(&AlembicSettings) -> T,
+        T: Clone,
+    {
+        let settings = SETTINGS.settings.read().unwrap();
+        f(&settings)
+    }
+
+    /// Apply `f` to the settings on disk and save the result.
+    ///
+    /// The file is reloaded while holding the settings lock, so changes made
+    /// by other processes since we last read it aren't overwritten.
+    pub fn modify<F>(f: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(&mut AlembicSettings),
+    {
+        // Loading SETTINGS takes the settings lock itself, so it has to happen
+        // before we take it below
+        Lazy::force(&SETTINGS);
+
+        let settings_path = ensure_settings_file()?;
+        let _lock = persist::lock_settings_file(&settings_path)?;
+
+        let mut settings = read_settings_file(&settings_path)?;
+        f(&mut settings);
+        persist::write_private(&settings_path, &serde_json::to_string_pretty(&settings)?)?;
+
+        settings.apply_env_overrides();
+        *SETTINGS.settings.write().unwrap() = settings;
+
+        Ok(())
+    }
+
+    pub fn to_string() -> Result<std::string::String, serde_json::Error> {
+        let settings = SETTINGS.settings.read().unwrap();
+        serde_json::to_string_pretty(&*settings)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AlembicSettings {
+    pub version: u32,
+    pub is_configured: bool,
+
+    /// All discovered/configured client installations
+    #[serde(default)]
+    pub clients: Vec<ClientConfigType>,
+
+    /// ID of the currently selected client
+    #[serde(default)]
+    pub selected_client: Option<String>,
+
+    /// ID of the currently selected server
+    pub selected_server: Option<String>,
+    /// ID of the currently selected account
+    pub selected_account: Option<String>,
+    pub accounts: Vec<Account>,
+    pub servers: Vec<ServerInfo>,
+
+    /// Named client, DLL, server and account combinations
+    #[serde(default)]
+    pub profiles: Vec<LaunchProfile>,
+
+    /// Extra places to look when scanning for clients and DLLs
+    #[serde(default)]
+    pub scan: ScanOptions,
+
+    /// Selections replaced by environment variables, with the values they
+    /// replaced. See `overrides`.
+    #[serde(skip)]
+    overridden: Vec<(overrides::Selection, Option<String>)>,
+}
+
+impl Default for AlembicSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlembicSettings {
+    pub fn new() -> AlembicSettings {
+        AlembicSettings {
+            version: SETTINGS_VERSION,
+            is_configured: false,
+            clients: vec![],
+            selected_client: None,
+            selected_account: None,
+            selected_server: None,
+            accounts: vec![],
+            servers: vec![],
+            profiles: vec![],
+            scan: ScanOptions::default(),
+            overridden: vec![],
+        }
+    }
+
+    /// Index of the currently selected client
+    pub fn selected_client_index(&self) -> Option<usize> {
+        self.client_index(self.selected_client.as_deref()?)
+    }
+
+    /// Index of the currently selected server
+    pub fn selected_server_index(&self) -> Option<usize> {
+        self.server_index(self.selected_server.as_deref()?)
+    }
+
+    /// Index of the currently selected account
+    pub fn selected_account_index(&self) -> Option<usize> {
+        self.account_index(self.selected_account.as_deref()?)
+    }
+
+    /// Index of the client with this exact ID. Inside `modify`, look entries
+    /// up by ID with these rather than reusing an index from before the file
+    /// was reloaded, since another process may have added or removed some.
+    pub fn client_index(&self, id: &str) -> Option<usize> {
+        self.clients.iter().position(|c| c.id() == id)
+    }
+
+    /// Index of the server with this exact ID. See `client_index`.
+    pub fn server_index(&self, id: &str) -> Option<usize> {
+        self.servers.iter().position(|s| s.id == id)
+    }
+
+    /// Index of the account with this exact ID. See `client_index`.
+    pub fn account_index(&self, id: &str) -> Option<usize> {
+        self.accounts.iter().position(|a| a.id == id)
+    }
+
+    /// Find a client by index, ID or name. See `resolve_index`.
+    pub fn find_client(&self, key: &str) -> Option<usize> {
+        resolve_index(&self.clients, key, |c| c.id(), |c| c.name())
+    }
+
+    /// Find a server by index, ID or name. See `resolve_index`.
+    pub fn find_server(&self, key: &str) -> Option<usize> {
+        resolve_index(&self.servers, key, |s| &s.id, |s| &s.name)
+    }
+
+    /// Find an account by index, ID or username. See `resolve_index`.
+    pub fn find_account(&self, key: &str) -> Option<usize> {
+        resolve_index(&self.accounts, key, |a| &a.id, |a| &a.username)
+    }
+
+    /// Get the server an account belongs to
+    pub fn account_server(&self, account: &Account) -> Option<&ServerInfo> {
+        self.servers.iter().find(|s| s.id == account.server_id)
+    }
+
+    /// Get the currently selected client config
+    pub fn get_selected_client(&self) -> Option<&ClientConfigType> {
+        self.selected_client_index()
+            .and_then(|idx| self.clients.get(idx))
+    }
+
+    /// Get mutable reference to selected client
+    pub fn get_selected_client_mut(&mut self) -> Option<&mut ClientConfigType> {
+        self.selected_client_index()
+            .and_then(|idx| self.clients.get_mut(idx))
+    }
+
+    /// Add a new client config and optionally select it
+    pub fn add_client(&mut self, config: ClientConfigType, select: bool) {
+        if select || self.selected_client.is_none() {
+            self.selected_client = Some(config.id().to_string());
+        }
+        self.clients.push(config);
+    }
+
+    /// Remove a client config by index
+    pub fn remove_client(&mut self, index: usize) -> Option<ClientConfigType> {
+        if index >= self.clients.len() {
+            return None;
+        }
+
+        let removed = self.clients.remove(index);
+
+        // Removed the selected client, so select the first remaining one
+        if self.selected_client.as_deref() == Some(removed.id()) {
+            self.selected_client = self.clients.first().map(|c| c.id().to_string());
+        }
+
+        Some(removed)
+    }
+
+    /// Remove a server by index, along with any accounts on it
+    pub fn remove_server(&mut self, index: usize) -> Option<ServerInfo> {
+        if index >= self.servers.len() {
+            return None;
+        }
+
+        let removed = self.servers.remove(index);
+
+        if self.selected_server.as_deref() == Some(removed.id.as_str()) {
+            self.selected_server = None;
+        }
+
+        self.accounts.retain(|a| a.server_id != removed.id);
+
+        if self.selected_account_index().is_none() {
+            self.selected_account = None;
+        }
+
+        Some(removed)
+    }
+
+    /// Remove an account by index
+    pub fn remove_account(&mut self, index: usize) -> Option<Account> {
+        if index >= self.accounts.len() {
+            return None;
+        }
+
+        let removed = self.accounts.remove(index);
+
+        if self.selected_account.as_deref() == Some(removed.id.as_str()) {
+            self.selected_account = None;
+        }
+
+        Some(removed)
+    }
+
+    /// Get the selected server
+    pub fn get_selected_server(&self) -> Option<&ServerInfo> {
+        self.selected_server_index()
+            .and_then(|idx| self.servers.get(idx))
+    }
+
+    /// Get the selected account
+    pub fn get_selected_account(&self) -> Option<&Account> {
+        self.selected_account_index()
+            .and_then(|idx| self.accounts.get(idx))
+    }
+
+    /// Get the selected DLL for the selected client
+    pub fn get_selected_dll(&self) -> Option<&InjectConfig> {
+        self.get_selected_client()
+            .and_then(|client| client.selected_dll().and_then(|idx| client.dlls().get(idx)))
+    }
+
+    /// Get mutable reference to DLLs for a specific client
+    pub fn get_client_dlls_mut(&mut self, client_idx: usize) -> Option<&mut Vec<InjectConfig>> {
+        self.clients.get_mut(client_idx).map(|c| c.dlls_mut())
+    }
+
+    /// Get immutable reference to DLLs for a specific client
+    pub fn get_client_dlls(&self, client_idx: usize) -> Option<&Vec<InjectConfig>> {
+        self.clients.get(client_idx).map(|c| c.dlls())
+    }
+
+    /// Get selected DLL for a specific client
+    pub fn get_client_selected_dll(&self, client_idx: usize) -> Option<&InjectConfig> {
+        self.clients
+            .get(client_idx)
+            .and_then(|client| client.selected_dll().and_then(|idx| client.dlls().get(idx)))
+    }
+
+    /// Add a DLL to a specific client, skipping if a DLL with the same path already exists
+    pub fn add_dll_to_client(&mut self, client_idx: usize, inject_config: InjectConfig) -> bool {
+        if let Some(client) = self.clients.get_mut(client_idx) {
+            let already_exists = client
+                .dlls()
+                .iter()
+                .any(|existing| existing.dll_path == inject_config.dll_path);
+            if already_exists {
+                return false;
+            }
+            client.dlls_mut().push(inject_config);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove a DLL from a specific client by index
+    pub fn remove_dll_from_client(&mut self, client_idx: usize, dll_idx: usize) -> bool {
+        let Some(client) = self.clients.get_mut(client_idx) else {
+            return false;
+        };
+
+        if dll_idx >= client.dlls().len() {
+            return false;
+        }
+
+        let removed = client.dlls_mut().remove(dll_idx);
+
+        // Clear the selection if it pointed at the removed DLL
+        let selected_dll = client.selected_dll_id_mut();
+        if selected_dll.as_deref() == Some(removed.id.as_str()) {
+            *selected_dll = None;
+        }
+
+        true
+    }
+
+    /// Select a client's DLL by their IDs, or clear the client's selection.
+    /// Returns false, changing nothing, if the client or DLL no longer exists.
+    pub fn select_client_dll(&mut self, client_id: &str, dll_id: Option<&str>) -> bool {
+        let Some(client) = self.clients.iter_mut().find(|c| c.id() == client_id) else {
+            return false;
+        };
+        if let Some(dll_id) = dll_id
+            && client.dll_index(dll_id).is_none()
+        {
+            return false;
+        }
+
+        *client.selected_dll_id_mut() = dll_id.map(str::to_string);
+        true
+    }
+
+    /// Set the selected DLL for a specific client
+    pub fn select_dll_for_client(&mut self, client_idx: usize, dll_idx: Option<usize>) {
+        if let Some(client) = self.clients.get_mut(client_idx) {
+            let dll_id = dll_idx.and_then(|idx| client.dlls().get(idx).map(|dll| dll.id.clone()));
+            *client.selected_dll_id_mut() = dll_id;
+        }
+    }
+}
+
+impl AlembicSettings {
+    pub fn load(&mut self) -> anyhow::Result<()> {
+        let path = ensure_settings_file()?;
+        let _lock = persist::lock_settings_file(&path)?;
+        *self = read_settings_file(&path)?;
+        self.apply_env_overrides();
+
+        Ok(())
+    }
+
+    /// Write these settings to disk as-is, replacing whatever is there
+    pub fn save(&self) -> anyhow::Result<()> {
+        let settings_file_path = ensure_settings_file()?;
+        let _lock = persist::lock_settings_file(&settings_file_path)?;
+        persist::write_private(&settings_file_path, &self.to_saved_json()?)
+    }
+
+    /// Reload from disk, apply `f` and save, all while holding the settings
+    /// lock. Use this instead of `save` for discrete changes so edits made by
+    /// other processes in the meantime aren't lost.
+    pub fn modify<F>(&mut self, f: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(&mut AlembicSettings),
+    {
+        let settings_file_path = ensure_settings_file()?;
+        let _lock = persist::lock_settings_file(&settings_file_path)?;
+
+        *self = read_settings_file(&settings_file_path)?;
+        f(self);
+
+        let serialized = serde_json::to_string_pretty(&self)?;
+        persist::write_private(&settings_file_path, &serialized)?;
+
+        self.apply_env_overrides();
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ServerInfo {
+    /// Stable identifier used to reference this server from accounts
+    pub id: String,
+    pub name: String,
+    pub hostname: String,
+    pub port: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Account {
+    /// Stable identifier used to reference this account from elsewhere in settings
+    pub id: String,
+    /// ID of the server this account belongs to
+    pub server_id: String,
+    pub username: String,
+    /// Where the account's password is kept
+    pub credential: CredentialRef,
+}
+
+/// Generate a new ID for a settings entry
+pub fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Resolve a user-supplied reference to a position in `items`.
+///
+/// The reference is tried, in order, as an index, an exact ID, a name, and
+/// finally an unambiguous ID prefix so the short IDs shown in listings work
+/// too.
+pub fn resolve_index<T>(
+    items: &[T],
+    key: &str,
+    id: impl Fn(&T) -> &str,
+    name: impl Fn(&T) -> &str,
+) -> Option<usize> {
+    if let Ok(index) = key.parse::<usize>()
+        && index < items.len()
+    {
+        return Some(index);
+    }
+
+    if let Some(index) = items.iter().position(|item| id(item) == key) {
+        return Some(index);
+    }
+
+    if let Some(index) = items.iter().position(|item| name(item) == key) {
+        return Some(index);
+    }
+
+    if key.len() < SHORT_ID_LEN {
+        return None;
+    }
+
+    let mut matches = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| id(item).starts_with(key));
+
+    match (matches.next(), matches.next()) {
+        (Some((index, _)), None) => Some(index),
+        _ => None,
+    }
+}
+
+/// Shorten an ID for display
+pub fn short_id(id: &str) -> &str {
+    id.get(..SHORT_ID_LEN).unwrap_or(id)
+}
+
+/// Directory containing the settings file
+pub fn get_settings_dir() -> anyhow::Result<PathBuf> {
+    let path = get_settings_file_path()?;
+    match path.parent() {
+        Some(dir) => Ok(dir.to_path_buf()),
+        None => bail!("Settings file {} has no parent directory", path.display()),
+    }
+}
+
+/// Path to the settings file, usually config.json in the user's config
+/// directory. See `overrides` for the other places it can be. The file may not
+/// exist yet.
+pub fn get_settings_file_path() -> anyhow::Result<PathBuf> {
+    overrides::settings_file_path()
+}
+
+fn ensure_settings_dir() -> anyhow::Result<PathBuf> {
+    let settings_dir = get_settings_dir()?;
+    fs::create_dir_all(&settings_dir)?;
+
+    Ok(settings_dir)
+}
+
+fn ensure_settings_file() -> anyhow::Result<PathBuf> {
+    ensure_settings_dir()?;
+    let settings_file_path = get_settings_file_path()?;
+
+    if fs::exists(&settings_file_path)? {
+        return Ok(settings_file_path);
+    }
+
+    // Check again under the lock in case another process just created it
+    let _lock = persist::lock_settings_file(&settings_file_path)?;
+    if !fs::exists(&settings_file_path)? {
+        let serialized = serde_json::to_string_pretty(&AlembicSettings::new())?;
+        persist::write_private(&settings_file_path, &serialized)?;
+    }
+
+    Ok(settings_file_path)
+}
+
+fn ensure_settings() -> anyhow::Result<AlembicSettings> {
+    let path = ensure_settings_file()?;
+    let _lock = persist::lock_settings_file(&path)?;
+    let mut settings = read_settings_file(&path)?;
+    settings.apply_env_overrides();
+    Ok(settings)
+}
+
+/// Read and deserialize a settings file, migrating it first if it was written
+/// by an older version of Alembic.
+///
+/// Migrated files are backed up next to the original before being rewritten
+/// in the current format, so callers must hold the settings lock.
+fn read_settings_file(path: &Path) -> anyhow::Result<AlembicSettings> {
+    let contents = fs::read_to_string(path)?;
+    let mut value: serde_json::Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse settings file {}", path.display()))?;
+
+    let from_version = migration::migrate(&mut value)
+        .with_context(|| format!("Failed to migrate settings file {}", path.display()))?;
+
+    let mut settings: AlembicSettings = serde_json::from_value(value)
+        .with_context(|| format!("Failed to read settings file {}", path.display()))?;
+
+    if let Some(from_version) = from_version {
+        let backup_path = migration::backup_settings_file(path, from_version)?;
+
+        // Files from before credential stores held passwords in plaintext
+        if from_version < migration::CREDENTIAL_STORE_VERSION {
+            let secured = credentials::secure_plaintext_passwords(&mut settings.accounts);
+
+            if !secured.moved.is_empty() {
+                eprintln!(
+                    "Moved {} password(s) from config.json to the {}",
+                    secured.moved.len(),
+                    CredentialStoreKind::default()
+                );
+                migration::redact_backup_passwords(&backup_path, &secured.moved)?;
+            }
+
+            if let Some(e) = secured.error {
+                eprintln!(
+                    "Couldn't move {} password(s) out of config.json, so they're still stored there: {:#}. Use 'alembic config account edit --store' to move them.",
+                    secured.remaining, e
+                );
+            }
+        }
+
+        persist::write_private(path, &serde_json::to_string_pretty(&settings)?)?;
+
+        eprintln!(
+            "Migrated settings from version {} to {}. The original was saved to {}",
+            from_version,
+            SETTINGS_VERSION,
+            backup_path.display()
+        );
+    }
+
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, name: &str) -> ServerInfo {
+        ServerInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            hostname: "localhost".to_string(),
+            port: "9000".to_string(),
+        }
+    }
+
+    #[test]
+    fn find_server_accepts_index_id_name_and_id_prefix() {
+        let mut settings = AlembicSettings::new();
+        settings.servers = vec![
+            server("3f2a9c1b-0000-4000-8000-000000000000", "Coldeve"),
+            server("3f2a9c1b-1111-4000-8000-000000000000", "Levistras"),
+        ];
+
+        assert_eq!(settings.find_server("1"), Some(1));
+        assert_eq!(
+            settings.find_server("3f2a9c1b-1111-4000-8000-000000000000"),
+            Some(1)
+        );
+        assert_eq!(settings.find_server("Coldeve"), Some(0));
+        assert_eq!(settings.find_server("3f2a9c1b-0"), Some(0));
+        // Ambiguous prefixes and unknown names don't match anything
+        assert_eq!(settings.find_server("3f2a9c1b"), None);
+        assert_eq!(settings.find_server("Frostfell"), None);
+    }
+
+    #[test]
+    fn remove_server_removes_its_accounts() {
+        let mut settings = AlembicSettings::new();
+        settings.servers = vec![server("a", "Coldeve"), server("b", "Levistras")];
+        settings.accounts = vec![Account {
+            id: "account".to_string(),
+            server_id: "a".to_string(),
+            username: "alice".to_string(),
+            credential: CredentialRef::Keyring,
+        }];
+        settings.selected_server = Some("a".to_string());
+        settings.selected_account = Some("account".to_string());
+
+        settings.remove_server(0);
+
+        assert!(settings.accounts.is_empty());
+        assert_eq!(settings.selected_server, None);
+        assert_eq!(settings.selected_account, None);
+    }
+
+    #[test]
+    fn apply_profile_restores_selections() {
+        let mut settings = AlembicSettings::new();
+        settings.servers = vec![server("a", "Coldeve"), server("b", "Levistras")];
+        settings.accounts = vec![Account {
+            id: "account".to_string(),
+            server_id: "b".to_string(),
+            username: "alice".to_string(),
+            credential: CredentialRef::Keyring,
+        }];
+        settings.add_client(
+            ClientConfigType::Windows(WindowsClientConfig {
+                id: "client".to_string(),
+                name: "Windows".to_string(),
+                client_path: PathBuf::from("C:\\Turbine\\Asheron's Call\\acclient.exe"),
+                dlls: vec![],
+                selected_dll: None,
+                fingerprint: None,
+            }),
+            true,
+        );
+        settings.selected_server = Some("b".to_string());
+        settings.selected_account = Some("account".to_string());
+
+        let profile = settings.profile_from_selection("Test").unwrap();
+        settings.add_profile(profile);
+        assert_eq!(settings.active_profile_index(), Some(0));
+
+        settings.selected_server = Some("a".to_string());
+        settings.selected_account = None;
+        assert_eq!(settings.active_profile_index(), None);
+
+        settings.apply_profile(0).unwrap();
+        assert_eq!(settings.selected_server.as_deref(), Some("b"));
+        assert_eq!(settings.selected_account.as_deref(), Some("account"));
+
+        // Profiles that point at removed entries can't be applied
+        settings.remove_server(1);
+        assert!(settings.apply_profile(0).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! Overrides for where settings are kept and what is selected, for scripted
+//! setups and portable installs.
+//!
+//! The settings file is the first of:
+//!
+//! 1. The path passed to `set_settings_file_path` (the `--config` flag)
+//! 2. `ALEMBIC_CONFIG`
+//! 3. `config.json` next to the executable, in portable mode. Portable mode is
+//!    on when `ALEMBIC_PORTABLE` is set or a `portable.txt` file sits next to
+//!    the executable.
+//! 4. `config.json` in the user's config directory
+//!
+//! `ALEMBIC_CLIENT`, `ALEMBIC_SERVER` and `ALEMBIC_ACCOUNT` replace the
+//! selected client, server and account (by index, ID or name) for the current
+//! process only. They are never written back to the settings file, and ones
+//! that don't match anything are ignored with a warning.
+
+use std::{path::PathBuf, sync::Once};
+
+use anyhow::{anyhow, bail};
+use directories::BaseDirs;
+use once_cell::sync::OnceCell;
+
+use super::{AlembicSettings, ENV_PREFIX, SETTINGS_DIR_NAME, SETTINGS_FILE_NAME};
+
+/// File that turns on portable mode when it sits next to the executable
+const PORTABLE_MARKER: &str = "portable.txt";
+
+/// A selection that an environment variable can override
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Selection {
+    Client,
+    Server,
+    Account,
+}
+
+impl Selection {
+    /// The settings field holding this selection
+    fn field(self) -> &'static str {
+        match self {
+            Selection::Client => "selected_client",
+            Selection::Server => "selected_server",
+            Selection::Account => "selected_account",
+        }
+    }
+}
+
+static SETTINGS_FILE_PATH: OnceCell<PathBuf> = OnceCell::new();
+
+/// Use `path` as the settings file. This has to be called before settings are
+/// first used, and only once.
+pub fn set_settings_file_path(path: impl Into<PathBuf>) -> anyhow::Result<()> {
+    let path = std::path::absolute(path.into())?;
+
+    SETTINGS_FILE_PATH
+        .set(path)
+        .map_err(|_| anyhow!("The settings file path has already been set"))
+}
+
+/// Read `ALEMBIC_<name>`, treating an empty value as unset
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(format!("{}_{}", ENV_PREFIX, name))
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// The directory next to the executable, when running in portable mode
+fn portable_dir() -> Option<PathBuf> {
+    let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+    let enabled = env_var("PORTABLE").is_some_and(|value| value != "0")
+        || exe_dir.join(PORTABLE_MARKER).exists();
+
+    enabled.then_some(exe_dir)
+}
+
+pub(super) fn settings_file_path() -> anyhow::Result<PathBuf> {
+    if let Some(path) = SETTINGS_FILE_PATH.get() {
+        return Ok(path.clone());
+    }
+
+    if let Some(path) = env_var("CONFIG") {
+        return Ok(std::path::absolute(path)?);
+    }
+
+    if let Some(dir) = portable_dir() {
+        return Ok(dir.join(SETTINGS_FILE_NAME));
+    }
+
+    let Some(base_dir) = BaseDirs::new() else {
+        bail!("Failed to get BaseDirs. Not loading existing settings.");
+    };
+
+    Ok(base_dir
+        .config_dir()
+        .join(SETTINGS_DIR_NAME)
+        .join(SETTINGS_FILE_NAME))
+}
+
+impl AlembicSettings {
+    /// Apply `ALEMBIC_CLIENT`, `ALEMBIC_SERVER` and `ALEMBIC_ACCOUNT`. Ones
+    /// that don't match anything are skipped with a warning, printed the
+    /// first time settings are loaded.
+    pub(super) fn apply_env_overrides(&mut self) {
+        static WARNED: Once = Once::new();
+
+        let warnings =
+            self.apply_overrides(env_var("CLIENT"), env_var("SERVER"), env_var("ACCOUNT"));
+
+        WARNED.call_once(|| {
+            for warning in warnings {
+                eprintln!("Warning: {}", warning);
+            }
+        });
+    }
+
+    /// Apply the overrides that match something, returning a warning for
+    /// each one that doesn't
+    fn apply_overrides(
+        &mut self,
+        client: Option<String>,
+        server: Option<String>,
+        account: Option<String>,
+    ) -> Vec<String> {
+        let mut warnings = vec![];
+
+        if let Some(client) = client {
+            match self.find_client(&client) {
+                Some(index) => {
+                    let id = self.clients[index].id().to_string();
+                    self.override_selection(Selection::Client, id);
+                }
+                None => warnings.push(format!(
+                    "Ignoring {}_CLIENT, which doesn't match any client: {}",
+                    ENV_PREFIX, client
+                )),
+            }
+        }
+
+        let mut server_overridden = false;
+        if let Some(server) = server {
+            match self.find_server(&server) {
+                Some(index) => {
+                    let id = self.servers[index].id.clone();
+                    self.override_selection(Selection::Server, id);
+                    server_overridden = true;
+                }
+                None => warnings.push(format!(
+                    "Ignoring {}_SERVER, which doesn't match any server: {}",
+                    ENV_PREFIX, server
+                )),
+            }
+        }
+
+        if let Some(account) = account {
+            match self.find_account(&account) {
+                Some(index) => {
+                    let id = self.accounts[index].id.clone();
+                    let server_id = self.accounts[index].server_id.clone();
+                    self.override_selection(Selection::Account, id);
+
+                    // An account only works with its own server
+                    if !server_overridden {
+                        self.override_selection(Selection::Server, server_id);
+                    }
+                }
+                None => warnings.push(format!(
+                    "Ignoring {}_ACCOUNT, which doesn't match any account: {}",
+                    ENV_PREFIX, account
+                )),
+            }
+        }
+
+        warnings
+    }
+
+    fn override_selection(&mut self, selection: Selection, id: String) {
+        let saved = match selection {
+            Selection::Client => &mut self.selected_client,
+            Selection::Server => &mut self.selected_server,
+            Selection::Account => &mut self.selected_account,
+        }
+        .replace(id);
+
+        // Keep the value from the settings file, not an earlier override
+        if !self.overridden.iter().any(|(s, _)| *s == selection) {
+            self.overridden.push((selection, saved));
+        }
+    }
+
+    /// These settings as they'd be written to the settings file, with any
+    /// overridden selections put back the way they were
+    pub fn to_saved_value(&self) -> anyhow::Result<serde_json::Value> {
+        let mut value = serde_json::to_value(self)?;
+        for (selection, saved) in &self.overridden {
+            value[selection.field()] = serde_json::to_value(saved)?;
+        }
+
+        Ok(value)
+    }
+
+    /// Serialize for writing to the settings file. See `to_saved_value`.
+    pub(super) fn to_saved_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(&self.to_saved_value()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{Account, ServerInfo};
+
+    #[test]
+    fn overrides_are_not_saved() {
+        let mut settings = AlembicSettings::new();
+        for (id, name) in [("s1", "Coldeve"), ("s2", "Levistras")] {
+            settings.servers.push(ServerInfo {
+                id: id.to_string(),
+                name: name.to_string(),
+                hostname: format!("{}.ac", name.to_lowercase()),
+                port: "9000".to_string(),
+            });
+        }
+        settings.accounts.push(Account {
+            id: "a1".to_string(),
+            server_id: "s2".to_string(),
+            username: "alice".to_string(),
+            credential: crate::credentials::CredentialStoreKind::Plaintext.empty_ref(),
+        });
+        settings.selected_server = Some("s1".to_string());
+
+        let warnings = settings.apply_overrides(None, None, Some("alice".to_string()));
+        assert!(warnings.is_empty());
+
+        // The account's server comes along with it
+        assert_eq!(settings.selected_account.as_deref(), Some("a1"));
+        assert_eq!(settings.selected_server.as_deref(), Some("s2"));
+
+        let saved: serde_json::Value =
+            serde_json::from_str(&settings.to_saved_json().unwrap()).unwrap();
+        assert_eq!(saved["selected_server"], "s1");
+        assert!(saved["selected_account"].is_null());
+
+        // Overrides that don't match anything are skipped
+        let warnings = settings.apply_overrides(None, Some("nope".to_string()), None);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(settings.selected_server.as_deref(), Some("s2"));
+    }
+}
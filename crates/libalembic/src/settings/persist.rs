@@ -0,0 +1,166 @@
+//! Locking and atomic writes for config.json and the other files Alembic
+//! writes.
+//!
+//! The desktop app, TUI and CLI can all have the settings file open at once.
+//! Each read-modify-write holds an advisory lock on a sidecar `.lock` file,
+//! and new contents are written to a temporary file that is renamed over the
+//! original so a crash never leaves a truncated config.json behind.
+
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+/// Exclusive lock on a settings file, released when dropped
+pub struct SettingsLock {
+    _file: File,
+}
+
+fn sibling_path(path: &Path, prefix: &str, suffix: &str) -> PathBuf {
+    let mut file_name = OsString::from(prefix);
+    file_name.push(path.file_name().unwrap_or_default());
+    file_name.push(suffix);
+
+    path.with_file_name(file_name)
+}
+
+/// Block until we hold the lock for the settings file at `path`
+pub fn lock_settings_file(path: &Path) -> anyhow::Result<SettingsLock> {
+    let lock_path = sibling_path(path, "", ".lock");
+
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open lock file {}", lock_path.display()))?;
+
+    file.lock()
+        .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+
+    Ok(SettingsLock { _file: file })
+}
+
+/// Replace the contents of `path` without ever exposing a partially written
+/// file to readers
+pub fn write_atomic(path: &Path, contents: &str) -> anyhow::Result<()> {
+    replace_file(path, contents, false)
+}
+
+/// Like `write_atomic`, but only the current user can read the new file, for
+/// files that may hold passwords
+pub fn write_private(path: &Path, contents: &str) -> anyhow::Result<()> {
+    replace_file(path, contents, true)
+}
+
+fn replace_file(path: &Path, contents: &str, private: bool) -> anyhow::Result<()> {
+    let temp_path = sibling_path(path, ".", &format!(".{}.tmp", std::process::id()));
+
+    let result = (|| {
+        let mut file = File::create(&temp_path)?;
+        if private {
+            restrict_to_owner(&file)?;
+        } else if let Ok(metadata) = fs::metadata(path) {
+            // Keep any permissions the user set on the file being replaced
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result.with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Make a file readable and writable only by its owner. Files under the
+/// user's profile on Windows already are.
+fn restrict_to_owner(file: &File) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = file;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::TryLockError;
+
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn write_atomic_replaces_contents() {
+        let dir = TempDir::new("write-atomic");
+        let path = dir.join("config.json");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, "new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        // Only the settings file itself should be left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomic_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new("write-atomic-permissions");
+        let path = dir.join("config.json");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_atomic(&path, "new").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_private_is_only_readable_by_the_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new("write-private");
+        let path = dir.join("vault.json");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private(&path, "new").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    }
+
+    #[test]
+    fn lock_is_exclusive() {
+        let dir = TempDir::new("lock");
+        let path = dir.join("config.json");
+
+        let lock = lock_settings_file(&path).unwrap();
+
+        let other = File::options()
+            .write(true)
+            .open(sibling_path(&path, "", ".lock"))
+            .unwrap();
+        assert!(matches!(other.try_lock(), Err(TryLockError::WouldBlock)));
+
+        drop(lock);
+        assert!(other.try_lock().is_ok());
+    }
+}
@@ -0,0 +1,169 @@
+//! Named launch profiles.
+//!
+//! A profile bundles a client, an optional DLL, a server and an account so a
+//! combination can be launched, or selected, in one step.
+
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize};
+
+use super::{Account, AlembicSettings, ClientConfigType, ServerInfo, new_id, resolve_index};
+use crate::inject_config::InjectConfig;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct LaunchProfile {
+    pub id: String,
+    pub name: String,
+    pub client_id: String,
+    /// DLL to inject, or `None` to launch without injection
+    pub dll_id: Option<String>,
+    pub server_id: String,
+    pub account_id: String,
+}
+
+/// Everything needed to launch a client, resolved from settings
+#[derive(Clone, Debug)]
+pub struct LaunchTarget {
+    pub client: ClientConfigType,
+    pub dll: Option<InjectConfig>,
+    pub server: ServerInfo,
+    pub account: Account,
+}
+
+impl LaunchProfile {
+    pub fn new(
+        name: &str,
+        client: &ClientConfigType,
+        dll: Option<&InjectConfig>,
+        server: &ServerInfo,
+        account: &Account,
+    ) -> Self {
+        Self {
+            id: new_id(),
+            name: name.to_string(),
+            client_id: client.id().to_string(),
+            dll_id: dll.map(|dll| dll.id.clone()),
+            server_id: server.id.clone(),
+            account_id: account.id.clone(),
+        }
+    }
+}
+
+impl AlembicSettings {
+    /// Find a profile by index, ID or name. See `resolve_index`.
+    pub fn find_profile(&self, key: &str) -> Option<usize> {
+        resolve_index(&self.profiles, key, |p| &p.id, |p| &p.name)
+    }
+
+    /// Index of the profile with this exact ID. See `client_index`.
+    pub fn profile_index(&self, id: &str) -> Option<usize> {
+        self.profiles.iter().position(|p| p.id == id)
+    }
+
+    /// Build a profile from the current selections
+    pub fn profile_from_selection(&self, name: &str) -> anyhow::Result<LaunchProfile> {
+        let client = self.get_selected_client().context("No client selected")?;
+        let server = self.get_selected_server().context("No server selected")?;
+        let account = self.get_selected_account().context("No account selected")?;
+
+        Ok(LaunchProfile::new(
+            name,
+            client,
+            self.get_selected_dll(),
+            server,
+            account,
+        ))
+    }
+
+    /// Add a profile, replacing any existing profile with the same name
+    pub fn add_profile(&mut self, profile: LaunchProfile) {
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => {
+                *existing = LaunchProfile {
+                    id: existing.id.clone(),
+                    ..profile
+                }
+            }
+            None => self.profiles.push(profile),
+        }
+    }
+
+    /// Remove a profile by index
+    pub fn remove_profile(&mut self, index: usize) -> Option<LaunchProfile> {
+        if index >= self.profiles.len() {
+            return None;
+        }
+
+        Some(self.profiles.remove(index))
+    }
+
+    /// Resolve everything a profile refers to, failing if any of it has since
+    /// been removed
+    pub fn profile_launch_target(&self, profile: &LaunchProfile) -> anyhow::Result<LaunchTarget> {
+        let Some(client) = self.clients.iter().find(|c| c.id() == profile.client_id) else {
+            bail!(
+                "Profile '{}' uses a client that no longer exists",
+                profile.name
+            );
+        };
+
+        let dll = match &profile.dll_id {
+            Some(dll_id) => match client.dlls().iter().find(|dll| &dll.id == dll_id) {
+                Some(dll) => Some(dll.clone()),
+                None => bail!(
+                    "Profile '{}' uses a DLL that no longer exists",
+                    profile.name
+                ),
+            },
+            None => None,
+        };
+
+        let Some(server) = self.servers.iter().find(|s| s.id == profile.server_id) else {
+            bail!(
+                "Profile '{}' uses a server that no longer exists",
+                profile.name
+            );
+        };
+
+        let Some(account) = self.accounts.iter().find(|a| a.id == profile.account_id) else {
+            bail!(
+                "Profile '{}' uses an account that no longer exists",
+                profile.name
+            );
+        };
+
+        Ok(LaunchTarget {
+            client: client.clone(),
+            dll,
+            server: server.clone(),
+            account: account.clone(),
+        })
+    }
+
+    /// Select the client, DLL, server and account of a profile
+    pub fn apply_profile(&mut self, index: usize) -> anyhow::Result<()> {
+        let profile = self.profiles.get(index).context("No such profile")?.clone();
+        // Make sure everything still exists before changing any selection
+        self.profile_launch_target(&profile)?;
+
+        self.selected_client = Some(profile.client_id.clone());
+        if let Some(client) = self.get_selected_client_mut() {
+            *client.selected_dll_id_mut() = profile.dll_id.clone();
+        }
+        self.selected_server = Some(profile.server_id);
+        self.selected_account = Some(profile.account_id);
+
+        Ok(())
+    }
+
+    /// Index of the profile that matches the current selections, if any
+    pub fn active_profile_index(&self) -> Option<usize> {
+        let dll_id = self.get_selected_dll().map(|dll| dll.id.as_str());
+
+        self.profiles.iter().position(|p| {
+            self.selected_client.as_deref() == Some(p.client_id.as_str())
+                && dll_id == p.dll_id.as_deref()
+                && self.selected_server.as_deref() == Some(p.server_id.as_str())
+                && self.selected_account.as_deref() == Some(p.account_id.as_str())
+        })
+    }
+}
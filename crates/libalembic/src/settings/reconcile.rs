@@ -0,0 +1,399 @@
+//! Reconciling scan results with the configured clients.
+//!
+//! Scanned clients are matched to configured ones by client path and Wine
+//! prefix, so re-scanning reports what changed instead of adding duplicates.
+//! Merging keeps the names and selected DLLs users have chosen.
+
+use std::{fmt, path::PathBuf};
+
+use super::{AlembicSettings, ClientConfigType};
+use crate::fingerprint::ClientFingerprint;
+
+/// How a client found by a scan relates to the configured clients
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanStatus {
+    /// Found by the scan but not configured yet
+    New,
+    /// Configured and the scan found nothing different
+    Unchanged,
+    /// Configured, but the scan found differences
+    Changed(Vec<ScanChange>),
+    /// Configured, but the scan didn't find it
+    Missing,
+}
+
+impl fmt::Display for ScanStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanStatus::New => write!(f, "new"),
+            ScanStatus::Unchanged => write!(f, "unchanged"),
+            ScanStatus::Changed(_) => write!(f, "changed"),
+            ScanStatus::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+/// A difference between a configured client and what the scan found
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanChange {
+    /// The client now launches with a different program, like a new Wine
+    Program { from: PathBuf, to: PathBuf },
+    /// The client now launches with different arguments
+    Args { from: Vec<String>, to: Vec<String> },
+    /// Environment variables the scan sets that are missing or different
+    Env(Vec<String>),
+    /// DLLs the scan found that the client doesn't have yet
+    NewDlls(Vec<PathBuf>),
+    /// The client executable is a different build than the one recorded
+    Build {
+        from: Option<ClientFingerprint>,
+        to: ClientFingerprint,
+    },
+}
+
+impl fmt::Display for ScanChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanChange::Program { from, to } => {
+                write!(f, "program {} -> {}", from.display(), to.display())
+            }
+            ScanChange::Args { from, to } => {
+                write!(f, "arguments '{}' -> '{}'", from.join(" "), to.join(" "))
+            }
+            ScanChange::Env(keys) => write!(f, "environment {}", keys.join(", ")),
+            ScanChange::NewDlls(paths) => {
+                let paths: Vec<_> = paths.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "new DLLs {}", paths.join(", "))
+            }
+            ScanChange::Build {
+                from: Some(from),
+                to,
+            } => {
+                write!(f, "client build {} -> {}", from, to)
+            }
+            ScanChange::Build { from: None, to } => write!(f, "client build {}", to),
+        }
+    }
+}
+
+/// One client in the result of `AlembicSettings::reconcile_scan`
+#[derive(Debug, Clone)]
+pub struct ScanEntry {
+    pub status: ScanStatus,
+    /// Name of the configured client, or the scanned name for new clients
+    pub name: String,
+    pub install_path: PathBuf,
+    /// ID of the configured client. `None` for new clients.
+    pub existing_id: Option<String>,
+    /// The client as scanned. `None` for missing clients.
+    pub scanned: Option<ClientConfigType>,
+}
+
+/// What `AlembicSettings::merge_scan` changed
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScanMergeSummary {
+    pub added: usize,
+    pub updated: usize,
+}
+
+/// Compare a configured client with its scanned counterpart
+fn scan_changes(existing: &ClientConfigType, scanned: &ClientConfigType) -> Vec<ScanChange> {
+    let mut changes = vec![];
+
+    if let (Some(old), Some(new)) = (existing.launch_command(), scanned.launch_command()) {
+        if old.program != new.program {
+            changes.push(ScanChange::Program {
+                from: old.program.clone(),
+                to: new.program.clone(),
+            });
+        }
+
+        if old.args != new.args {
+            changes.push(ScanChange::Args {
+                from: old.args.clone(),
+                to: new.args.clone(),
+            });
+        }
+
+        // Variables the user added themselves aren't a change
+        let mut keys: Vec<_> = new
+            .env
+            .iter()
+            .filter(|(key, value)| old.env.get(*key) != Some(*value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        if !keys.is_empty() {
+            keys.sort();
+            changes.push(ScanChange::Env(keys));
+        }
+    }
+
+    let new_dlls: Vec<_> = scanned
+        .dlls()
+        .iter()
+        .filter(|dll| !existing.dlls().iter().any(|d| d.dll_path == dll.dll_path))
+        .map(|dll| dll.dll_path.clone())
+        .collect();
+    if !new_dlls.is_empty() {
+        changes.push(ScanChange::NewDlls(new_dlls));
+    }
+
+    if let Some(to) = scanned.fingerprint()
+        && existing
+            .fingerprint()
+            .is_none_or(|from| !from.is_same_file(to))
+    {
+        changes.push(ScanChange::Build {
+            from: existing.fingerprint().cloned(),
+            to: to.clone(),
+        });
+    }
+
+    changes
+}
+
+impl AlembicSettings {
+    /// Compare scanned clients with the configured ones.
+    ///
+    /// Returns an entry for every scanned client, marked new, unchanged or
+    /// changed, followed by an entry for every configured client the scan
+    /// didn't find. Clients found more than once, like a prefix picked up by
+    /// two scanners, are only reported the first time.
+    pub fn reconcile_scan(&self, scanned: &[ClientConfigType]) -> Vec<ScanEntry> {
+        let mut entries: Vec<ScanEntry> = vec![];
+        let mut found = vec![false; self.clients.len()];
+
+        for client in scanned {
+            let duplicate = entries
+                .iter()
+                .filter_map(|e| e.scanned.as_ref())
+                .any(|c| c.is_same_install(client));
+            if duplicate {
+                continue;
+            }
+
+            let existing = self.clients.iter().position(|c| c.is_same_install(client));
+
+            let entry = match existing {
+                Some(index) => {
+                    found[index] = true;
+                    let configured = &self.clients[index];
+                    let changes = scan_changes(configured, client);
+
+                    ScanEntry {
+                        status: if changes.is_empty() {
+                            ScanStatus::Unchanged
+                        } else {
+                            ScanStatus::Changed(changes)
+                        },
+                        name: configured.name().to_string(),
+                        install_path: configured.install_path(),
+                        existing_id: Some(configured.id().to_string()),
+                        scanned: Some(client.clone()),
+                    }
+                }
+                None => ScanEntry {
+                    status: ScanStatus::New,
+                    name: client.name().to_string(),
+                    install_path: client.install_path(),
+                    existing_id: None,
+                    scanned: Some(client.clone()),
+                },
+            };
+
+            entries.push(entry);
+        }
+
+        for (client, found) in self.clients.iter().zip(found) {
+            if !found {
+                entries.push(ScanEntry {
+                    status: ScanStatus::Missing,
+                    name: client.name().to_string(),
+                    install_path: client.install_path(),
+                    existing_id: Some(client.id().to_string()),
+                    scanned: None,
+                });
+            }
+        }
+
+        entries
+    }
+
+    /// Apply reconciled scan entries.
+    ///
+    /// New clients are added, selecting the first one if no clients were
+    /// configured. Changed clients take the scanned launch command, keeping
+    /// environment variables the scan doesn't set, gain the new DLLs and
+    /// record the scanned build; their names and selected DLLs are kept.
+    /// Unchanged and missing entries are skipped; missing clients are never
+    /// removed.
+    pub fn merge_scan(&mut self, entries: &[ScanEntry]) -> ScanMergeSummary {
+        let mut summary = ScanMergeSummary::default();
+        let had_no_clients = self.clients.is_empty();
+
+        for entry in entries {
+            let Some(scanned) = &entry.scanned else {
+                continue;
+            };
+
+            match &entry.status {
+                ScanStatus::New => {
+                    self.add_client(scanned.clone(), had_no_clients && summary.added == 0);
+                    self.is_configured = true;
+                    summary.added += 1;
+                }
+                ScanStatus::Changed(_) => {
+                    let Some(client) = entry
+                        .existing_id
+                        .as_deref()
+                        .and_then(|id| self.clients.iter_mut().find(|c| c.id() == id))
+                    else {
+                        continue;
+                    };
+
+                    if let (Some(command), Some(new)) =
+                        (client.launch_command_mut(), scanned.launch_command())
+                    {
+                        command.program = new.program.clone();
+                        command.args = new.args.clone();
+                        command.env.extend(new.env.clone());
+                    }
+
+                    for dll in scanned.dlls() {
+                        if !client.dlls().iter().any(|d| d.dll_path == dll.dll_path) {
+                            client.dlls_mut().push(dll.clone());
+                        }
+                    }
+
+                    if let Some(fingerprint) = scanned.fingerprint() {
+                        *client.fingerprint_mut() = Some(fingerprint.clone());
+                    }
+
+                    summary.updated += 1;
+                }
+                ScanStatus::Unchanged | ScanStatus::Missing => {}
+            }
+        }
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_config::{LaunchCommand, WineClientConfig};
+    use crate::inject_config::{DllType, InjectConfig};
+
+    fn wine_client(
+        id: &str,
+        prefix: &str,
+        wine: &str,
+        dlls: Vec<InjectConfig>,
+    ) -> ClientConfigType {
+        let selected_dll = dlls.first().map(|dll| dll.id.clone());
+
+        ClientConfigType::Wine(WineClientConfig {
+            id: id.to_string(),
+            name: format!("Wine: {}", prefix),
+            client_path: PathBuf::from(r"C:\Turbine\Asheron's Call\acclient.exe"),
+            launch_command: LaunchCommand::new(wine).env("WINEPREFIX", prefix),
+            dlls,
+            selected_dll,
+            fingerprint: None,
+        })
+    }
+
+    fn dll(id: &str, path: &str) -> InjectConfig {
+        InjectConfig {
+            id: id.to_string(),
+            dll_path: PathBuf::from(path),
+            dll_type: DllType::Alembic,
+            startup_function: None,
+        }
+    }
+
+    #[test]
+    fn rescanning_reports_changes_and_merges_without_losing_edits() {
+        let mut settings = AlembicSettings::new();
+        let mut renamed = wine_client(
+            "changed",
+            "/games/ac",
+            "/usr/bin/wine",
+            vec![dll("alembic", r"C:\Alembic\Alembic.dll")],
+        );
+        *renamed.name_mut() = "My AC".to_string();
+        settings.clients = vec![
+            renamed,
+            wine_client("unchanged", "/games/other", "/usr/bin/wine", vec![]),
+            wine_client("missing", "/games/gone", "/usr/bin/wine", vec![]),
+        ];
+        settings.clients[0]
+            .launch_command_mut()
+            .unwrap()
+            .env
+            .insert("DXVK_HUD".to_string(), "1".to_string());
+
+        let scanned = vec![
+            wine_client(
+                "scan-1",
+                "/games/ac",
+                "/opt/wine-staging/bin/wine",
+                vec![
+                    dll("scan-dll-1", r"C:\Decal\Inject.dll"),
+                    dll("scan-dll-2", r"C:\Alembic\Alembic.dll"),
+                ],
+            ),
+            wine_client("scan-2", "/games/other", "/usr/bin/wine", vec![]),
+            wine_client("scan-3", "/games/other", "/usr/bin/wine", vec![]),
+            wine_client("scan-4", "/games/new", "/usr/bin/wine", vec![]),
+        ];
+
+        let entries = settings.reconcile_scan(&scanned);
+        let statuses: Vec<_> = entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.status.to_string()))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("My AC", "changed".to_string()),
+                ("Wine: /games/other", "unchanged".to_string()),
+                ("Wine: /games/new", "new".to_string()),
+                ("Wine: /games/gone", "missing".to_string()),
+            ]
+        );
+        assert_eq!(
+            entries[0].status,
+            ScanStatus::Changed(vec![
+                ScanChange::Program {
+                    from: PathBuf::from("/usr/bin/wine"),
+                    to: PathBuf::from("/opt/wine-staging/bin/wine"),
+                },
+                ScanChange::NewDlls(vec![PathBuf::from(r"C:\Decal\Inject.dll")]),
+            ])
+        );
+
+        let summary = settings.merge_scan(&entries);
+        assert_eq!(
+            summary,
+            ScanMergeSummary {
+                added: 1,
+                updated: 1
+            }
+        );
+        assert_eq!(settings.clients.len(), 4);
+
+        let merged = &settings.clients[0];
+        assert_eq!(merged.name(), "My AC");
+        assert_eq!(merged.selected_dll_id(), Some("alembic"));
+        assert_eq!(merged.dlls().len(), 2);
+        let command = merged.launch_command().unwrap();
+        assert_eq!(command.program, PathBuf::from("/opt/wine-staging/bin/wine"));
+        assert_eq!(command.env.get("DXVK_HUD").map(String::as_str), Some("1"));
+
+        // Scanning the merged clients again finds nothing to do
+        let entries = settings.reconcile_scan(&settings.clients);
+        assert!(entries.iter().all(|e| e.status == ScanStatus::Unchanged));
+    }
+}
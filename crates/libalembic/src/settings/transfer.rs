@@ -0,0 +1,383 @@
+//! Exporting settings to a file and merging them back in on another machine.
+//!
+//! Exports leave out passwords unless asked to include them, and imports match
+//! servers, accounts, clients, DLLs and profiles against what is already
+//! configured so importing the same file twice doesn't duplicate anything.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize};
+
+use super::{Account, AlembicSettings, ClientConfigType, LaunchProfile, ServerInfo};
+use crate::credentials::{self, CredentialRef, CredentialStoreKind};
+
+/// Version of the export file format, independent of `SETTINGS_VERSION`
+const EXPORT_VERSION: u32 = 1;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SettingsExport {
+    pub export_version: u32,
+    pub servers: Vec<ServerInfo>,
+    pub accounts: Vec<ExportedAccount>,
+    #[serde(default)]
+    pub clients: Vec<ClientConfigType>,
+    #[serde(default)]
+    pub profiles: Vec<LaunchProfile>,
+}
+
+/// An account as written to an export. The password is only present when the
+/// export was made with passwords included.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ExportedAccount {
+    pub id: String,
+    pub server_id: String,
+    pub username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExportOptions {
+    /// Look up and include account passwords
+    pub include_passwords: bool,
+    /// Leave out clients (and the profiles that use them), since their paths
+    /// are usually specific to one machine
+    pub skip_clients: bool,
+}
+
+/// What an import changed
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub servers_added: usize,
+    pub servers_updated: usize,
+    pub accounts_added: usize,
+    pub accounts_updated: usize,
+    pub clients_added: usize,
+    pub clients_merged: usize,
+    pub profiles_added: usize,
+    pub profiles_updated: usize,
+    /// Imported passwords by local account ID, still to be saved with
+    /// `save_imported_passwords`
+    pub passwords: Vec<(String, String)>,
+}
+
+impl SettingsExport {
+    /// An export of just servers and accounts
+    pub fn new(servers: Vec<ServerInfo>, accounts: Vec<ExportedAccount>) -> Self {
+        Self {
+            export_version: EXPORT_VERSION,
+            servers,
+            accounts,
+            clients: vec![],
+            profiles: vec![],
+        }
+    }
+
+    pub fn from_json(contents: &str) -> anyhow::Result<Self> {
+        let export: SettingsExport =
+            serde_json::from_str(contents).context("Not an Alembic settings export")?;
+
+        if export.export_version > EXPORT_VERSION {
+            bail!(
+                "Export is version {} but this version of Alembic only supports up to version {}. Please upgrade Alembic.",
+                export.export_version,
+                EXPORT_VERSION
+            );
+        }
+
+        Ok(export)
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Build an export of `settings`
+pub fn export_settings(
+    settings: &AlembicSettings,
+    options: ExportOptions,
+) -> anyhow::Result<SettingsExport> {
+    let accounts = settings
+        .accounts
+        .iter()
+        .map(|account| {
+            let password = if options.include_passwords {
+                Some(credentials::get_password(account)?)
+            } else {
+                None
+            };
+
+            Ok(ExportedAccount {
+                id: account.id.clone(),
+                server_id: account.server_id.clone(),
+                username: account.username.clone(),
+                password,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let (clients, profiles) = if options.skip_clients {
+        (vec![], vec![])
+    } else {
+        (settings.clients.clone(), settings.profiles.clone())
+    };
+
+    Ok(SettingsExport {
+        export_version: EXPORT_VERSION,
+        servers: settings.servers.clone(),
+        accounts,
+        clients,
+        profiles,
+    })
+}
+
+/// Save passwords returned by an import to `store`, returning the credential
+/// each account should now use
+pub fn save_imported_passwords(
+    passwords: &[(String, String)],
+    store: CredentialStoreKind,
+) -> anyhow::Result<Vec<(String, CredentialRef)>> {
+    passwords
+        .iter()
+        .map(|(account_id, password)| {
+            let credential = credentials::set_password(account_id, store, password)?;
+            Ok((account_id.clone(), credential))
+        })
+        .collect()
+}
+
+fn local_id(ids: &HashMap<String, String>, id: &str) -> String {
+    ids.get(id).cloned().unwrap_or_else(|| id.to_string())
+}
+
+impl AlembicSettings {
+    /// Merge an export into these settings.
+    ///
+    /// Entries are matched by ID first and then by what identifies them
+    /// (hostname and port, server and username, install path, DLL path or
+    /// profile name). Matched servers, accounts and profiles are updated from
+    /// the export. Matched clients keep their local paths and only gain DLLs
+    /// they don't have yet. Passwords aren't stored here; they are returned in
+    /// the summary instead.
+    pub fn merge_import(&mut self, import: &SettingsExport) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+
+        // Map IDs in the export to the IDs of the entries they matched here
+        let mut server_ids = HashMap::new();
+        let mut account_ids = HashMap::new();
+        let mut client_ids = HashMap::new();
+        let mut dll_ids = HashMap::new();
+
+        for server in &import.servers {
+            let existing = self
+                .servers
+                .iter()
+                .position(|s| s.id == server.id)
+                .or_else(|| {
+                    self.servers.iter().position(|s| {
+                        s.hostname.eq_ignore_ascii_case(&server.hostname) && s.port == server.port
+                    })
+                });
+
+            match existing {
+                Some(index) => {
+                    let local = &mut self.servers[index];
+                    local.name = server.name.clone();
+                    local.hostname = server.hostname.clone();
+                    local.port = server.port.clone();
+                    server_ids.insert(server.id.clone(), local.id.clone());
+                    summary.servers_updated += 1;
+                }
+                None => {
+                    self.servers.push(server.clone());
+                    summary.servers_added += 1;
+                }
+            }
+        }
+
+        for account in &import.accounts {
+            let server_id = local_id(&server_ids, &account.server_id);
+            let existing = self
+                .accounts
+                .iter()
+                .position(|a| a.id == account.id)
+                .or_else(|| {
+                    self.accounts
+                        .iter()
+                        .position(|a| a.server_id == server_id && a.username == account.username)
+                });
+
+            let id = match existing {
+                Some(index) => {
+                    let local = &mut self.accounts[index];
+                    local.server_id = server_id;
+                    local.username = account.username.clone();
+                    account_ids.insert(account.id.clone(), local.id.clone());
+                    summary.accounts_updated += 1;
+                    local.id.clone()
+                }
+                None => {
+                    // The password, if any, is saved by the caller
+                    self.accounts.push(Account {
+                        id: account.id.clone(),
+                        server_id,
+                        username: account.username.clone(),
+                        credential: CredentialStoreKind::default().empty_ref(),
+                    });
+                    summary.accounts_added += 1;
+                    account.id.clone()
+                }
+            };
+
+            if let Some(password) = &account.password {
+                summary.passwords.push((id, password.clone()));
+            }
+        }
+
+        for client in &import.clients {
+            let existing = self
+                .clients
+                .iter()
+                .position(|c| c.id() == client.id())
+                .or_else(|| self.clients.iter().position(|c| c.is_same_install(client)));
+
+            let Some(index) = existing else {
+                self.clients.push(client.clone());
+                summary.clients_added += 1;
+                continue;
+            };
+
+            let local = &mut self.clients[index];
+            client_ids.insert(client.id().to_string(), local.id().to_string());
+
+            for dll in client.dlls() {
+                let matched = local
+                    .dlls()
+                    .iter()
+                    .find(|d| d.id == dll.id || d.dll_path == dll.dll_path)
+                    .map(|d| d.id.clone());
+
+                match matched {
+                    Some(id) => {
+                        dll_ids.insert(dll.id.clone(), id);
+                    }
+                    None => local.dlls_mut().push(dll.clone()),
+                }
+            }
+            summary.clients_merged += 1;
+        }
+
+        for profile in &import.profiles {
+            let imported = LaunchProfile {
+                id: profile.id.clone(),
+                name: profile.name.clone(),
+                client_id: local_id(&client_ids, &profile.client_id),
+                dll_id: profile.dll_id.as_deref().map(|id| local_id(&dll_ids, id)),
+                server_id: local_id(&server_ids, &profile.server_id),
+                account_id: local_id(&account_ids, &profile.account_id),
+            };
+
+            let existing = self
+                .profiles
+                .iter()
+                .position(|p| p.id == profile.id || p.name == profile.name);
+
+            match existing {
+                Some(index) => {
+                    let id = self.profiles[index].id.clone();
+                    self.profiles[index] = LaunchProfile { id, ..imported };
+                    summary.profiles_updated += 1;
+                }
+                None => {
+                    self.profiles.push(imported);
+                    summary.profiles_added += 1;
+                }
+            }
+        }
+
+        summary
+    }
+
+    /// Point accounts at the credentials returned by `save_imported_passwords`
+    pub fn apply_imported_credentials(&mut self, credentials: &[(String, CredentialRef)]) {
+        for (account_id, credential) in credentials {
+            if let Some(account) = self.accounts.iter_mut().find(|a| &a.id == account_id) {
+                account.credential = credential.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, hostname: &str) -> ServerInfo {
+        ServerInfo {
+            id: id.to_string(),
+            name: hostname.to_string(),
+            hostname: hostname.to_string(),
+            port: "9000".to_string(),
+        }
+    }
+
+    #[test]
+    fn import_matches_existing_entries_instead_of_duplicating() {
+        let mut settings = AlembicSettings::new();
+        settings.servers = vec![server("local-server", "coldeve.ac")];
+        settings.accounts = vec![Account {
+            id: "local-account".to_string(),
+            server_id: "local-server".to_string(),
+            username: "alice".to_string(),
+            credential: CredentialRef::Keyring,
+        }];
+
+        let import = SettingsExport {
+            export_version: EXPORT_VERSION,
+            servers: vec![
+                server("other-server", "COLDEVE.AC"),
+                server("new-server", "levistras.ac"),
+            ],
+            accounts: vec![
+                ExportedAccount {
+                    id: "other-account".to_string(),
+                    server_id: "other-server".to_string(),
+                    username: "alice".to_string(),
+                    password: None,
+                },
+                ExportedAccount {
+                    id: "new-account".to_string(),
+                    server_id: "new-server".to_string(),
+                    username: "bob".to_string(),
+                    password: Some("secret".to_string()),
+                },
+            ],
+            clients: vec![],
+            profiles: vec![],
+        };
+
+        let summary = settings.merge_import(&import);
+
+        assert_eq!(summary.servers_updated, 1);
+        assert_eq!(summary.servers_added, 1);
+        assert_eq!(summary.accounts_updated, 1);
+        assert_eq!(summary.accounts_added, 1);
+        assert_eq!(settings.servers.len(), 2);
+        assert_eq!(settings.accounts.len(), 2);
+        // The matched account keeps its local ID and credential
+        assert_eq!(settings.accounts[0].id, "local-account");
+        assert_eq!(settings.accounts[0].credential, CredentialRef::Keyring);
+        assert_eq!(settings.accounts[1].server_id, "new-server");
+        assert_eq!(
+            summary.passwords,
+            vec![("new-account".to_string(), "secret".to_string())]
+        );
+
+        // Importing again changes nothing
+        let summary = settings.merge_import(&import);
+        assert_eq!(summary.servers_added + summary.accounts_added, 0);
+        assert_eq!(settings.accounts.len(), 2);
+    }
+}
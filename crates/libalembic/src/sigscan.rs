@@ -0,0 +1,286 @@
+//! Finding functions in acclient.exe by signature instead of by address.
+//!
+//! A signature is a byte pattern like `55 8B EC ?? 8B 0D`, where `??` matches
+//! any byte, usually covering addresses and offsets that change between
+//! builds. Patterns often match an instruction that refers to the function
+//! rather than the function itself, like a `call`, so a signature can also
+//! say how to follow the match to its target.
+//!
+//! Scanning works the same on a PE file on disk as on the client's memory,
+//! so signatures can be written and checked on any host before the DLL uses
+//! them.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{Context, Result, bail};
+
+use crate::pe::PeFile;
+
+/// A byte pattern, where `None` matches any byte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    bytes: Vec<Option<u8>>,
+}
+
+impl Pattern {
+    /// Parse a pattern of hex bytes separated by spaces, with `?` or `??`
+    /// for bytes that can be anything
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let bytes = pattern
+            .split_whitespace()
+            .map(|byte| match byte {
+                "?" | "??" => Ok(None),
+                _ if byte.len() == 2 && byte.chars().all(|c| c.is_ascii_hexdigit()) => {
+                    Ok(u8::from_str_radix(byte, 16).ok())
+                }
+                _ => bail!("'{}' isn't a hex byte or ??", byte),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if !bytes.iter().any(Option::is_some) {
+            bail!("A pattern needs at least one byte that isn't ??");
+        }
+
+        Ok(Self { bytes })
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn matches_at(&self, data: &[u8], offset: usize) -> bool {
+        data.get(offset..offset + self.bytes.len())
+            .is_some_and(|window| {
+                window
+                    .iter()
+                    .zip(&self.bytes)
+                    .all(|(byte, wanted)| wanted.is_none_or(|wanted| *byte == wanted))
+            })
+    }
+
+    /// Offsets of every match in `data`, in order
+    pub fn find_all(&self, data: &[u8]) -> Vec<usize> {
+        // Only look at offsets where the first fixed byte lines up
+        let Some((anchor, first)) = self
+            .bytes
+            .iter()
+            .enumerate()
+            .find_map(|(i, byte)| byte.map(|byte| (i, byte)))
+        else {
+            return vec![];
+        };
+
+        data.iter()
+            .enumerate()
+            .skip(anchor)
+            .filter(|&(_, &byte)| byte == first)
+            .map(|(i, _)| i - anchor)
+            .filter(|&offset| self.matches_at(data, offset))
+            .collect()
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes: Vec<String> = self
+            .bytes
+            .iter()
+            .map(|byte| match byte {
+                Some(byte) => format!("{:02X}", byte),
+                None => "??".to_string(),
+            })
+            .collect();
+        write!(f, "{}", bytes.join(" "))
+    }
+}
+
+/// How to get from a match to the address it refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolve {
+    /// A 32-bit displacement `offset` bytes into the match, relative to the
+    /// end of its instruction, like the operand of `E8 rel32` (call) with
+    /// `offset: 1, instruction_end: 5`
+    Relative {
+        offset: usize,
+        instruction_end: usize,
+    },
+    /// A 32-bit absolute address `offset` bytes into the match, like the
+    /// operand of `8B 0D abs32` (mov ecx, [abs32]) with `offset: 2`
+    Absolute { offset: usize },
+}
+
+impl Resolve {
+    fn target(self, data: &[u8], offset: usize, address: u64) -> Option<u64> {
+        let read = |at: usize| {
+            data.get(at..at + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+
+        match self {
+            Resolve::Relative {
+                offset: displacement,
+                instruction_end,
+            } => {
+                let displacement = read(offset + displacement)? as i32;
+                Some(
+                    address
+                        .wrapping_add(instruction_end as u64)
+                        .wrapping_add_signed(displacement as i64),
+                )
+            }
+            Resolve::Absolute {
+                offset: displacement,
+            } => read(offset + displacement).map(u64::from),
+        }
+    }
+}
+
+/// A pattern and, optionally, how to follow its matches
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub pattern: Pattern,
+    pub resolve: Option<Resolve>,
+}
+
+/// Where a signature matched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigMatch {
+    /// Address of the match, in the image's address space or in memory
+    pub address: u64,
+    /// Offset of the match in what was scanned: the file, for PE files
+    pub offset: usize,
+    /// Name of the section the match is in, for PE files
+    pub section: Option<String>,
+    /// Where the match refers to, if the signature says how to follow it
+    pub target: Option<u64>,
+}
+
+impl Signature {
+    pub fn new(pattern: Pattern) -> Self {
+        Self {
+            pattern,
+            resolve: None,
+        }
+    }
+
+    pub fn resolve(mut self, resolve: Resolve) -> Self {
+        self.resolve = Some(resolve);
+        self
+    }
+
+    /// Scan `data`, which is at address `base`, like a module mapped in the
+    /// client's memory
+    pub fn scan_memory(&self, data: &[u8], base: u64) -> Vec<SigMatch> {
+        self.pattern
+            .find_all(data)
+            .into_iter()
+            .map(|offset| {
+                let address = base + offset as u64;
+                SigMatch {
+                    address,
+                    offset,
+                    section: None,
+                    target: self
+                        .resolve
+                        .and_then(|resolve| resolve.target(data, offset, address)),
+                }
+            })
+            .collect()
+    }
+
+    /// Scan a section of a PE file, or all of its code sections if no
+    /// section is named. Addresses are where the image would be loaded.
+    pub fn scan_pe(&self, pe: &PeFile, section: Option<&str>) -> Result<Vec<SigMatch>> {
+        let sections: Vec<_> = match section {
+            Some(name) => vec![
+                pe.section(name)
+                    .with_context(|| format!("There's no {} section", name))?,
+            ],
+            None => pe.sections().iter().filter(|s| s.is_executable()).collect(),
+        };
+
+        let mut matches = vec![];
+        for section in sections {
+            let base = pe.image_base() + section.virtual_address as u64;
+            matches.extend(
+                self.scan_memory(pe.section_data(section), base)
+                    .into_iter()
+                    .map(|m| SigMatch {
+                        offset: m.offset + section.raw_offset as usize,
+                        section: Some(section.name.clone()),
+                        ..m
+                    }),
+            );
+        }
+
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pe::tests::build_pe;
+
+    #[test]
+    fn finds_every_match_and_follows_it() {
+        let pattern = Pattern::parse("E8 ?? ?? ?? ?? 8B 0D").expect("pattern should parse");
+        assert_eq!(pattern.len(), 7);
+        assert_eq!(pattern.to_string(), "E8 ?? ?? ?? ?? 8B 0D");
+        assert!(Pattern::parse("?? ??").is_err());
+        assert!(Pattern::parse("E8 1").is_err());
+        assert!(Pattern::parse("E8 GG").is_err());
+        assert!(Pattern::parse("E8 +1").is_err());
+
+        // Two calls: one 0x10 bytes forward, one 0x20 bytes back
+        let mut data = vec![0x90u8; 0x40];
+        data[0x08..0x0f].copy_from_slice(&[0xe8, 0x10, 0, 0, 0, 0x8b, 0x0d]);
+        data[0x30..0x37].copy_from_slice(&[0xe8, 0xe0, 0xff, 0xff, 0xff, 0x8b, 0x0d]);
+
+        let signature = Signature::new(pattern.clone()).resolve(Resolve::Relative {
+            offset: 1,
+            instruction_end: 5,
+        });
+        let matches = signature.scan_memory(&data, 0x1000);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.address, m.target))
+                .collect::<Vec<_>>(),
+            vec![
+                (0x1008, Some(0x1008 + 5 + 0x10)),
+                (0x1030, Some(0x1030 + 5 - 0x20)),
+            ]
+        );
+
+        // The same bytes in a PE file's code section
+        let mut pe = build_pe(0x014c, &[], None);
+        pe[0x300..0x307].copy_from_slice(&[0xe8, 0x10, 0, 0, 0, 0x8b, 0x0d]);
+        let pe = PeFile::parse(pe).expect("pe should parse");
+        let matches = Signature::new(pattern)
+            .resolve(Resolve::Absolute { offset: 1 })
+            .scan_pe(&pe, None)
+            .expect("pe should be scanned");
+        assert_eq!(
+            matches,
+            vec![SigMatch {
+                address: 0x0040_0000 + 0x1100,
+                offset: 0x300,
+                section: Some(".text".to_string()),
+                target: Some(0x10),
+            }]
+        );
+    }
+}
@@ -1,97 +1,193 @@
 //! Validation utilities for launch configurations.
 //!
 //! This module provides functions to validate that paths exist before launching,
-//! with special handling for Wine where Windows paths need to be checked via Wine.
+//! with special handling for Wine where Windows paths are resolved through the
+//! prefix's drive mappings.
 
-use std::collections::HashMap;
-use std::path::Path;
-use std::process::Command;
+use std::{fmt, path::Path};
 
-/// Result of validating a launch configuration
-#[derive(Debug)]
-pub struct ValidationResult {
-    pub is_valid: bool,
-    pub errors: Vec<String>,
+use crate::wine_paths::windows_to_unix_path;
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// A check passed, or something worth knowing that isn't a problem
+    Info,
+    /// Launching may work, but something looks wrong
+    Warning,
+    /// Launching will fail until this is fixed
+    Error,
 }
 
-impl ValidationResult {
-    pub fn ok() -> Self {
-        Self {
-            is_valid: true,
-            errors: vec![],
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "ok"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
         }
     }
+}
+
+/// One result of checking a launch configuration or the setup around it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Stable identifier for the kind of finding, like `client-missing`
+    pub code: &'static str,
+    /// What was checked, like "Client executable"
+    pub subject: String,
+    pub message: String,
+    /// What the user can do about it
+    pub fix: Option<String>,
+}
 
-    pub fn error(msg: impl Into<String>) -> Self {
+impl Finding {
+    pub fn new(
+        severity: Severity,
+        code: &'static str,
+        subject: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
         Self {
-            is_valid: false,
-            errors: vec![msg.into()],
+            severity,
+            code,
+            subject: subject.into(),
+            message: message.into(),
+            fix: None,
         }
     }
 
-    pub fn add_error(&mut self, msg: impl Into<String>) {
-        self.is_valid = false;
-        self.errors.push(msg.into());
+    pub fn info(
+        code: &'static str,
+        subject: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::new(Severity::Info, code, subject, message)
+    }
+
+    pub fn warning(
+        code: &'static str,
+        subject: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::new(Severity::Warning, code, subject, message)
+    }
+
+    pub fn error(
+        code: &'static str,
+        subject: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::new(Severity::Error, code, subject, message)
+    }
+
+    pub fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.subject, self.message)?;
+        if let Some(fix) = &self.fix {
+            write!(f, " ({})", fix)?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of validating a launch configuration
+#[derive(Debug, Default, Clone)]
+pub struct ValidationResult {
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationResult {
+    pub fn ok() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, finding: Finding) {
+        self.findings.push(finding);
     }
 
     pub fn merge(&mut self, other: ValidationResult) {
-        if !other.is_valid {
-            self.is_valid = false;
+        self.findings.extend(other.findings);
+    }
+
+    /// Whether nothing was found that would stop a launch
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == Severity::Error)
+    }
+
+    /// The most serious finding's severity, if there are any findings
+    pub fn worst(&self) -> Option<Severity> {
+        self.findings.iter().map(|finding| finding.severity).max()
+    }
+}
+
+impl From<Finding> for ValidationResult {
+    fn from(finding: Finding) -> Self {
+        Self {
+            findings: vec![finding],
         }
-        self.errors.extend(other.errors);
     }
 }
 
 /// Check if a native (Unix) path exists
-pub fn validate_native_path(path: &Path, description: &str) -> ValidationResult {
+pub fn validate_native_path(
+    path: &Path,
+    code: &'static str,
+    description: &str,
+) -> ValidationResult {
     if path.exists() {
-        ValidationResult::ok()
+        Finding::info(code, description, format!("found at {}", path.display())).into()
     } else {
-        ValidationResult::error(format!("{} not found: {}", description, path.display()))
+        Finding::error(code, description, format!("not found: {}", path.display()))
+            .with_fix("Update the path in settings, or scan for clients again")
+            .into()
     }
 }
 
-/// Check if a Windows path exists under Wine.
+/// Check if a Windows path exists in a Wine prefix.
 ///
-/// This runs `wine cmd /c type "path"` and checks the exit code.
-/// Returns success if the file exists, error if it doesn't.
+/// The path is resolved through the prefix's `dosdevices` drive mappings, so
+/// this works for any launcher (including Flatpak) without starting Wine.
 pub fn validate_wine_path(
-    wine_executable: &Path,
+    prefix: &Path,
     windows_path: &Path,
-    env: &HashMap<String, String>,
+    code: &'static str,
     description: &str,
 ) -> ValidationResult {
-    // Convert backslashes to forward slashes - Wine's cmd.exe handles forward slashes
-    // correctly, and this avoids issues with shell escaping and path interpretation
-    let path_str = windows_path.to_string_lossy().replace('\\', "/");
-
-    // Use "type" command and check exit code - it returns 0 if file exists, 1 if not.
-    // Pass command as separate arguments to avoid shell quoting issues.
-    let mut cmd = Command::new(wine_executable);
-    cmd.arg("cmd")
-        .arg("/c")
-        .arg("type")
-        .arg(&path_str)
-        .envs(env)
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null());
-
-    match cmd.status() {
-        Ok(status) => {
-            if status.success() {
-                ValidationResult::ok()
-            } else {
-                ValidationResult::error(format!(
-                    "{} not found (Wine path): {}",
-                    description,
-                    windows_path.display()
-                ))
-            }
-        }
-        Err(e) => ValidationResult::error(format!(
-            "Failed to run Wine to validate {}: {}",
-            description, e
-        )),
+    match windows_to_unix_path(prefix, windows_path) {
+        Ok(unix_path) if unix_path.exists() => Finding::info(
+            code,
+            description,
+            format!("found at {}", unix_path.display()),
+        )
+        .into(),
+        Ok(unix_path) => Finding::error(
+            code,
+            description,
+            format!(
+                "not found (Wine path): {} ({})",
+                windows_path.display(),
+                unix_path.display()
+            ),
+        )
+        .with_fix("Update the path in settings, or scan for clients again")
+        .into(),
+        Err(e) => Finding::error(code, description, format!("couldn't be resolved: {}", e))
+            .with_fix("Use a path on a drive the Wine prefix has, like C:\\")
+            .into(),
     }
 }
 
@@ -117,12 +213,18 @@ mod tests {
     #[test]
     fn test_validate_native_path() {
         // Current directory should exist
-        let result = validate_native_path(Path::new("."), "Current dir");
-        assert!(result.is_valid);
+        let result = validate_native_path(Path::new("."), "test-path", "Current dir");
+        assert!(result.is_valid());
 
         // Non-existent path
-        let result = validate_native_path(Path::new("/nonexistent/path/12345"), "Test path");
-        assert!(!result.is_valid);
-        assert!(result.errors[0].contains("not found"));
+        let result = validate_native_path(
+            Path::new("/nonexistent/path/12345"),
+            "test-path",
+            "Test path",
+        );
+        assert!(!result.is_valid());
+        let error = result.errors().next().expect("missing path is an error");
+        assert_eq!(error.code, "test-path");
+        assert!(error.message.contains("not found"));
     }
 }
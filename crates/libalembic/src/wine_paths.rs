@@ -0,0 +1,253 @@
+//! Mapping between Windows paths in a Wine prefix and host paths.
+//!
+//! Wine keeps a symlink per drive in `$WINEPREFIX/dosdevices`, like
+//! `c: -> ../drive_c` and `z: -> /`, so reading those gives the same answer
+//! Wine would without starting it. Wine matches path components
+//! case-insensitively against the host filesystem, so resolving does too.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Result, bail};
+
+/// The drives of a Wine prefix and the host directories they map to
+#[derive(Debug, Clone)]
+pub struct DosDevices {
+    /// Lowercase drive letters and their host directories
+    drives: Vec<(char, PathBuf)>,
+}
+
+impl DosDevices {
+    /// Read the drive mappings of a prefix. Prefixes that haven't been
+    /// started yet have no `dosdevices`, so `C:` falls back to `drive_c`.
+    pub fn load(prefix: &Path) -> Self {
+        let dosdevices = prefix.join("dosdevices");
+        let mut drives = vec![];
+
+        if let Ok(entries) = fs::read_dir(&dosdevices) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_lowercase();
+                let mut chars = name.chars();
+                let (Some(letter), Some(':'), None) = (chars.next(), chars.next(), chars.next())
+                else {
+                    continue;
+                };
+                if !letter.is_ascii_alphabetic() {
+                    continue;
+                }
+
+                // Relative targets like ../drive_c are relative to dosdevices
+                if let Ok(target) = fs::read_link(entry.path()) {
+                    drives.push((letter, dosdevices.join(target)));
+                }
+            }
+        }
+
+        if !drives.iter().any(|(letter, _)| *letter == 'c') {
+            drives.push(('c', prefix.join("drive_c")));
+        }
+        drives.sort();
+
+        Self { drives }
+    }
+
+    /// The host directory a drive letter maps to
+    pub fn drive_root(&self, letter: char) -> Option<&Path> {
+        let letter = letter.to_ascii_lowercase();
+        self.drives
+            .iter()
+            .find(|(l, _)| *l == letter)
+            .map(|(_, root)| root.as_path())
+    }
+
+    /// Resolve a Windows path like `D:\Games\acclient.exe` to a host path.
+    ///
+    /// Each component is matched case-insensitively against what exists on
+    /// disk. Components that don't exist are kept as written, so the result
+    /// can be checked with `exists()`.
+    pub fn to_unix(&self, windows_path: &Path) -> Result<PathBuf> {
+        let path_str = windows_path.to_string_lossy();
+        let mut chars = path_str.chars();
+        let (Some(letter), Some(':')) = (chars.next(), chars.next()) else {
+            bail!(
+                "Path does not start with a drive letter: {}",
+                windows_path.display()
+            );
+        };
+        let Some(root) = self.drive_root(letter) else {
+            bail!(
+                "Drive {}: is not mapped in this Wine prefix",
+                letter.to_ascii_uppercase()
+            );
+        };
+
+        let rest = chars.as_str();
+        let mut resolved = root.to_path_buf();
+        for component in rest.split(['\\', '/']).filter(|c| !c.is_empty()) {
+            resolved = match component {
+                "." => resolved,
+                ".." => {
+                    if resolved != root {
+                        resolved.pop();
+                    }
+                    resolved
+                }
+                _ => resolve_component(&resolved, component),
+            };
+        }
+
+        Ok(resolved)
+    }
+
+    /// Find the Windows path of a host path, using the drive whose directory
+    /// contains it most closely. Paths outside the prefix's drive directories
+    /// are usually reachable through `Z:`.
+    pub fn to_windows(&self, unix_path: &Path) -> Result<PathBuf> {
+        let path = canonical(unix_path);
+
+        let best = self
+            .drives
+            .iter()
+            .filter_map(|(letter, root)| {
+                let relative = path.strip_prefix(canonical(root)).ok()?;
+                Some((*letter, relative.to_path_buf()))
+            })
+            .min_by_key(|(letter, relative)| (relative.components().count(), *letter));
+
+        let Some((letter, relative)) = best else {
+            bail!(
+                "Path is not on any drive of the Wine prefix: {}",
+                unix_path.display()
+            );
+        };
+
+        let components: Vec<_> = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+
+        Ok(PathBuf::from(format!(
+            "{}:\\{}",
+            letter.to_ascii_uppercase(),
+            components.join("\\")
+        )))
+    }
+}
+
+/// Join `component` onto `dir`, using the existing entry's spelling if one
+/// matches case-insensitively
+fn resolve_component(dir: &Path, component: &str) -> PathBuf {
+    let exact = dir.join(component);
+    if exact.exists() {
+        return exact;
+    }
+
+    let folded = component.to_lowercase();
+    fs::read_dir(dir)
+        .ok()
+        .and_then(|entries| {
+            entries
+                .flatten()
+                .find(|entry| entry.file_name().to_string_lossy().to_lowercase() == folded)
+        })
+        .map(|entry| entry.path())
+        .unwrap_or(exact)
+}
+
+/// Canonicalize as much of `path` as exists, so symlinked prefixes and
+/// drive mappings compare equal to the paths they point to
+fn canonical(path: &Path) -> PathBuf {
+    if let Ok(path) = fs::canonicalize(path) {
+        return path;
+    }
+
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => canonical(parent).join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Resolve a Windows path in a Wine prefix to a host path
+pub fn windows_to_unix_path(prefix: &Path, windows_path: &Path) -> Result<PathBuf> {
+    DosDevices::load(prefix).to_unix(windows_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn resolves_mapped_drives_and_folds_case() {
+        use std::os::unix::fs::symlink;
+
+        let root = TempDir::new("wine-paths");
+        let prefix = root.join("prefix");
+        let games = root.join("games");
+        let client_dir = prefix.join("drive_c/Turbine/Asheron's Call");
+        fs::create_dir_all(&client_dir).expect("client dir should be created");
+        fs::create_dir_all(prefix.join("dosdevices")).expect("dosdevices should be created");
+        fs::create_dir_all(games.join("Decal")).expect("games dir should be created");
+        fs::write(client_dir.join("acclient.exe"), b"").expect("client should be written");
+        fs::write(games.join("Decal/Inject.dll"), b"").expect("dll should be written");
+        symlink("../drive_c", prefix.join("dosdevices/c:")).expect("c: should be linked");
+        symlink(&games, prefix.join("dosdevices/d:")).expect("d: should be linked");
+        symlink("/", prefix.join("dosdevices/z:")).expect("z: should be linked");
+
+        let devices = DosDevices::load(&prefix);
+
+        let client = devices
+            .to_unix(Path::new(r"c:\TURBINE\asheron's call\ACCLIENT.EXE"))
+            .expect("c: should resolve");
+        assert_eq!(
+            fs::canonicalize(&client).ok(),
+            fs::canonicalize(client_dir.join("acclient.exe")).ok()
+        );
+        assert_eq!(
+            devices
+                .to_unix(Path::new(r"D:\decal\inject.dll"))
+                .expect("d: should resolve"),
+            games.join("Decal/Inject.dll")
+        );
+        assert!(
+            !devices
+                .to_unix(Path::new(r"C:\Turbine\missing.exe"))
+                .expect("missing files still resolve")
+                .exists()
+        );
+        assert!(devices.to_unix(Path::new(r"Q:\anything")).is_err());
+
+        assert_eq!(
+            devices
+                .to_windows(&client_dir.join("acclient.exe"))
+                .expect("client is on c:"),
+            PathBuf::from(r"C:\Turbine\Asheron's Call\acclient.exe")
+        );
+        assert_eq!(
+            devices
+                .to_windows(&games.join("Decal/Inject.dll"))
+                .expect("dll is on d:"),
+            PathBuf::from(r"D:\Decal\Inject.dll")
+        );
+        assert_eq!(
+            devices
+                .to_windows(Path::new("/etc"))
+                .expect("everything is on z:"),
+            PathBuf::from(r"Z:\etc")
+        );
+
+        // Prefixes that haven't been started yet only have drive_c
+        let fresh = DosDevices::load(&root.join("fresh"));
+        assert_eq!(
+            fresh.drive_root('C'),
+            Some(root.join("fresh/drive_c").as_path())
+        );
+        assert!(fresh.drive_root('z').is_none());
+    }
+}
@@ -0,0 +1,649 @@
+//! Reading and editing the text registry files in a Wine prefix (`system.reg`,
+//! `user.reg` and `userdef.reg`).
+//!
+//! Files are kept line by line, so saving writes back exactly what was read
+//! apart from the values that were changed. Key timestamps, `#time` lines and
+//! comments are left alone. Wine rewrites these files when a prefix's
+//! wineserver exits, so only edit them while nothing in the prefix is running.
+//!
+//! Key paths are relative to the file's root, which is `HKEY_LOCAL_MACHINE`
+//! for `system.reg` and `HKEY_CURRENT_USER` for `user.reg`, and are matched
+//! without regard to case like Windows does.
+
+use std::{fmt, fs, path::Path};
+
+use anyhow::{Context, bail};
+
+use crate::settings::persist;
+
+/// Lines longer than this are wrapped when writing hex data, like Wine does
+const HEX_LINE_WIDTH: usize = 76;
+
+/// A registry value's data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegValue {
+    /// `REG_SZ`
+    String(String),
+    /// `REG_EXPAND_SZ`, which may refer to environment variables
+    ExpandString(String),
+    /// `REG_MULTI_SZ`
+    MultiString(Vec<String>),
+    /// `REG_DWORD`
+    Dword(u32),
+    /// `REG_BINARY`
+    Binary(Vec<u8>),
+    /// Any other type, stored as raw bytes
+    Other { kind: u32, data: Vec<u8> },
+}
+
+impl RegValue {
+    /// The value as a string, for string and expandable string values
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            RegValue::String(s) | RegValue::ExpandString(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_dword(&self) -> Option<u32> {
+        match self {
+            RegValue::Dword(d) => Some(*d),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum KeyLine {
+    Value {
+        name: String,
+        value: RegValue,
+        /// The value's text as read, which may span several lines. `None`
+        /// once the value has been changed.
+        raw: Option<String>,
+    },
+    /// Options like `#time=`, comments, blank lines and values we can't parse
+    Other(String),
+}
+
+/// A key and the lines that follow its header
+#[derive(Debug, Clone)]
+pub struct RegistryKey {
+    path: String,
+    header: String,
+    lines: Vec<KeyLine>,
+}
+
+/// A parsed Wine registry file
+#[derive(Debug, Clone)]
+pub struct RegistryFile {
+    /// Everything before the first key, like the version line and `#arch`
+    preamble: Vec<String>,
+    keys: Vec<RegistryKey>,
+}
+
+/// Read a string up to an unescaped `delim`, returning it and what follows
+/// the delimiter. Handles the escapes Wine writes and reads.
+fn read_escaped(s: &str, delim: char) -> anyhow::Result<(String, &str)> {
+    let mut out = String::new();
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if c == delim {
+            return Ok((out, &s[i + c.len_utf8()..]));
+        }
+
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        let Some((_, escaped)) = chars.next() else {
+            break;
+        };
+
+        let code = match escaped {
+            'a' => 0x07,
+            'b' => 0x08,
+            'e' => 0x1b,
+            'f' => 0x0c,
+            'n' => 0x0a,
+            'r' => 0x0d,
+            't' => 0x09,
+            'v' => 0x0b,
+            'x' => {
+                let mut code = 0;
+                for _ in 0..4 {
+                    match chars.peek().and_then(|(_, c)| c.to_digit(16)) {
+                        Some(digit) => {
+                            code = code * 16 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                code
+            }
+            '0'..='7' => {
+                let mut code = escaped.to_digit(8).unwrap_or_default();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|(_, c)| c.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                code
+            }
+            other => other as u32,
+        };
+        out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+    }
+
+    bail!("Missing closing '{}'", delim)
+}
+
+/// Escape a string the way Wine writes it, ending at `delim`
+fn escape(s: &str, delim: char) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let named = match c {
+            '\x07' => Some('a'),
+            '\x08' => Some('b'),
+            '\t' => Some('t'),
+            '\n' => Some('n'),
+            '\x0b' => Some('v'),
+            '\x0c' => Some('f'),
+            '\r' => Some('r'),
+            '\x1b' => Some('e'),
+            _ => None,
+        };
+
+        if let Some(named) = named {
+            out.push('\\');
+            out.push(named);
+        } else if c == '\\' || c == delim {
+            out.push('\\');
+            out.push(c);
+        } else if (c as u32) < 0x20 || (c as u32) > 0x7e {
+            // Pad the code when a hex digit follows so it isn't read as part of it
+            let pad = chars.peek().is_some_and(|next| next.is_ascii_hexdigit());
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                if pad {
+                    out.push_str(&format!("\\x{:04x}", unit));
+                } else {
+                    out.push_str(&format!("\\x{:x}", unit));
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+
+    out
+}
+
+fn parse_hex(data: &str) -> anyhow::Result<Vec<u8>> {
+    data.split(',')
+        .map(|byte| byte.trim_matches(|c: char| c == '\\' || c.is_whitespace()))
+        .filter(|byte| !byte.is_empty())
+        .map(|byte| u8::from_str_radix(byte, 16).with_context(|| format!("Bad hex byte {}", byte)))
+        .collect()
+}
+
+fn utf16_bytes(s: &str) -> Vec<u8> {
+    s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+/// Parse a value line (and any continuation lines) into its name and data
+fn parse_value(raw: &str) -> anyhow::Result<(String, RegValue)> {
+    let (name, rest) = if let Some(rest) = raw.strip_prefix('@') {
+        (String::new(), rest)
+    } else if let Some(rest) = raw.strip_prefix('"') {
+        read_escaped(rest, '"')?
+    } else {
+        bail!("Not a value");
+    };
+
+    let Some(data) = rest.trim_start().strip_prefix('=') else {
+        bail!("Missing '=' after value name");
+    };
+    let data = data.trim_start().trim_end_matches('\r');
+
+    let value = if let Some(quoted) = data.strip_prefix('"') {
+        RegValue::String(read_escaped(quoted, '"')?.0)
+    } else if let Some(rest) = data.strip_prefix("str(") {
+        let (kind, rest) = rest.split_once("):\"").context("Bad str() value")?;
+        let kind = u32::from_str_radix(kind, 16)?;
+        let s = read_escaped(rest, '"')?.0;
+
+        match kind {
+            1 => RegValue::String(s),
+            2 => RegValue::ExpandString(s),
+            7 => {
+                let items = s.trim_end_matches('\0');
+                RegValue::MultiString(if items.is_empty() {
+                    vec![]
+                } else {
+                    items.split('\0').map(str::to_string).collect()
+                })
+            }
+            kind => RegValue::Other {
+                kind,
+                data: utf16_bytes(&s),
+            },
+        }
+    } else if let Some(hex) = data.strip_prefix("dword:") {
+        RegValue::Dword(u32::from_str_radix(hex.trim(), 16)?)
+    } else if let Some(hex) = data.strip_prefix("hex:") {
+        RegValue::Binary(parse_hex(hex)?)
+    } else if let Some(rest) = data.strip_prefix("hex(") {
+        let (kind, hex) = rest.split_once("):").context("Bad hex() value")?;
+        RegValue::Other {
+            kind: u32::from_str_radix(kind, 16)?,
+            data: parse_hex(hex)?,
+        }
+    } else {
+        bail!("Unknown value type");
+    };
+
+    Ok((name, value))
+}
+
+/// Write `data` as comma-separated hex after `line`, wrapping like Wine
+fn push_hex(line: &mut String, data: &[u8]) {
+    let mut width = line.len();
+
+    for (i, byte) in data.iter().enumerate() {
+        line.push_str(&format!("{:02x}", byte));
+        width += 2;
+
+        if i + 1 < data.len() {
+            line.push(',');
+            width += 1;
+            if width > HEX_LINE_WIDTH {
+                line.push_str("\\\n  ");
+                width = 2;
+            }
+        }
+    }
+}
+
+fn format_value(name: &str, value: &RegValue) -> String {
+    let mut line = if name.is_empty() {
+        "@=".to_string()
+    } else {
+        format!("\"{}\"=", escape(name, '"'))
+    };
+
+    match value {
+        RegValue::String(s) => line.push_str(&format!("\"{}\"", escape(s, '"'))),
+        RegValue::ExpandString(s) => line.push_str(&format!("str(2):\"{}\"", escape(s, '"'))),
+        RegValue::MultiString(items) => {
+            let joined: String = items.iter().map(|item| format!("{}\0", item)).collect();
+            line.push_str(&format!("str(7):\"{}\"", escape(&joined, '"')));
+        }
+        RegValue::Dword(d) => line.push_str(&format!("dword:{:08x}", d)),
+        RegValue::Binary(data) => {
+            line.push_str("hex:");
+            push_hex(&mut line, data);
+        }
+        RegValue::Other { kind, data } => {
+            line.push_str(&format!("hex({:x}):", kind));
+            push_hex(&mut line, data);
+        }
+    }
+
+    line
+}
+
+/// Normalize a key path for comparison: single backslashes, no leading or
+/// trailing separators, lowercase
+fn normalize_path(path: &str) -> String {
+    path.split('\\')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\\")
+        .to_lowercase()
+}
+
+impl RegistryKey {
+    fn parse_header(line: &str) -> anyhow::Result<Self> {
+        let rest = line.strip_prefix('[').context("Not a key")?;
+        let (path, _timestamp) = read_escaped(rest, ']')?;
+
+        Ok(Self {
+            path,
+            header: line.to_string(),
+            lines: vec![],
+        })
+    }
+
+    /// The key's path relative to the file's root, like `Software\Decal`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The last part of the key's path
+    pub fn name(&self) -> &str {
+        self.path.rsplit('\\').next().unwrap_or(&self.path)
+    }
+
+    /// When the key was last written, in seconds since the Unix epoch
+    pub fn timestamp(&self) -> Option<u64> {
+        let (_, rest) = self.header.rsplit_once(']')?;
+        rest.trim().parse().ok()
+    }
+
+    /// Read a value. The default value is named `""`.
+    pub fn value(&self, name: &str) -> Option<&RegValue> {
+        self.values()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// All values, in file order
+    pub fn values(&self) -> impl Iterator<Item = (&str, &RegValue)> {
+        self.lines.iter().filter_map(|line| match line {
+            KeyLine::Value { name, value, .. } => Some((name.as_str(), value)),
+            KeyLine::Other(_) => None,
+        })
+    }
+
+    /// Add or replace a value. Replaced values keep their place in the file.
+    pub fn set_value(&mut self, name: &str, value: RegValue) {
+        for line in &mut self.lines {
+            if let KeyLine::Value {
+                name: existing,
+                value: old,
+                raw,
+            } = line
+                && existing.eq_ignore_ascii_case(name)
+            {
+                if *old != value {
+                    *old = value;
+                    *raw = None;
+                }
+                return;
+            }
+        }
+
+        // Add it before the blank lines that separate this key from the next
+        let index = self
+            .lines
+            .iter()
+            .rposition(|line| !matches!(line, KeyLine::Other(s) if s.trim().is_empty()))
+            .map_or(0, |i| i + 1);
+        self.lines.insert(
+            index,
+            KeyLine::Value {
+                name: name.to_string(),
+                value,
+                raw: None,
+            },
+        );
+    }
+
+    /// Remove a value, returning its data
+    pub fn remove_value(&mut self, name: &str) -> Option<RegValue> {
+        let index = self.lines.iter().position(
+            |line| matches!(line, KeyLine::Value { name: n, .. } if n.eq_ignore_ascii_case(name)),
+        )?;
+
+        match self.lines.remove(index) {
+            KeyLine::Value { value, .. } => Some(value),
+            KeyLine::Other(_) => None,
+        }
+    }
+}
+
+impl RegistryFile {
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        let mut preamble = vec![];
+        let mut keys: Vec<RegistryKey> = vec![];
+
+        let contents = contents.strip_suffix('\n').unwrap_or(contents);
+        let mut lines = contents.split('\n').enumerate();
+
+        while let Some((number, line)) = lines.next() {
+            if line.starts_with('[') {
+                let key = RegistryKey::parse_header(line)
+                    .with_context(|| format!("Bad key on line {}", number + 1))?;
+                keys.push(key);
+                continue;
+            }
+
+            let Some(key) = keys.last_mut() else {
+                preamble.push(line.to_string());
+                continue;
+            };
+
+            if !(line.starts_with('"') || line.starts_with('@')) {
+                key.lines.push(KeyLine::Other(line.to_string()));
+                continue;
+            }
+
+            // Long hex values continue on the next line after a backslash
+            let mut raw = line.to_string();
+            while raw.trim_end().ends_with('\\') {
+                let Some((_, next)) = lines.next() else {
+                    break;
+                };
+                raw.push('\n');
+                raw.push_str(next);
+            }
+
+            // Keep values we don't understand as they are
+            match parse_value(&raw) {
+                Ok((name, value)) => key.lines.push(KeyLine::Value {
+                    name,
+                    value,
+                    raw: Some(raw),
+                }),
+                Err(_) => key.lines.push(KeyLine::Other(raw)),
+            }
+        }
+
+        Ok(Self { preamble, keys })
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        persist::write_atomic(path, &self.to_string())
+    }
+
+    /// Look up a key by path, like `Software\Decal\Agent`
+    pub fn key(&self, path: &str) -> Option<&RegistryKey> {
+        let path = normalize_path(path);
+        self.keys
+            .iter()
+            .find(|key| normalize_path(&key.path) == path)
+    }
+
+    pub fn key_mut(&mut self, path: &str) -> Option<&mut RegistryKey> {
+        let path = normalize_path(path);
+        self.keys
+            .iter_mut()
+            .find(|key| normalize_path(&key.path) == path)
+    }
+
+    /// All keys, in file order
+    pub fn keys(&self) -> impl Iterator<Item = &RegistryKey> {
+        self.keys.iter()
+    }
+
+    /// Keys directly under `path`, in file order
+    pub fn subkeys(&self, path: &str) -> impl Iterator<Item = &RegistryKey> {
+        let parent = normalize_path(path);
+        self.keys.iter().filter(move |key| {
+            normalize_path(&key.path)
+                .rsplit_once('\\')
+                .is_some_and(|(dir, _)| dir == parent)
+        })
+    }
+
+    /// Get a key, adding it to the end of the file if it doesn't exist.
+    /// Wine creates missing parent keys when it loads the file.
+    pub fn create_key(&mut self, path: &str) -> &mut RegistryKey {
+        if let Some(index) = self
+            .keys
+            .iter()
+            .position(|key| normalize_path(&key.path) == normalize_path(path))
+        {
+            return &mut self.keys[index];
+        }
+
+        let path = path.trim_matches('\\').to_string();
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        // Keys are separated by a blank line
+        if let Some(last) = self.keys.last_mut()
+            && !matches!(last.lines.last(), Some(KeyLine::Other(s)) if s.is_empty())
+        {
+            last.lines.push(KeyLine::Other(String::new()));
+        }
+
+        self.keys.push(RegistryKey {
+            header: format!("[{}] {}", escape(&path, ']'), timestamp),
+            path,
+            lines: vec![],
+        });
+        self.keys.last_mut().expect("a key was just added")
+    }
+
+    /// Read a value from a key
+    pub fn value(&self, key: &str, name: &str) -> Option<&RegValue> {
+        self.key(key)?.value(name)
+    }
+
+    /// The prefix architecture from the `#arch=` line, `win32` or `win64`
+    pub fn arch(&self) -> Option<&str> {
+        self.preamble
+            .iter()
+            .find_map(|line| line.strip_prefix("#arch="))
+            .map(str::trim)
+    }
+}
+
+impl fmt::Display for RegistryFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.preamble {
+            writeln!(f, "{}", line)?;
+        }
+
+        for key in &self.keys {
+            writeln!(f, "{}", key.header)?;
+
+            for line in &key.lines {
+                match line {
+                    KeyLine::Value { raw: Some(raw), .. } => writeln!(f, "{}", raw)?,
+                    KeyLine::Value { name, value, .. } => {
+                        writeln!(f, "{}", format_value(name, value))?
+                    }
+                    KeyLine::Other(line) => writeln!(f, "{}", line)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYSTEM_REG: &str = r#"WINE REGISTRY Version 2
+;; All keys relative to \\Machine
+
+#arch=win32
+
+[Software\\Decal\\Agent] 1700000000
+#time=1da1b2c3d4e5f60
+"AgentPath"="C:\\Program Files\\Decal 3.0\\"
+"Flags"=dword:0000000a
+@="Decal \"Agent\""
+
+[Software\\Turbine\\AC] 1600000000
+"Blob"=hex:00,01,02,03,04,05,06,07,08,09,0a,0b,0c,0d,0e,0f,10,11,12,13,14,15,16,\
+  17,18,19
+"Paths"=str(7):"C:\\one\0C:\\two\0"
+"Home"=str(2):"%USERPROFILE%\\AC"
+"#;
+
+    #[test]
+    fn registry_round_trips_and_edits_in_place() {
+        let mut registry = RegistryFile::parse(SYSTEM_REG).expect("registry should parse");
+        assert_eq!(registry.to_string(), SYSTEM_REG);
+
+        let agent = registry.key("software\\decal\\agent").expect("key exists");
+        assert_eq!(agent.timestamp(), Some(1700000000));
+        assert_eq!(
+            agent.value("AgentPath").and_then(RegValue::as_str),
+            Some(r"C:\Program Files\Decal 3.0\")
+        );
+        assert_eq!(agent.value("flags").and_then(RegValue::as_dword), Some(10));
+        assert_eq!(
+            agent.value("").and_then(RegValue::as_str),
+            Some("Decal \"Agent\"")
+        );
+
+        assert_eq!(
+            registry.value(r"Software\Turbine\AC", "Blob"),
+            Some(&RegValue::Binary((0..26).collect()))
+        );
+        assert_eq!(
+            registry.value(r"Software\Turbine\AC", "Paths"),
+            Some(&RegValue::MultiString(vec![
+                r"C:\one".to_string(),
+                r"C:\two".to_string()
+            ]))
+        );
+        assert_eq!(
+            registry.value(r"Software\Turbine\AC", "Home"),
+            Some(&RegValue::ExpandString(r"%USERPROFILE%\AC".to_string()))
+        );
+
+        let agent = registry
+            .key_mut(r"Software\Decal\Agent")
+            .expect("key exists");
+        agent.set_value("Flags", RegValue::Dword(0x1f));
+        agent.set_value("Enabled", RegValue::String("yes".to_string()));
+        registry
+            .create_key(r"Software\Alembic")
+            .set_value("Path", RegValue::String(r"C:\Alembic".to_string()));
+
+        let saved = registry.to_string();
+        assert!(saved.contains("#time=1da1b2c3d4e5f60\n"));
+        assert!(saved.contains(
+            "\"Flags\"=dword:0000001f\n@=\"Decal \\\"Agent\\\"\"\n\"Enabled\"=\"yes\"\n\n[Software\\\\Turbine\\\\AC] 1600000000\n"
+        ));
+        assert!(
+            saved.contains("\"Home\"=str(2):\"%USERPROFILE%\\\\AC\"\n\n[Software\\\\Alembic] ")
+        );
+        assert!(saved.ends_with("\"Path\"=\"C:\\\\Alembic\"\n"));
+
+        let reparsed = RegistryFile::parse(&saved).expect("saved registry should parse");
+        assert_eq!(
+            reparsed.value(r"Software\Alembic", "Path"),
+            Some(&RegValue::String(r"C:\Alembic".to_string()))
+        );
+        assert_eq!(
+            reparsed.value(r"Software\Decal\Agent", "Flags"),
+            Some(&RegValue::Dword(0x1f))
+        );
+    }
+}
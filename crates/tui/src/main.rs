@@ -1,15 +1,18 @@
 use std::{
     io,
     net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
     sync::Arc,
 };
 
 use app::App;
 
+use clap::Parser;
+
 use futures::{StreamExt, future};
 use libalembic::{
     msg::{client_server::ClientServerMessage, server_gui::ServerGuiMessage},
-    rpc::{HelloServer, World, spawn},
+    rpc::{HelloServer, RPC_PORT, World, spawn},
 };
 use tarpc::{
     server::{self, Channel},
@@ -20,7 +23,20 @@ use tokio::sync::{Mutex, mpsc::channel};
 pub mod app;
 pub mod tabs;
 
+#[derive(Parser)]
+#[command(name = "alembic-tui")]
+struct Args {
+    /// Use this settings file instead of the default (or ALEMBIC_CONFIG)
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+}
+
 fn main() -> io::Result<()> {
+    let args = Args::parse();
+    if let Some(config) = args.config {
+        libalembic::settings::set_settings_file_path(config).map_err(io::Error::other)?;
+    }
+
     // Channel: ClientServer
     let (client_server_tx, client_server_rx) = channel::<ClientServerMessage>(32);
     let client_server_tx_ref = Arc::new(Mutex::new(client_server_tx));
@@ -34,7 +50,7 @@ fn main() -> io::Result<()> {
     // tarpc
     let runtime = tokio::runtime::Runtime::new().unwrap();
     runtime.spawn(async move {
-        let addr = (IpAddr::V4(Ipv4Addr::LOCALHOST), 5000);
+        let addr = (IpAddr::V4(Ipv4Addr::LOCALHOST), RPC_PORT);
 
         let listener = tarpc::serde_transport::tcp::listen(&addr, Json::default)
             .await